@@ -1,20 +1,52 @@
 use comfy_table::{modifiers::UTF8_ROUND_CORNERS, presets::UTF8_FULL, ContentArrangement, Table};
-use eelf::{reader::ElfValue, ElfReader, Endianness, SegmentFlag, MACHINE_NAMES};
+use eelf::{
+    machine_name, reader::ElfValue, segment_flags_str, ElfReader, Endianness, RelocationKind,
+    SectionKind,
+};
 use listing::ListingFormatter;
-use num_traits::ToPrimitive;
 
 mod listing;
 
 fn main() {
-    let filename = std::env::args().nth(1).unwrap();
-    let f = std::fs::read(&filename).unwrap();
+    let mut filename = None;
+    let mut symbol_table = None;
+    let mut hex_section = None;
+    let mut relocations = false;
+
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--symbols" => symbol_table = Some(SectionKind::SymbolTable),
+            "--dyn-syms" => symbol_table = Some(SectionKind::DynSym),
+            "--relocs" => relocations = true,
+            "--hex" => hex_section = Some(args.next().expect("--hex requires a section name")),
+            _ => filename = Some(arg),
+        }
+    }
+
+    let f = std::fs::read(filename.expect("no file name given")).unwrap();
     let reader = ElfReader::new(&f).unwrap();
 
+    if let Some(name) = hex_section {
+        print_hex_dump(&reader, &name);
+        return;
+    }
+
     print_elf_header(&reader);
     println!();
     print_program_headers(&reader);
     println!();
     print_sections(&reader);
+
+    if let Some(kind) = symbol_table {
+        println!();
+        print_symbols(&reader, kind);
+    }
+
+    if relocations {
+        println!();
+        print_relocations(&reader);
+    }
 }
 
 fn print_elf_header(reader: &ElfReader<'_>) {
@@ -33,7 +65,7 @@ fn print_elf_header(reader: &ElfReader<'_>) {
     header_listing.add(
         "OS/ABI",
         match header.osabi() {
-            ElfValue::Known(osabi) => format!("{osabi:?}"),
+            ElfValue::Known(osabi) => osabi.to_string(),
             ElfValue::Unknown(value) => {
                 format!("unknown OS/ABI {value}")
             }
@@ -44,7 +76,7 @@ fn print_elf_header(reader: &ElfReader<'_>) {
     header_listing.add(
         "Type",
         match header.kind() {
-            ElfValue::Known(kind) => format!("{kind:?}"),
+            ElfValue::Known(kind) => kind.to_string(),
             ElfValue::Unknown(value) => {
                 format!("unknown type {value}")
             }
@@ -54,13 +86,9 @@ fn print_elf_header(reader: &ElfReader<'_>) {
     header_listing.add(
         "Machine",
         match header.machine() {
-            ElfValue::Known(machine) => MACHINE_NAMES
-                .get(&machine.to_u16().unwrap())
-                .unwrap()
-                .to_string(),
-            ElfValue::Unknown(value) => {
-                format!("unknown machine {value}")
-            }
+            ElfValue::Known(machine) => machine.name().to_string(),
+            ElfValue::Unknown(value) => machine_name(value)
+                .map_or_else(|| format!("unknown machine {value}"), str::to_string),
         },
     );
 
@@ -118,7 +146,7 @@ fn print_program_headers(reader: &ElfReader<'_>) {
     );
     for program_header in program_headers {
         match program_header.kind() {
-            ElfValue::Known(kind) => print!("  {: <18}", format!("{:?}", kind)),
+            ElfValue::Known(kind) => print!("  {kind: <18}"),
             ElfValue::Unknown(value) => print!("  0x{: <16x}", value),
         }
 
@@ -132,26 +160,8 @@ fn print_program_headers(reader: &ElfReader<'_>) {
         print!(" 0x{:016x}  ", program_header.memsz());
 
         match program_header.flags() {
-            ElfValue::Known(flags) => {
-                if flags.contains(SegmentFlag::Read) {
-                    print!("R");
-                } else {
-                    print!(" ");
-                }
-
-                if flags.contains(SegmentFlag::Write) {
-                    print!("W");
-                } else {
-                    print!(" ");
-                }
-
-                if flags.contains(SegmentFlag::Execute) {
-                    print!("E");
-                } else {
-                    print!(" ");
-                }
-            }
-            ElfValue::Unknown(_) => todo!(),
+            ElfValue::Known(flags) => print!("{}", segment_flags_str(flags)),
+            ElfValue::Unknown(value) => print!("0x{value:x}"),
         }
 
         print!("    ");
@@ -165,13 +175,44 @@ fn print_program_headers(reader: &ElfReader<'_>) {
 
         println!();
     }
+
+    println!();
+    print_section_to_segment_mapping(reader);
+}
+
+fn print_section_to_segment_mapping(reader: &ElfReader<'_>) {
+    // files without sections have no section name string table
+    let strings = reader.strings().ok();
+
+    println!("Section to segment mapping:");
+    println!("  Segment Sections...");
+
+    for (i, segment) in reader.segments().unwrap().into_iter().enumerate() {
+        let names = reader
+            .segment_sections(&segment)
+            .unwrap()
+            .into_iter()
+            .map(|section| {
+                strings
+                    .as_ref()
+                    .and_then(|strings| strings.get_str(section.name().into()))
+                    .and_then(Result::ok)
+                    .unwrap_or("<unknown>")
+            })
+            .collect::<Vec<_>>();
+
+        println!("   {i:02}     {}", names.join(" "));
+    }
 }
 
 fn print_sections(reader: &ElfReader<'_>) {
     println!("Sections:");
 
     let sections = reader.sections().unwrap();
-    let strings = reader.strings().unwrap();
+    let machine = match reader.header().unwrap().machine() {
+        ElfValue::Known(machine) => Some(machine),
+        ElfValue::Unknown(_) => None,
+    };
     let mut table = Table::new();
     table
         .load_preset(UTF8_FULL)
@@ -181,19 +222,17 @@ fn print_sections(reader: &ElfReader<'_>) {
             "Index", "Name", "Type", "Address", "Offset", "Size", "EntSize", "Flags", "Link",
             "Info", "Align",
         ]);
-    for (i, section) in sections.into_iter().enumerate() {
+    for (i, (section, name)) in sections.with_names().enumerate() {
         let mut row = Vec::new();
         row.push(i.to_string());
-        row.push(
-            strings
-                .get_str(section.name())
-                .unwrap()
-                .unwrap()
-                .to_string(),
-        );
-        row.push(match section.kind() {
-            ElfValue::Known(kind) => format!("{kind:?}"),
-            ElfValue::Unknown(value) => format!("0x{value:x}"),
+        row.push(name.unwrap().to_string());
+        row.push(match (section.kind(), machine) {
+            (ElfValue::Known(kind), _) => kind.to_string(),
+            (ElfValue::Unknown(value), Some(machine)) => match section.kind_for(machine) {
+                ElfValue::Known(kind) => kind.to_string(),
+                ElfValue::Unknown(_) => format!("0x{value:x}"),
+            },
+            (ElfValue::Unknown(value), None) => format!("0x{value:x}"),
         });
         row.push(format!("0x{:x}", section.addr()));
         row.push(format!("0x{:x}", section.offset()));
@@ -221,3 +260,202 @@ fn print_sections(reader: &ElfReader<'_>) {
 
     println!("{table}");
 }
+
+fn print_symbols(reader: &ElfReader<'_>, kind: SectionKind) {
+    let sections = reader.sections().unwrap();
+    let section_names = reader.strings().unwrap();
+
+    let Some(section) = sections.first_of_kind(kind) else {
+        println!("There is no {kind} section in this file.");
+        return;
+    };
+
+    let section_name = section_names
+        .get_str(section.name().into())
+        .unwrap()
+        .unwrap();
+    println!("Symbol table '{section_name}':");
+
+    let symbols = section.symbols().unwrap();
+    let strings = symbols.strings().unwrap();
+    let mut table = Table::new();
+    table
+        .load_preset(UTF8_FULL)
+        .apply_modifier(UTF8_ROUND_CORNERS)
+        .set_content_arrangement(ContentArrangement::Dynamic)
+        .set_header([
+            "Index",
+            "Value",
+            "Size",
+            "Type",
+            "Binding",
+            "Visibility",
+            "Section",
+            "Name",
+        ]);
+    for (i, symbol) in symbols.into_iter().enumerate() {
+        let mut row = Vec::new();
+        row.push(i.to_string());
+        row.push(format!("0x{:x}", symbol.value()));
+        row.push(symbol.size().to_string());
+        row.push(match symbol.kind() {
+            ElfValue::Known(kind) => kind.to_string(),
+            ElfValue::Unknown(value) => format!("{value}"),
+        });
+        row.push(match symbol.binding() {
+            ElfValue::Known(binding) => binding.to_string(),
+            ElfValue::Unknown(value) => format!("{value}"),
+        });
+        row.push(
+            match symbol.other() & 0x3 {
+                0 => "DEFAULT",
+                1 => "INTERNAL",
+                2 => "HIDDEN",
+                _ => "PROTECTED",
+            }
+            .to_string(),
+        );
+        row.push(match symbol.shndx() {
+            0 => "UND".to_string(),
+            0xfff1 => "ABS".to_string(),
+            0xfff2 => "COM".to_string(),
+            index => sections
+                .get(index.into())
+                .and_then(|section| section_names.get_str(section.name().into()))
+                .and_then(Result::ok)
+                .map_or_else(|| index.to_string(), str::to_string),
+        });
+        row.push(
+            strings
+                .get_str(symbol.name().into())
+                .and_then(Result::ok)
+                .unwrap_or("")
+                .to_string(),
+        );
+
+        table.add_row(row);
+    }
+
+    println!("{table}");
+}
+
+fn print_hex_dump(reader: &ElfReader<'_>, name: &str) {
+    let Some((section, _)) = reader
+        .sections()
+        .unwrap()
+        .with_names()
+        .find(|(_, section_name)| *section_name == Some(name))
+    else {
+        eprintln!("error: section '{name}' was not found");
+        std::process::exit(1);
+    };
+
+    println!("Hex dump of section '{name}':");
+
+    if section.kind() == ElfValue::Known(SectionKind::Nobits) {
+        println!("  section has no data");
+        return;
+    }
+
+    let data = section.data().unwrap();
+    for (i, row) in data.chunks(16).enumerate() {
+        print!(
+            "  0x{:08x} ",
+            section.addr() + u64::try_from(i * 16).unwrap()
+        );
+
+        for column in 0..16 {
+            match row.get(column) {
+                Some(byte) => print!(" {byte:02x}"),
+                None => print!("   "),
+            }
+        }
+
+        print!("  ");
+        for &byte in row {
+            if byte.is_ascii_graphic() || byte == b' ' {
+                print!("{}", char::from(byte));
+            } else {
+                print!(".");
+            }
+        }
+
+        println!();
+    }
+}
+
+fn print_relocations(reader: &ElfReader<'_>) {
+    let machine = reader.header().unwrap().machine();
+    let sections = reader.sections().unwrap();
+    let mut found = false;
+
+    for (section, section_name) in sections.with_names() {
+        let (ElfValue::Known(SectionKind::Rel) | ElfValue::Known(SectionKind::Rela)) =
+            section.kind()
+        else {
+            continue;
+        };
+
+        if found {
+            println!();
+        }
+        found = true;
+
+        println!("Relocation section '{}':", section_name.unwrap());
+
+        let relocations = section.relocations().unwrap();
+        // a link of 0 means the relocations don't refer to symbols
+        let symbols = match section.link() {
+            0 => None,
+            link => sections
+                .get(link.try_into().unwrap())
+                .map(|section| section.symbols().unwrap()),
+        };
+        let strings = symbols.as_ref().map(|symbols| symbols.strings().unwrap());
+
+        let mut table = Table::new();
+        table
+            .load_preset(UTF8_FULL)
+            .apply_modifier(UTF8_ROUND_CORNERS)
+            .set_content_arrangement(ContentArrangement::Dynamic)
+            .set_header(["Offset", "Symbol", "Symbol Name", "Type", "Addend"]);
+        for relocation in relocations {
+            let mut row = Vec::new();
+            row.push(format!("0x{:x}", relocation.offset()));
+            row.push(relocation.symbol().to_string());
+            row.push(
+                symbols
+                    .as_ref()
+                    .zip(strings.as_ref())
+                    .and_then(|(symbols, strings)| {
+                        let symbol = symbols.get(relocation.symbol().try_into().unwrap())?;
+                        strings.get_str(symbol.name().into())?.ok()
+                    })
+                    .unwrap_or("")
+                    .to_string(),
+            );
+            row.push(match machine {
+                ElfValue::Known(machine) => match relocation.reloc_type_for(machine) {
+                    ElfValue::Known(RelocationKind::X86_64(kind)) => format!("{kind:?}"),
+                    ElfValue::Known(RelocationKind::RiscV(kind)) => format!("{kind:?}"),
+                    ElfValue::Known(RelocationKind::AArch64(kind)) => format!("{kind:?}"),
+                    ElfValue::Known(_) | ElfValue::Unknown(_) => relocation.kind().to_string(),
+                },
+                ElfValue::Unknown(_) => relocation.kind().to_string(),
+            });
+            row.push(
+                relocation
+                    .addend()
+                    .map_or_else(String::new, |addend| format!("{addend}")),
+            );
+
+            table.add_row(row);
+        }
+
+        println!("{table}");
+    }
+
+    if !found {
+        println!("There are no relocations in this file.");
+    }
+}
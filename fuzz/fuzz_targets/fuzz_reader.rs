@@ -0,0 +1,141 @@
+#![no_main]
+
+use eelf::{
+    reader::{ElfValue, TypedSection},
+    ElfReader, MachineKind,
+};
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| read_everything(data));
+
+/// Reads every part of the file the reader can parse. Errors are expected, panics are bugs.
+///
+/// `Section::to_owned_data` is skipped, since it allocates as much memory as the file asks for.
+fn read_everything(data: &[u8]) {
+    let Ok(reader) = ElfReader::new(data) else {
+        return;
+    };
+
+    let machine = match reader.header() {
+        Ok(header) => {
+            let _ = header.snapshot();
+            match header.machine() {
+                ElfValue::Known(machine) => machine,
+                ElfValue::Unknown(_) => MachineKind::X86_64,
+            }
+        }
+        Err(_) => MachineKind::X86_64,
+    };
+
+    let _ = reader.shstrndx();
+    let _ = reader.phdr_bytes();
+    let _ = reader.shdr_bytes();
+    let _ = reader.image_end();
+    let _ = reader.interpreter();
+    let _ = reader.build_id();
+    let _ = reader.comment();
+    let _ = reader.abi_tag();
+    let _ = reader.is_core();
+    let _ = reader.is_stripped();
+    let _ = reader.executable_ranges();
+    let _ = reader.core_note_kinds();
+    let _ = reader.notes();
+    let _ = reader.load_image();
+
+    if let Ok(Some(header)) = reader.eh_frame_hdr() {
+        header.entries().for_each(drop);
+        let _ = header.lookup(header.eh_frame_ptr());
+    }
+
+    if let Ok(strings) = reader.strings() {
+        strings.iter().for_each(drop);
+    }
+
+    if let Ok(segments) = reader.segments() {
+        for segment in segments.clone() {
+            let _ = segment.snapshot();
+            let _ = segment.is_well_aligned();
+            let _ = segment.data();
+            let _ = reader.segment_sections(&segment);
+            let _ = reader.read_u64_at_vaddr(segment.vaddr());
+            let last = segment.vaddr().wrapping_add(segment.filesz()).wrapping_sub(4);
+            let _ = reader.read_u32_at_vaddr(last);
+            let _ = reader.vaddr_to_offset(segment.vaddr().wrapping_add(segment.memsz()));
+            if let Ok(notes) = segment.notes() {
+                let _ = notes.prstatus(machine);
+                notes.auxv().for_each(drop);
+                notes.into_iter().for_each(drop);
+            }
+            // a segment starting at offset 0 contains the file itself
+            if let Some(embedded) = segment.as_embedded_elf() {
+                if embedded.bytes().len() < data.len() {
+                    read_everything(embedded.bytes());
+                }
+            }
+        }
+
+        let _ = segments.covering(reader.header().map_or(0, |header| header.entry()));
+        let _ = segments.check();
+    }
+
+    let Ok(sections) = reader.sections() else {
+        return;
+    };
+
+    sections.with_names().for_each(drop);
+
+    for (index, section) in sections.clone().into_iter().enumerate() {
+        let _ = section.snapshot();
+        let _ = section.kind_for(machine);
+        let _ = section.known_flags();
+        let _ = reader.string_table(index);
+        let _ = reader.section_at_offset(section.offset());
+        let _ = section.compression();
+        let _ = section.decompressed_data();
+        let _ = section.group_members().map(|members| members.for_each(drop));
+
+        let Ok(typed) = section.typed() else {
+            continue;
+        };
+
+        match typed {
+            TypedSection::Symbols(symbols) => {
+                let _ = symbols.strings();
+                for (index, symbol) in symbols.clone().into_iter().enumerate() {
+                    let _ = symbol.section_index();
+                    let _ = symbols.version(index);
+                    let _ = symbols.symbol_at(symbol.value());
+                }
+            }
+            TypedSection::Relocations(relocations) => {
+                for relocation in relocations {
+                    let _ = relocation.addend();
+                    let _ = relocation.reloc_type_for(machine);
+                }
+            }
+            TypedSection::Relr(relr) => relr.into_iter().for_each(drop),
+            TypedSection::Strings(strings) => strings.iter().for_each(drop),
+            TypedSection::Notes(notes) => notes.into_iter().for_each(drop),
+            TypedSection::Dynamic(dynamic) => {
+                let _ = dynamic.needed_libraries();
+                dynamic.into_iter().for_each(drop);
+            }
+            TypedSection::Hash(hash) => {
+                let _ = hash.lookup("main");
+            }
+            TypedSection::GnuHash(hash) => {
+                let _ = hash.lookup("main");
+            }
+            TypedSection::Group(group) => group.into_iter().for_each(drop),
+            TypedSection::VersionDefinitions(definitions) => {
+                let _ = definitions.strings();
+                definitions.into_iter().for_each(drop);
+            }
+            TypedSection::VersionRequirements(requirements) => {
+                let _ = requirements.strings();
+                requirements.into_iter().for_each(drop);
+            }
+            TypedSection::Raw(_) => {}
+        }
+    }
+}
@@ -7,32 +7,48 @@
 //!
 //! See [tests/builder.rs](https://github.com/renshyle/eelf/blob/main/tests/builder.rs).
 
-use std::{borrow::Cow, io::Write, num::TryFromIntError};
+use std::{
+    borrow::Cow,
+    collections::BTreeMap,
+    io::{Read, Write},
+    num::TryFromIntError,
+};
 
 use num_traits::ToPrimitive;
+use thiserror::Error;
 
 use crate::{
     consts::{
-        SectionKind, SymbolKind, ELF64_HEADER_SIZE, ELF64_PROGRAM_HEADER_SIZE,
-        ELF64_SECTION_HEADER_SIZE, ELF_MAGIC,
+        SectionKind, SymbolBinding, SymbolKind, EI_NIDENT, EI_PAD, ELF64_HEADER_SIZE,
+        ELF64_PROGRAM_HEADER_SIZE, ELF64_SECTION_HEADER_SIZE, ELF_MAGIC, SHN_ABS, SHN_COMMON,
+        SHN_LORESERVE, SHN_XINDEX,
     },
     flagset::FlagSet,
-    Endianness, MachineKind, SegmentKind,
+    ElfReader, Endianness, MachineKind, OsAbi, ParseError, SegmentKind,
 };
 
 use super::{
-    consts::{ELF32_HEADER_SIZE, ELF32_PROGRAM_HEADER_SIZE, ELF32_SECTION_HEADER_SIZE},
-    ElfKind, SectionFlag, SegmentFlag,
+    consts::{
+        ELF32_HEADER_SIZE, ELF32_PROGRAM_HEADER_SIZE, ELF32_SECTION_HEADER_SIZE, NT_GNU_BUILD_ID,
+    },
+    CompressionKind, ElfKind, SectionFlag, SegmentFlag,
 };
 
+mod deflate;
 mod elf32;
 mod elf64;
+mod import;
+mod writer;
+
+use writer::Writer;
 
 // The built ELF file's section headers look as follows:
 // ----------------
 // |   section 1  |
 // |     ...      |
 // |   section n  |
+// |  dyn symtab  |
+// |  dyn strtab  |
 // | symbol table |
 // | relocation 1 |
 // |     ...      |
@@ -40,9 +56,12 @@ mod elf64;
 // | string table |
 // ----------------
 //
-// Sections 1..=n are the ones added with ElfBuilder::add_section. A symbol table is included if
+// Sections 1..=n are the ones added with ElfBuilder::add_section and ElfBuilder::add_section_at,
+// the latter filling any gaps with null sections. A symbol table is included if
 // ElfBuilder::should_build_symbol_table() == true, which happens if the symbol table's ID has been
-// requested using ElfBuilder::symbol_table or if a symbol has been added to the symbol table.
+// requested using ElfBuilder::symbol_table or if a symbol has been added to the symbol table. The
+// dynamic symbol table and its string table are included on the same conditions, using
+// ElfBuilder::dynamic_symbol_table and ElfBuilder::add_dynamic_symbol.
 
 /// A builder for ELF object files.
 #[derive(Debug, Clone)]
@@ -50,15 +69,38 @@ pub struct ElfBuilder<'data> {
     sections: Vec<Section<'data>>,
     strings: Vec<String>,
     symbols: Vec<Symbol>,
+    dynamic_strings: Vec<String>,
+    dynamic_symbols: Vec<Symbol>,
     relocations: Vec<RelocationTable>,
     segments: Vec<Segment>,
+    /// The flags and the virtual address of the [`SegmentKind::Phdr`] segment, if one is emitted
+    phdr_segment: Option<(FlagSet<SegmentFlag>, u64)>,
     entrypoint: u64,
+    flags: u32,
+    osabi: OsAbi,
+    abiversion: u8,
+    /// The padding at the end of the identification bytes, `ei_pad`
+    ident_padding: [u8; EI_NIDENT - EI_PAD],
+    version: u32,
+    ei_version: u8,
     kind: ElfKind,
     machine: MachineKind,
     endianness: Endianness,
     is_64bit: bool,
     /// Whether a symbol table, even an empty one, is required
     symbol_table_needed: bool,
+    /// Whether a dynamic symbol table, even an empty one, is required
+    dynamic_symbol_table_needed: bool,
+    /// Whether strings that are suffixes of other strings are merged into them when building
+    tail_merge_strings: bool,
+    /// Whether non-allocated sections, the symbol table, and the relocation tables are omitted
+    /// when building
+    strip: bool,
+    /// Where the section header table is placed in the built file
+    section_header_placement: SectionHeaderPlacement,
+    /// Whether combinations of the file type and its contents that are likely mistakes are built
+    /// anyway
+    allow_unusual: bool,
 }
 
 impl<'data> ElfBuilder<'data> {
@@ -70,317 +112,1433 @@ impl<'data> ElfBuilder<'data> {
         endianness: Endianness,
     ) -> Self {
         Self {
-            sections: vec![Section {
-                data: Cow::Borrowed(&[]),
-                name: StringId::empty(),
-                kind: SectionKind::Null,
-                flags: Default::default(),
-                info: 0,
-                vaddr: 0,
-                entsize: 0,
-                alignment: 0,
-            }],
+            sections: vec![Section::null()],
             strings: vec![String::new()],
-            symbols: vec![Symbol {
-                name: StringId::empty(),
-                value: 0,
-                size: 0,
-                global: false,
-                kind: SymbolKind::NoType,
-                section: SectionId {
-                    inner: SectionIdInner::Id(0),
-                },
-            }],
+            symbols: vec![Symbol::null()],
+            dynamic_strings: vec![String::new()],
+            dynamic_symbols: vec![Symbol::null()],
             relocations: Vec::new(),
             segments: Vec::new(),
+            phdr_segment: None,
             entrypoint: 0,
+            flags: 0,
+            osabi: OsAbi::None,
+            abiversion: 0,
+            ident_padding: [0; EI_NIDENT - EI_PAD],
+            version: 1,
+            ei_version: 1,
             kind,
             machine,
             endianness,
             is_64bit,
             symbol_table_needed: false,
+            dynamic_symbol_table_needed: false,
+            tail_merge_strings: false,
+            strip: false,
+            section_header_placement: SectionHeaderPlacement::End,
+            allow_unusual: false,
         }
     }
 
+    /// Creates an `ElfBuilder` containing the contents of an existing ELF file, so that it can be
+    /// modified and built again. Returns an error if the file could not be read, or if it contains
+    /// values unknown to this crate, such as an unknown section type.
+    ///
+    /// The header fields, sections, symbols, relocations, and segments are imported, but the
+    /// conversion is lossy:
+    /// * The section name string table and the string table of the symbol table are merged into
+    ///   `.strtab`, and the symbol tables and relocation tables are moved after the other sections
+    ///   as described in the module's layout. Section indices stored in section data, such as in
+    ///   section groups, are not updated.
+    /// * Relocation tables that don't belong to the symbol table, such as `.rela.dyn`, are imported
+    ///   as plain sections, keeping their `sh_link`.
+    /// * `st_other`, which contains the symbol visibility, is not imported.
+    /// * [`SegmentKind::Phdr`] segments, segments of unknown types, and segments that don't start
+    ///   at a section, such as a segment containing the ELF header, are dropped. Segments that end
+    ///   at the end of a section cover the same sections after building, but the sizes of other
+    ///   segments are imported as is.
+    pub fn from_reader(reader: &ElfReader<'data>) -> Result<Self, ParseError> {
+        import::from_reader(reader)
+    }
+
     /// Builds the ELF file, consuming the builder.
+    ///
+    /// The file is built in two passes: the sizes and offsets of all sections are computed first,
+    /// and the contents of the symbol tables, string tables, and relocation tables are then
+    /// written directly to `target` without being buffered.
     pub fn build<W: Write>(self, mut target: W) -> std::io::Result<()> {
-        let mut builder = self;
-        let endianness = builder.endianness;
-
-        let mut symbol_table = Vec::new();
+        let (builder, generated, symbol_indices) = self.prepare()?;
+        let layout = builder.compute_layout();
 
         if builder.is_64bit {
-            for symbol in &builder.symbols {
-                symbol_table
-                    .extend_from_slice(&endianness.u32_to_bytes(symbol.name.try_into().unwrap()));
-                let info = symbol.kind.to_u8().unwrap() | if symbol.global { 16 } else { 0 };
-                symbol_table.push(info);
-                symbol_table.push(0); // other, always 0
-                let section = match symbol.section {
-                    SectionId {
-                        inner: SectionIdInner::Id(id),
-                    } => id,
-                    _ => todo!(),
-                };
-                symbol_table.extend_from_slice(&endianness.u16_to_bytes(section));
+            elf64::write_header(&builder, &layout, &mut target)?;
+            elf64::write_phdrs(&builder, &layout, &mut target)?;
+        } else {
+            elf32::write_header(&builder, &layout, &mut target)?;
+            elf32::write_phdrs(&builder, &layout, &mut target)?;
+        }
 
-                symbol_table.extend_from_slice(&endianness.u64_to_bytes(symbol.value));
-                symbol_table.extend_from_slice(&endianness.u64_to_bytes(symbol.size));
+        match builder.section_header_placement {
+            SectionHeaderPlacement::End => {
+                builder.write_sections(&mut target, &layout, &generated, &symbol_indices)?;
+                builder.write_section_headers(&mut target, &layout)?;
             }
-        } else {
-            for symbol in &builder.symbols {
-                symbol_table
-                    .extend_from_slice(&endianness.u32_to_bytes(symbol.name.try_into().unwrap()));
-                symbol_table
-                    .extend_from_slice(&endianness.u32_to_bytes(symbol.value.try_into().unwrap()));
-                symbol_table
-                    .extend_from_slice(&endianness.u32_to_bytes(symbol.size.try_into().unwrap()));
-
-                let info = symbol.kind.to_u8().unwrap() | if symbol.global { 16 } else { 0 };
-                symbol_table.push(info);
-                symbol_table.push(0); // other, always 0
-
-                let section = match symbol.section {
-                    SectionId {
-                        inner: SectionIdInner::Id(id),
-                    } => id,
-                    _ => todo!(),
-                };
-                symbol_table.extend_from_slice(&endianness.u16_to_bytes(section));
+            SectionHeaderPlacement::AfterProgramHeaders => {
+                write_padding(
+                    &mut target,
+                    layout.section_headers - builder.program_headers_end(),
+                )?;
+                builder.write_section_headers(&mut target, &layout)?;
+                builder.write_sections(&mut target, &layout, &generated, &symbol_indices)?;
             }
         }
 
-        if builder.should_build_symbol_table() {
-            let name = builder.add_string(".symtab");
-            builder.add_section(Section {
-                name,
-                data: Cow::Borrowed(&symbol_table),
-                kind: SectionKind::SymbolTable,
-                flags: Default::default(),
-                vaddr: 0,
-                entsize: if builder.is_64bit { 24 } else { 16 },
-                alignment: 0,
-                info: builder.symbols.len().try_into().unwrap(),
-            });
+        Ok(())
+    }
+
+    /// Returns the file offsets at which [`ElfBuilder::build`] would place the program header
+    /// table, the sections, and the section header table, without building the file. The
+    /// sections include the ones added when building, such as the symbol table and the string
+    /// table, so the builder is cloned and they are added to the clone.
+    ///
+    /// # Errors
+    ///
+    /// Returns the errors [`ElfBuilder::build`] would return before computing the offsets, such
+    /// as for a relocatable file with program headers. Errors in the program headers, which are
+    /// only found when writing them, are not returned.
+    pub fn layout(&self) -> Result<Layout, BuildError> {
+        let (builder, _, _) = self.clone().prepare()?;
+
+        Ok(builder.compute_layout())
+    }
+
+    /// Strips and checks the file and adds the sections generated when building: the symbol
+    /// tables, the relocation tables, and the string table. Returns the builder, the indices and
+    /// the contents of the generated sections, and the indices of the symbols in the symbol table
+    /// as returned by [`symbol_order`].
+    fn prepare(self) -> Result<PreparedBuilder<'data>, BuildError> {
+        let mut builder = self;
+        let mut generated = Vec::new();
+
+        if builder.strip {
+            builder.strip_sections()?;
         }
 
-        let mut relocation_sections = Vec::new();
+        if !builder.allow_unusual {
+            builder.check_kind()?;
+        }
 
-        for table in &builder.relocations {
-            match table {
-                RelocationTable::Rela(table) => {
-                    let relocation_table = table.to_bytes(endianness, builder.is_64bit);
+        let (symbol_indices, first_global) = symbol_order(&builder.symbols);
+        let symbol_entry_size: u64 = if builder.is_64bit { 24 } else { 16 };
+
+        // the signature symbols of section groups are referred to by their IDs until the symbols
+        // have been sorted
+        for section in &mut builder.sections {
+            if section.kind == SectionKind::Group {
+                section.info = symbol_indices
+                    .get(usize::try_from(section.info).unwrap())
+                    .and_then(|&index| u32::try_from(index).ok())
+                    .ok_or(BuildError::InvalidValue("sh_info"))?;
+            }
+        }
 
-                    relocation_sections.push((
-                        table.target_section,
-                        table.name,
-                        SectionKind::Rela,
-                        if builder.is_64bit { 24 } else { 12 },
-                        Cow::Owned(relocation_table),
-                    ));
-                }
-                RelocationTable::Rel(table) => {
-                    let relocation_table = table.to_bytes(endianness, builder.is_64bit);
-
-                    relocation_sections.push((
-                        table.target_section,
-                        table.name,
-                        SectionKind::Rel,
-                        if builder.is_64bit { 16 } else { 8 },
-                        Cow::Owned(relocation_table),
-                    ));
-                }
+        if builder.should_build_dynamic_symbol_table() {
+            // add_dynamic_symbol keeps the dynamic symbols in order, so they aren't remapped
+            let (_, first_global) = symbol_order(&builder.dynamic_symbols);
+
+            let name = builder.add_string(".dynsym");
+            builder.add_generated_section(
+                Section {
+                    name,
+                    data: Cow::Borrowed(&[]),
+                    kind: SectionKind::DynSym,
+                    flags: SectionFlag::Alloc.into(),
+                    extra_flags: 0,
+                    vaddr: 0,
+                    entsize: symbol_entry_size,
+                    alignment: if builder.is_64bit { 8 } else { 4 },
+                    size: Some(
+                        symbol_entry_size * u64::try_from(builder.dynamic_symbols.len()).unwrap(),
+                    ),
+                    link: None,
+                    info: first_global.try_into().unwrap(),
+                },
+                GeneratedSection::DynamicSymbolTable,
+                &mut generated,
+            )?;
+
+            let name = builder.add_string(".dynstr");
+            builder.add_generated_section(
+                Section {
+                    name,
+                    data: Cow::Borrowed(&[]),
+                    kind: SectionKind::StringTable,
+                    flags: SectionFlag::Alloc.into(),
+                    extra_flags: 0,
+                    vaddr: 0,
+                    entsize: 0,
+                    alignment: 0,
+                    size: Some(string_table_size(&builder.dynamic_strings)),
+                    link: None,
+                    info: 0,
+                },
+                GeneratedSection::DynamicStringTable,
+                &mut generated,
+            )?;
+        }
+
+        if builder.should_build_symbol_table() {
+            let name = builder.add_string(".symtab");
+            builder.add_generated_section(
+                Section {
+                    name,
+                    data: Cow::Borrowed(&[]),
+                    kind: SectionKind::SymbolTable,
+                    flags: Default::default(),
+                    extra_flags: 0,
+                    vaddr: 0,
+                    entsize: symbol_entry_size,
+                    alignment: 0,
+                    size: Some(symbol_entry_size * u64::try_from(builder.symbols.len()).unwrap()),
+                    link: None,
+                    info: first_global.try_into().unwrap(),
+                },
+                GeneratedSection::SymbolTable,
+                &mut generated,
+            )?;
+
+            // +1 for this section, +1 for the string table, matching `has_symbol_section_indices`
+            if builder.sections.len() + builder.relocations.len() + 2 > usize::from(SHN_LORESERVE) {
+                let name = builder.add_string(".symtab_shndx");
+                let symbol_table = builder.symbol_table();
+                builder.add_generated_section(
+                    Section {
+                        name,
+                        data: Cow::Borrowed(&[]),
+                        kind: SectionKind::SymTabShndx,
+                        flags: Default::default(),
+                        extra_flags: 0,
+                        vaddr: 0,
+                        entsize: 4,
+                        alignment: 4,
+                        size: Some(4 * u64::try_from(builder.symbols.len()).unwrap()),
+                        link: Some(symbol_table),
+                        info: 0,
+                    },
+                    GeneratedSection::SymbolSectionIndices,
+                    &mut generated,
+                )?;
             }
         }
 
-        relocation_sections
-            .into_iter()
-            .for_each(|(section, name, kind, entsize, data)| {
-                builder.add_section(Section {
+        for index in 0..builder.relocations.len() {
+            let table = &builder.relocations[index];
+            table.check(builder.is_64bit, &symbol_indices)?;
+
+            let (name, section, kind, count) = match table {
+                RelocationTable::Rela(table) => (
+                    table.name,
+                    table.target_section,
+                    SectionKind::Rela,
+                    table.relocations.len(),
+                ),
+                RelocationTable::Rel(table) => (
+                    table.name,
+                    table.target_section,
+                    SectionKind::Rel,
+                    table.relocations.len(),
+                ),
+            };
+            let entsize = match (kind, builder.is_64bit) {
+                (SectionKind::Rela, true) => 24,
+                (SectionKind::Rela, false) => 12,
+                (_, true) => 16,
+                (_, false) => 8,
+            };
+
+            builder.add_generated_section(
+                Section {
                     name,
-                    data,
+                    data: Cow::Borrowed(&[]),
                     kind,
                     flags: Default::default(),
+                    extra_flags: 0,
                     vaddr: 0,
                     entsize,
                     alignment: 0,
+                    size: Some(entsize * u64::try_from(count).unwrap()),
+                    link: None,
                     info: match section {
                         SectionId {
                             inner: SectionIdInner::Id(id),
-                        } => id.into(),
-                        _ => todo!(),
+                        } => id,
+                        // relocations can't apply to the generated tables or reserved indices
+                        _ => return Err(BuildError::InvalidValue("sh_info")),
                     },
-                });
-            });
+                },
+                GeneratedSection::Relocations(index),
+                &mut generated,
+            )?;
+        }
+
+        // need to add the string before computing the size of the string table
+        let mut strtab_string = builder.add_string(".strtab");
 
-        // need to add the string before building the string table bytes
-        let strtab_string = builder.add_string(".strtab");
+        if builder.tail_merge_strings {
+            let (strings, offsets) = tail_merge(&builder.strings);
+            // a name that isn't the start of a string, such as one from the dynamic string table,
+            // can't be remapped
+            let remap = |id: &mut StringId, field| {
+                id.offset = *offsets
+                    .get(&id.offset)
+                    .ok_or(BuildError::InvalidValue(field))?;
 
-        let mut string_table = Vec::new();
+                Ok::<_, BuildError>(())
+            };
 
-        for string in &builder.strings {
-            string_table.extend_from_slice(string.as_bytes());
-            string_table.push(0);
+            for section in &mut builder.sections {
+                remap(&mut section.name, "sh_name")?;
+            }
+            for symbol in &mut builder.symbols {
+                remap(&mut symbol.name, "st_name")?;
+            }
+            remap(&mut strtab_string, "sh_name")?;
+            builder.strings = strings;
         }
 
-        builder.add_section(Section {
-            name: strtab_string,
-            data: Cow::Borrowed(&string_table),
-            kind: SectionKind::StringTable,
-            flags: Default::default(),
-            vaddr: 0,
-            info: 0,
-            entsize: 0,
-            alignment: 0,
-        });
+        builder.add_generated_section(
+            Section {
+                name: strtab_string,
+                data: Cow::Borrowed(&[]),
+                kind: SectionKind::StringTable,
+                flags: Default::default(),
+                extra_flags: 0,
+                vaddr: 0,
+                info: 0,
+                entsize: 0,
+                alignment: 0,
+                size: Some(string_table_size(&builder.strings)),
+                link: None,
+            },
+            GeneratedSection::StringTable,
+            &mut generated,
+        )?;
+
+        Ok((builder, generated, symbol_indices))
+    }
 
-        if builder.is_64bit {
-            elf64::write_header(&builder, &mut target)?;
-            elf64::write_phdrs(&builder, &mut target)?;
-            builder.write_sections(&mut target)?;
-            elf64::write_section_headers(&builder, &mut target)?;
+    fn write_section_headers<W: Write>(&self, target: W, layout: &Layout) -> std::io::Result<()> {
+        if self.is_64bit {
+            elf64::write_section_headers(self, layout, target)
         } else {
-            elf32::write_header(&builder, &mut target)?;
-            elf32::write_phdrs(&builder, &mut target)?;
-            builder.write_sections(&mut target)?;
-            elf32::write_section_headers(&builder, &mut target)?;
+            elf32::write_section_headers(self, layout, target)
         }
+    }
+
+    /// Builds the ELF file into a [`Vec`], consuming the builder. See [`ElfBuilder::build`].
+    pub fn build_to_vec(self) -> std::io::Result<Vec<u8>> {
+        let mut bytes = Vec::new();
+        self.build(&mut bytes)?;
+
+        Ok(bytes)
+    }
+
+    /// Adds a section whose contents are written by [`ElfBuilder::write_sections`] instead of
+    /// being stored in `data`. `size` must be the size of the contents.
+    fn add_generated_section(
+        &mut self,
+        section: Section<'data>,
+        contents: GeneratedSection,
+        generated: &mut Vec<(usize, GeneratedSection)>,
+    ) -> Result<(), BuildError> {
+        self.check_word(section.size(), "sh_size")?;
+
+        let index = self.sections.len();
+        if u32::try_from(index).is_err() {
+            return Err(BuildError::ValueTooLarge("e_shnum"));
+        }
+
+        self.sections.push(section);
+        generated.push((index, contents));
 
         Ok(())
     }
 
-    fn write_sections<W: Write>(&mut self, mut target: W) -> std::io::Result<()> {
-        for section in &self.sections {
-            target.write_all(&section.data)?;
+    /// Writes the contents of all sections. `generated` contains the indices and the contents of
+    /// the sections added with [`ElfBuilder::add_generated_section`].
+    fn write_sections<W: Write>(
+        &self,
+        mut target: W,
+        layout: &Layout,
+        generated: &[(usize, GeneratedSection)],
+        symbol_indices: &[u64],
+    ) -> std::io::Result<()> {
+        let mut position = self.data_offset();
+        let mut generated = generated.iter().peekable();
+
+        for (index, (section, &offset)) in self.sections.iter().zip(&layout.sections).enumerate() {
+            write_padding(&mut target, offset - position)?;
+
+            match generated.next_if(|(generated_index, _)| *generated_index == index) {
+                Some((_, contents)) => {
+                    self.write_generated(&mut target, *contents, symbol_indices)?
+                }
+                None if section.kind != SectionKind::Nobits => target.write_all(&section.data)?,
+                None => {}
+            }
+
+            position = offset + section.file_size();
+        }
+
+        match self.section_header_placement {
+            SectionHeaderPlacement::End => {
+                write_padding(&mut target, layout.section_headers - position)
+            }
+            SectionHeaderPlacement::AfterProgramHeaders => Ok(()),
+        }
+    }
+
+    fn write_generated<W: Write>(
+        &self,
+        mut target: W,
+        contents: GeneratedSection,
+        symbol_indices: &[u64],
+    ) -> std::io::Result<()> {
+        let mut writer = self.writer(&mut target);
+
+        match contents {
+            GeneratedSection::SymbolTable => {
+                write_symbol_table(&mut writer, &self.symbols, |section| {
+                    self.symbol_shndx(section, self.has_symbol_section_indices())
+                })
+            }
+            GeneratedSection::SymbolSectionIndices => {
+                write_symbol_section_indices(&mut writer, &self.symbols, |section| {
+                    match self.symbol_shndx(section, true) {
+                        Ok(SHN_XINDEX) => self.section_index(section),
+                        _ => 0,
+                    }
+                })
+            }
+            // the dynamic symbol table has no section indices section
+            GeneratedSection::DynamicSymbolTable => {
+                write_symbol_table(&mut writer, &self.dynamic_symbols, |section| {
+                    self.symbol_shndx(section, false)
+                })
+            }
+            GeneratedSection::StringTable => write_string_table(&mut target, &self.strings),
+            GeneratedSection::DynamicStringTable => {
+                write_string_table(&mut target, &self.dynamic_strings)
+            }
+            GeneratedSection::Relocations(index) => match &self.relocations[index] {
+                RelocationTable::Rela(table) => table.write(&mut writer, symbol_indices),
+                RelocationTable::Rel(table) => table.write(&mut writer, symbol_indices),
+            },
+        }
+    }
+
+    /// Removes the sections omitted by [`ElfBuilder::set_strip`], along with the symbols, the
+    /// relocation tables, and the strings only used by them. The remaining section IDs are
+    /// updated to the new indices.
+    fn strip_sections(&mut self) -> Result<(), BuildError> {
+        let mut keep = self
+            .sections
+            .iter()
+            .enumerate()
+            .map(|(index, section)| index == 0 || section.flags.contains(SectionFlag::Alloc))
+            .collect::<Vec<_>>();
+
+        // sections covered by segments are kept even if they aren't allocated
+        for segment in &self.segments {
+            let SectionIdInner::Id(first) = segment.section.inner else {
+                return Err(BuildError::InvalidValue("p_offset"));
+            };
+            let last = match segment.last_section.map(|section| section.inner) {
+                Some(SectionIdInner::Id(last)) => last,
+                Some(_) => return Err(BuildError::InvalidValue("p_filesz")),
+                None => first,
+            };
+
+            for index in usize::try_from(first).unwrap()..=usize::try_from(last).unwrap() {
+                if let Some(keep) = keep.get_mut(index) {
+                    *keep = true;
+                }
+            }
+        }
+
+        // so are the sections linked to by kept sections, which may link to further sections
+        let mut changed = true;
+        while changed {
+            changed = false;
+
+            for index in 0..self.sections.len() {
+                if !keep[index] {
+                    continue;
+                }
+
+                let section = &self.sections[index];
+                match section.link.map(|link| link.inner) {
+                    Some(SectionIdInner::Id(link)) => {
+                        if let Some(keep @ false) = keep.get_mut(usize::try_from(link).unwrap()) {
+                            *keep = true;
+                            changed = true;
+                        }
+                    }
+                    Some(SectionIdInner::SymbolTable) => {
+                        return Err(BuildError::InvalidValue("sh_link"))
+                    }
+                    // relocation tables without a link are linked to the symbol table
+                    None if matches!(section.kind, SectionKind::Rel | SectionKind::Rela) => {
+                        return Err(BuildError::InvalidValue("sh_link"))
+                    }
+                    _ => {}
+                }
+            }
+        }
+
+        let mut new_indices = Vec::with_capacity(keep.len());
+        let mut next_index = 0u32;
+        for &keep in &keep {
+            new_indices.push(keep.then_some(next_index));
+            next_index += u32::from(keep);
+        }
+
+        // removed sections are handled like in `import::section_id`
+        let remap = |id: &mut SectionId| {
+            if let SectionIdInner::Id(index) = id.inner {
+                if let Some(new_index) = new_indices.get(usize::try_from(index).unwrap()) {
+                    id.inner = SectionIdInner::Id(new_index.unwrap_or(0));
+                }
+            }
+        };
+
+        let mut index = 0;
+        self.sections.retain(|_| {
+            index += 1;
+            keep[index - 1]
+        });
+        for section in &mut self.sections {
+            if let Some(link) = &mut section.link {
+                remap(link);
+            }
+        }
+        for segment in &mut self.segments {
+            remap(&mut segment.section);
+            if let Some(last_section) = &mut segment.last_section {
+                remap(last_section);
+            }
+        }
+        for symbol in &mut self.dynamic_symbols {
+            remap(&mut symbol.section);
+        }
+
+        self.symbols = vec![Symbol::null()];
+        self.symbol_table_needed = false;
+        self.relocations.clear();
+
+        // only the names of the remaining sections are kept in the string table
+        let mut strings = BTreeMap::new();
+        let mut offset = 0;
+        for string in std::mem::replace(&mut self.strings, vec![String::new()]) {
+            let length = u64::try_from(string.len()).unwrap() + 1; // 1 for the null byte
+            strings.insert(offset, string);
+            offset += length;
+        }
+        for section in &mut self.sections {
+            // a name that isn't the start of a string, such as one from the dynamic string table,
+            // can't be kept
+            let name = strings
+                .get(&section.name.offset)
+                .ok_or(BuildError::InvalidValue("sh_name"))?;
+            section.name = add_string_to(&mut self.strings, name.clone());
         }
 
         Ok(())
     }
 
+    /// Returns a [`Writer`] writing to `target` in the endianness and class of the built file.
+    fn writer<W: Write>(&self, target: W) -> Writer<W> {
+        Writer::new(target, self.endianness, self.is_64bit)
+    }
+
+    /// Returns the number of entries in the program header table, including the
+    /// [`SegmentKind::Phdr`] segment.
+    fn program_header_count(&self) -> usize {
+        self.segments.len() + usize::from(self.phdr_segment.is_some())
+    }
+
+    /// Returns the file offset right after the ELF header and the program headers.
+    fn program_headers_end(&self) -> u64 {
+        let (header_size, program_header_size) = if self.is_64bit {
+            (ELF64_HEADER_SIZE, ELF64_PROGRAM_HEADER_SIZE)
+        } else {
+            (ELF32_HEADER_SIZE, ELF32_PROGRAM_HEADER_SIZE)
+        };
+
+        u64::from(header_size)
+            + u64::from(program_header_size) * u64::try_from(self.program_header_count()).unwrap()
+    }
+
+    /// Returns the offset of the section header table when it is placed right after the program
+    /// headers, aligned to the size of an address.
+    fn early_section_headers_offset(&self) -> u64 {
+        self.program_headers_end()
+            .next_multiple_of(if self.is_64bit { 8 } else { 4 })
+    }
+
+    /// Returns the file offset at which section data starts, right after the program headers, or
+    /// after the section header table if it is placed before the section data.
+    fn data_offset(&self) -> u64 {
+        match self.section_header_placement {
+            SectionHeaderPlacement::End => self.program_headers_end(),
+            SectionHeaderPlacement::AfterProgramHeaders => {
+                let section_header_size = if self.is_64bit {
+                    ELF64_SECTION_HEADER_SIZE
+                } else {
+                    ELF32_SECTION_HEADER_SIZE
+                };
+
+                self.early_section_headers_offset()
+                    + u64::from(section_header_size) * u64::try_from(self.sections.len()).unwrap()
+            }
+        }
+    }
+
+    /// Computes the [`Layout`] of the file. Zero padding is inserted before each section so that
+    /// its offset is a multiple of its alignment, and before the section header table so that it
+    /// is aligned to the size of an address. If a [`SegmentKind::Load`] segment with a
+    /// power-of-two alignment starts at a section, more padding is inserted so that the offset is
+    /// congruent to the segment's `vaddr` modulo its `align`. May only be used after all sections
+    /// have been added by [`ElfBuilder::prepare`].
+    fn compute_layout(&self) -> Layout {
+        let load_segments = self
+            .segments
+            .iter()
+            .filter(|segment| segment.kind == SegmentKind::Load && segment.align.is_power_of_two())
+            .map(|segment| {
+                (
+                    usize::try_from(self.section_index(segment.section)).unwrap(),
+                    segment,
+                )
+            })
+            .collect::<Vec<_>>();
+
+        let mut offset = self.data_offset();
+        let offsets = self
+            .sections
+            .iter()
+            .enumerate()
+            .map(|(index, section)| {
+                if section.alignment > 1 {
+                    offset = offset.next_multiple_of(section.alignment);
+                }
+
+                if let Some((_, segment)) = load_segments.iter().find(|(i, _)| *i == index) {
+                    offset += segment.vaddr.wrapping_sub(offset) & (segment.align - 1);
+                }
+
+                let section_offset = offset;
+                offset += section.file_size();
+
+                section_offset
+            })
+            .collect();
+
+        let (section_headers, size) = match self.section_header_placement {
+            SectionHeaderPlacement::End => {
+                let section_headers = offset.next_multiple_of(if self.is_64bit { 8 } else { 4 });
+                let section_header_size = if self.is_64bit {
+                    ELF64_SECTION_HEADER_SIZE
+                } else {
+                    ELF32_SECTION_HEADER_SIZE
+                };
+
+                (
+                    section_headers,
+                    section_headers
+                        + u64::from(section_header_size)
+                            * u64::try_from(self.sections.len()).unwrap(),
+                )
+            }
+            SectionHeaderPlacement::AfterProgramHeaders => {
+                (self.early_section_headers_offset(), offset)
+            }
+        };
+
+        // program headers right after the header if there are segments, 0 otherwise
+        let program_headers = match (self.program_header_count(), self.is_64bit) {
+            (0, _) => 0,
+            (_, true) => ELF64_HEADER_SIZE.into(),
+            (_, false) => ELF32_HEADER_SIZE.into(),
+        };
+
+        Layout {
+            program_headers,
+            sections: offsets,
+            section_headers,
+            size,
+        }
+    }
+
     fn should_build_symbol_table(&self) -> bool {
         self.symbol_table_needed || self.symbols.len() > 1
     }
 
+    fn should_build_dynamic_symbol_table(&self) -> bool {
+        self.dynamic_symbol_table_needed || self.dynamic_symbols.len() > 1
+    }
+
+    /// Returns the index of the dynamic string table in the section headers. May only be used
+    /// after all sections, including the symbol tables, relocations, and the string table have
+    /// been built.
+    fn dynamic_string_table_index(&self) -> u32 {
+        // -1 for the string table, another -1 for the dynamic string table, and more for the
+        // symbol table and its section indices if they exist
+        (self.sections.len()
+            - self.relocations.len()
+            - 2
+            - usize::from(self.should_build_symbol_table())
+            - usize::from(self.has_symbol_section_indices()))
+        .try_into()
+        .unwrap()
+    }
+
+    /// Returns the index of the dynamic symbol table in the section headers. May only be used
+    /// after all sections, including the symbol tables, relocations, and the string table have
+    /// been built.
+    fn dynamic_symbol_table_index(&self) -> u32 {
+        self.dynamic_string_table_index() - 1
+    }
+
     /// Returns the index of the symbol table in the section headers. May only be used after all
     /// sections, including the symbol table, relocations, and the string table have been built.
-    fn symbol_table_index(&self) -> u16 {
-        // -1 for the string table, another -1 for the symbol table
-        (self.sections.len() - self.relocations.len() - 2)
-            .try_into()
-            .unwrap()
+    fn symbol_table_index(&self) -> u32 {
+        // -1 for the string table, another -1 for the symbol table, and a third -1 for its section
+        // indices if they exist
+        (self.sections.len()
+            - self.relocations.len()
+            - 2
+            - usize::from(self.has_symbol_section_indices()))
+        .try_into()
+        .unwrap()
+    }
+
+    /// Returns whether the `.symtab_shndx` section, containing the section indices of the symbols
+    /// that don't fit in `st_shndx`, has been built. It is built if the symbol table is and the
+    /// last section index is reserved. May only be used after all sections, including the symbol
+    /// table, relocations, and the string table have been built.
+    fn has_symbol_section_indices(&self) -> bool {
+        self.should_build_symbol_table() && self.sections.len() > usize::from(SHN_LORESERVE)
     }
 
     /// Returns the index of the string table in the section headers. May only be used after all
     /// sections, including the symbol table, relocations, and the string table have been built.
-    fn string_table_index(&self) -> u16 {
+    fn string_table_index(&self) -> u32 {
         (self.sections.len() - 1).try_into().unwrap()
     }
 
+    /// Returns the `e_shnum` value, which is 0 if the number of sections doesn't fit and is stored
+    /// in `sh_size` of the null section instead.
+    fn shnum(&self) -> u16 {
+        u16::try_from(self.sections.len())
+            .ok()
+            .filter(|&count| count < SHN_LORESERVE)
+            .unwrap_or(0)
+    }
+
+    /// Returns the `e_shstrndx` value, which is `SHN_XINDEX` if the index of the string table
+    /// doesn't fit and is stored in `sh_link` of the null section instead. May only be used after
+    /// all sections, including the symbol table, relocations, and the string table have been
+    /// built.
+    fn shstrndx(&self) -> u16 {
+        u16::try_from(self.string_table_index())
+            .ok()
+            .filter(|&index| index < SHN_LORESERVE)
+            .unwrap_or(SHN_XINDEX)
+    }
+
+    /// Returns the `sh_size` and `sh_link` values of the section at an index, including the
+    /// values of the null section replacing `e_shnum` and `e_shstrndx`. May only be used after
+    /// all sections, including the symbol table, relocations, and the string table have been
+    /// built.
+    fn section_size_and_link(&self, index: usize, section: &Section) -> (u64, u32) {
+        if index != 0 {
+            return (section.size(), self.section_link(section));
+        }
+
+        (
+            match self.shnum() {
+                0 => u64::try_from(self.sections.len()).unwrap(),
+                _ => 0,
+            },
+            match self.shstrndx() {
+                SHN_XINDEX => self.string_table_index(),
+                _ => 0,
+            },
+        )
+    }
+
+    /// Returns the `st_shndx` value of a symbol defined in a section. Indices of sections that
+    /// don't fit in `st_shndx` are replaced with `SHN_XINDEX` if `extended` is true, and the real
+    /// index is stored in the `.symtab_shndx` section; otherwise an error is returned. May only be
+    /// used after all sections, including the symbol table, relocations, and the string table have
+    /// been built.
+    fn symbol_shndx(&self, section: SectionId, extended: bool) -> Result<u16, BuildError> {
+        if let SectionIdInner::Reserved(index) = section.inner {
+            return Ok(index);
+        }
+
+        match u16::try_from(self.section_index(section)) {
+            Ok(index) if index < SHN_LORESERVE => Ok(index),
+            _ if extended => Ok(SHN_XINDEX),
+            _ => Err(BuildError::ValueTooLarge("st_shndx")),
+        }
+    }
+
     /// Returns the index of a section in the section headers. May only be used after all sections,
     /// including the symbol table, relocations, and the string table have been built.
-    fn section_index(&self, section_id: SectionId) -> u16 {
+    fn section_index(&self, section_id: SectionId) -> u32 {
         let SectionId { inner: section_id } = section_id;
 
         match section_id {
             SectionIdInner::SymbolTable => self.symbol_table_index(),
             SectionIdInner::StringTable => self.string_table_index(),
+            SectionIdInner::DynamicSymbolTable => self.dynamic_symbol_table_index(),
+            SectionIdInner::DynamicStringTable => self.dynamic_string_table_index(),
             SectionIdInner::Id(id) => id,
+            SectionIdInner::Reserved(index) => index.into(),
+        }
+    }
+
+    /// Returns the index of the section a section is linked to. May only be used after all
+    /// sections, including the symbol table, relocations, and the string table have been built.
+    fn section_link(&self, section: &Section) -> u32 {
+        if let Some(link) = section.link {
+            return self.section_index(link);
+        }
+
+        match section.kind {
+            SectionKind::SymbolTable => self.section_index(self.find_section(".strtab").unwrap()),
+            SectionKind::DynSym => self.section_index(self.find_section(".dynstr").unwrap()),
+            SectionKind::Rela | SectionKind::Rel => {
+                self.section_index(self.find_section(".symtab").unwrap())
+            }
+            _ => 0,
+        }
+    }
+
+    /// Returns the file offset and the size in the file of a segment. May only be used after all
+    /// sections, including the symbol table, relocations, and the string table have been built.
+    fn segment_range(&self, segment: &Segment, offsets: &[u64]) -> Result<(u64, u64), BuildError> {
+        let offset = offsets[usize::try_from(self.section_index(segment.section)).unwrap()];
+
+        // only possible if several loadable segments with different alignments start at the same
+        // section
+        if segment.kind == SegmentKind::Load
+            && segment.align.is_power_of_two()
+            && (segment.vaddr.wrapping_sub(offset) & (segment.align - 1)) != 0
+        {
+            return Err(BuildError::InvalidValue("p_align"));
+        }
+
+        let Some(last_section) = segment.last_section else {
+            return Ok((offset, segment.filesz));
+        };
+
+        let last_index = usize::try_from(self.section_index(last_section)).unwrap();
+        let filesz = (offsets[last_index] + self.sections[last_index].file_size())
+            .checked_sub(offset)
+            .ok_or(BuildError::InvalidValue("p_filesz"))?;
+
+        if segment.memsz < filesz {
+            return Err(BuildError::InvalidValue("p_memsz"));
         }
+
+        Ok((offset, filesz))
+    }
+
+    /// Returns an error if the ELF file is 32-bit and the value of the field does not fit in 32
+    /// bits.
+    fn check_word(&self, value: u64, field: &'static str) -> Result<(), BuildError> {
+        if !self.is_64bit && value > u32::MAX.into() {
+            return Err(BuildError::ValueTooLarge(field));
+        }
+
+        Ok(())
+    }
+
+    /// Adds a section to the section table and the data to the ELF file. Returns the index at which
+    /// the section was added.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if
+    /// * the flags, virtual address, entry size, alignment, or size are greater than [`u32::MAX`]
+    ///   and the ELF file is 32-bit,
+    /// * a size is given for a section that isn't [`SectionKind::Nobits`], or
+    /// * the section table is full.
+    pub fn add_section(&mut self, section: Section<'data>) -> Result<SectionId, BuildError> {
+        self.check_section(&section)?;
+
+        let index =
+            u32::try_from(self.sections.len()).map_err(|_| BuildError::ValueTooLarge("e_shnum"))?;
+
+        self.sections.push(section);
+
+        Ok(SectionId {
+            inner: SectionIdInner::Id(index),
+        })
+    }
+
+    /// Adds a section like [`ElfBuilder::add_section`], with its data read from `reader` until
+    /// the end instead of taken from `section.data`, which is ignored. The data is read into an
+    /// owned buffer right away, since the builder needs the size of every section to lay out the
+    /// file, so the whole section is held in memory until the file is built.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if reading fails, or a [`BuildError`] wrapped in an I/O error if the
+    /// section is invalid, as described in [`ElfBuilder::add_section`].
+    pub fn add_section_from_reader<R: Read>(
+        &mut self,
+        section: Section<'data>,
+        mut reader: R,
+    ) -> std::io::Result<SectionId> {
+        let mut data = Vec::new();
+        reader.read_to_end(&mut data)?;
+
+        Ok(self.add_section(Section {
+            data: Cow::Owned(data),
+            ..section
+        })?)
+    }
+
+    /// Adds a section at a specific index in the section table, so that indices referring to it,
+    /// such as in `sh_link` or `st_shndx` values copied from another file, stay valid. Any gap
+    /// between the last section and the index is filled with [`SectionKind::Null`] sections, which
+    /// can be replaced by later calls. Returns the index at which the section was added.
+    ///
+    /// The symbol tables, string tables, and relocation tables generated by the builder are always
+    /// placed after the added sections, so they don't collide with the indices used here. Indices
+    /// aren't kept when the builder strips the file with [`ElfBuilder::set_strip`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if
+    /// * the index is 0 or the section at the index isn't a [`SectionKind::Null`] section, in which
+    ///   case the error is for `e_shnum`,
+    /// * the index is at least `SHN_LORESERVE` (`0xff00`) and greater than the number of
+    ///   sections, so that the gap would be filled with an unreasonable number of null sections,
+    ///   in which case the error is for `e_shnum`, or
+    /// * the section is invalid, as described in [`ElfBuilder::add_section`].
+    pub fn add_section_at(
+        &mut self,
+        index: u32,
+        section: Section<'data>,
+    ) -> Result<SectionId, BuildError> {
+        let position = usize::try_from(index).unwrap();
+        if position >= usize::from(SHN_LORESERVE) && position > self.sections.len() {
+            return Err(BuildError::ValueTooLarge("e_shnum"));
+        }
+
+        match self.sections.get(position) {
+            Some(existing) if position == 0 || existing.kind != SectionKind::Null => {
+                return Err(BuildError::InvalidValue("e_shnum"));
+            }
+            _ => {}
+        }
+
+        self.check_section(&section)?;
+
+        if position >= self.sections.len() {
+            self.sections.resize(position + 1, Section::null());
+        }
+        self.sections[position] = section;
+
+        Ok(SectionId {
+            inner: SectionIdInner::Id(index),
+        })
+    }
+
+    /// Checks that the contents of the file make sense for its type, as described in
+    /// [`ElfBuilder::allow_unusual`].
+    fn check_kind(&self) -> Result<(), BuildError> {
+        match self.kind {
+            ElfKind::Relocatable if self.program_header_count() != 0 => {
+                Err(BuildError::InvalidValue("e_phnum"))
+            }
+            ElfKind::Relocatable if self.entrypoint != 0 => {
+                Err(BuildError::InvalidValue("e_entry"))
+            }
+            ElfKind::Executable | ElfKind::Dynamic
+                if !self
+                    .segments
+                    .iter()
+                    .any(|segment| segment.kind == SegmentKind::Load) =>
+            {
+                Err(BuildError::InvalidValue("e_phnum"))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Checks that the values of a section fit in the ELF file.
+    fn check_section(&self, section: &Section) -> Result<(), BuildError> {
+        if section.size.is_some() && section.kind != SectionKind::Nobits {
+            return Err(BuildError::InvalidValue("sh_size"));
+        }
+
+        self.check_word(section.size(), "sh_size")?;
+        self.check_word(section.raw_flags(), "sh_flags")?;
+        self.check_word(section.vaddr, "sh_addr")?;
+        self.check_word(section.entsize, "sh_entsize")?;
+        self.check_word(section.alignment, "sh_addralign")
+    }
+
+    /// Adds a section whose data is compressed with zlib, like with
+    /// `objcopy --compress-debug-sections`. The data is compressed and prefixed with a compression
+    /// header containing its size and the alignment of the section, and the
+    /// [`SectionFlag::Compressed`] flag is set. The alignment of the section is changed to the
+    /// alignment of the compression header, the size of an address. The data can be read back with
+    /// [`Section::decompressed_data`](crate::reader::Section::decompressed_data). Returns the index
+    /// at which the section was added.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if
+    /// * the section is [`SectionKind::Nobits`] or has the [`SectionFlag::Alloc`] flag, which can't
+    ///   be used with compression,
+    /// * the size of the data is greater than [`u32::MAX`] and the ELF file is 32-bit, or
+    /// * the section table is full.
+    pub fn add_compressed_section(
+        &mut self,
+        mut section: Section<'data>,
+    ) -> Result<SectionId, BuildError> {
+        if section.kind == SectionKind::Nobits {
+            return Err(BuildError::InvalidValue("sh_type"));
+        }
+
+        if section.flags.contains(SectionFlag::Alloc) {
+            return Err(BuildError::InvalidValue("sh_flags"));
+        }
+
+        let size = u64::try_from(section.data.len()).unwrap();
+        self.check_word(size, "ch_size")?;
+        self.check_word(section.alignment, "ch_addralign")?;
+
+        let mut data = Vec::new();
+        let mut writer = self.writer(&mut data);
+        // writing to a Vec can't fail, and the values were checked to fit
+        writer
+            .write_u32(CompressionKind::Zlib.to_u32().unwrap())
+            .unwrap();
+        if writer.is_64bit() {
+            // ch_reserved
+            writer.write_u32(0).unwrap();
+        }
+        writer.write_addr(size, "ch_size").unwrap();
+        writer
+            .write_addr(section.alignment, "ch_addralign")
+            .unwrap();
+        data.extend_from_slice(&deflate::zlib_compress(&section.data));
+
+        section.data = Cow::Owned(data);
+        section.flags |= SectionFlag::Compressed;
+        section.alignment = if self.is_64bit { 8 } else { 4 };
+
+        self.add_section(section)
+    }
+
+    /// Adds a `.interp` section containing the null-terminated path of the program interpreter,
+    /// the dynamic loader, and a [`SegmentKind::Interp`] segment covering it. `vaddr` is the
+    /// virtual address of the section and the segment. The segment is written before all other
+    /// segments, as required by the specification. Returns the index at which the section was
+    /// added.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if
+    /// * an interpreter has already been set,
+    /// * `vaddr` or the length of the path is greater than [`u32::MAX`] and the ELF file is
+    ///   32-bit, or
+    /// * the section table is full.
+    pub fn set_interpreter(&mut self, path: &str, vaddr: u64) -> Result<SectionId, BuildError> {
+        if self
+            .segments
+            .iter()
+            .any(|segment| segment.kind == SegmentKind::Interp)
+        {
+            return Err(BuildError::InvalidValue("p_type"));
+        }
+
+        let mut data = path.as_bytes().to_vec();
+        data.push(0);
+        let size = u64::try_from(data.len()).unwrap();
+
+        let name = self.add_string(".interp");
+        let section = self.add_section(Section {
+            name,
+            data: Cow::Owned(data),
+            kind: SectionKind::Progbits,
+            flags: SectionFlag::Alloc.into(),
+            extra_flags: 0,
+            vaddr,
+            entsize: 0,
+            alignment: 1,
+            size: None,
+            link: None,
+            info: 0,
+        })?;
+
+        self.add_segment(Segment {
+            section,
+            kind: SegmentKind::Interp,
+            vaddr,
+            paddr: vaddr,
+            filesz: size,
+            memsz: size,
+            flags: SegmentFlag::Read.into(),
+            align: 1,
+            last_section: None,
+        })?;
+
+        Ok(section)
+    }
+
+    /// Adds a [`SectionKind::Note`] section containing a single note with the given owner name,
+    /// type, and descriptor. The name is null-terminated and both the name and the descriptor are
+    /// padded to 4 bytes. Returns the index at which the section was added.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the name or the descriptor is longer than [`u32::MAX`] bytes, or if the
+    /// section table is full.
+    pub fn add_note_section(
+        &mut self,
+        name: impl Into<String> + AsRef<str>,
+        note_name: &str,
+        kind: u32,
+        desc: &[u8],
+    ) -> Result<SectionId, BuildError> {
+        let namesz = u32::try_from(note_name.len() + 1)
+            .map_err(|_| BuildError::ValueTooLarge("n_namesz"))?;
+        let descsz =
+            u32::try_from(desc.len()).map_err(|_| BuildError::ValueTooLarge("n_descsz"))?;
+
+        let mut data = Vec::new();
+        let mut writer = self.writer(&mut data);
+        // writing to a Vec can't fail
+        writer.write_u32(namesz).unwrap();
+        writer.write_u32(descsz).unwrap();
+        writer.write_u32(kind).unwrap();
+        writer.write_bytes(note_name.as_bytes()).unwrap();
+        writer.write_u8(0).unwrap();
+        data.resize(data.len().next_multiple_of(4), 0);
+        data.extend_from_slice(desc);
+        data.resize(data.len().next_multiple_of(4), 0);
+
+        let name = self.add_string(name);
+        self.add_section(Section {
+            name,
+            data: Cow::Owned(data),
+            kind: SectionKind::Note,
+            flags: Default::default(),
+            extra_flags: 0,
+            vaddr: 0,
+            entsize: 0,
+            alignment: 4,
+            size: None,
+            link: None,
+            info: 0,
+        })
+    }
+
+    /// Adds a `.note.gnu.build-id` section containing a `GNU` note of type `NT_GNU_BUILD_ID` with
+    /// the given build ID, which can be read back with [`ElfReader::build_id`]. Returns the index
+    /// at which the section was added.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the build ID is longer than [`u32::MAX`] bytes, or if the section table
+    /// is full.
+    pub fn add_build_id(&mut self, build_id: &[u8]) -> Result<SectionId, BuildError> {
+        self.add_note_section(".note.gnu.build-id", "GNU", NT_GNU_BUILD_ID, build_id)
     }
 
-    /// Adds a section to the section table and the data to the ELF file. Returns the index at which
-    /// the section was added.
+    /// Adds a segment entry into the program header. The segment type must not be
+    /// [`SegmentKind::Phdr`].
     ///
-    /// # Panics
+    /// # Errors
     ///
-    /// Panics if the virtual address, entry size, or alignment is greater than [`u32::MAX`] and the
-    /// ELF file is 32-bit
-    pub fn add_section(&mut self, section: Section<'data>) -> SectionId {
-        if !self.is_64bit {
-            assert!(section.vaddr <= u32::MAX.into());
-            assert!(section.entsize <= u32::MAX.into());
-            assert!(section.alignment <= u32::MAX.into());
+    /// Returns an error if
+    /// * the segment type is [`SegmentKind::Phdr`],
+    /// * memsz is less than filesz, or
+    /// * one of the addresses, sizes, or the alignment is greater than [`u32::MAX`] and the ELF
+    ///   file is 32-bit.
+    pub fn add_segment(&mut self, segment: Segment) -> Result<(), BuildError> {
+        if segment.kind == SegmentKind::Phdr {
+            return Err(BuildError::InvalidValue("p_type"));
+        } else if segment.memsz < segment.filesz {
+            return Err(BuildError::InvalidValue("p_memsz"));
         }
 
-        self.sections.push(section);
-        SectionId {
-            inner: SectionIdInner::Id((self.sections.len() - 1).try_into().unwrap()),
+        self.check_word(segment.vaddr, "p_vaddr")?;
+        self.check_word(segment.paddr, "p_paddr")?;
+        self.check_word(segment.filesz, "p_filesz")?;
+        self.check_word(segment.memsz, "p_memsz")?;
+        self.check_word(segment.align, "p_align")?;
+
+        if self.program_header_count() >= usize::from(u16::MAX) {
+            return Err(BuildError::ValueTooLarge("e_phnum"));
         }
+
+        self.segments.push(segment);
+
+        Ok(())
     }
 
-    /// Adds a segment entry into the program header. The segment type must not be
-    /// [`SegmentKind::Phdr`].
+    /// Emits a [`SegmentKind::Phdr`] segment describing the program header table, which is
+    /// written right after the ELF header. Its offset and size are those of the table, and it is
+    /// written before all other segments. `vaddr` is the address the table is loaded at, so it
+    /// should be covered by a [`SegmentKind::Load`] segment. Calling this again replaces the flags
+    /// and the address.
     ///
-    /// # Panics
+    /// # Errors
     ///
-    /// Panics if
-    /// * the segment type is [`SegmentKind::Phdr`], or
-    /// * memsz is less than filesz.
-    pub fn add_segment(&mut self, segment: Segment) {
-        assert!(segment.memsz >= segment.filesz);
-        assert!(segment.kind != SegmentKind::Phdr);
+    /// Returns an error if
+    /// * `vaddr` is greater than [`u32::MAX`] and the ELF file is 32-bit, or
+    /// * the program header table is full.
+    pub fn emit_phdr_segment(
+        &mut self,
+        flags: impl Into<FlagSet<SegmentFlag>>,
+        vaddr: u64,
+    ) -> Result<(), BuildError> {
+        self.check_word(vaddr, "p_vaddr")?;
 
-        self.segments.push(segment);
+        if self.phdr_segment.is_none() && self.segments.len() >= usize::from(u16::MAX) {
+            return Err(BuildError::ValueTooLarge("e_phnum"));
+        }
+
+        self.phdr_segment = Some((flags.into(), vaddr));
+
+        Ok(())
+    }
+
+    /// Adds a read-only [`SegmentKind::Tls`] segment, the thread-local storage template, starting
+    /// at the section `tdata` and at its address. The initialized data, usually `.tdata`, is the
+    /// data of `tdata` stored in the file, and `tbss_size` zero bytes, usually `.tbss`, follow it
+    /// in memory, so `filesz` is the size of `tdata` in the file and `memsz` is the size of
+    /// `tdata` plus `tbss_size`. If there is no initialized data, `tdata` can be the
+    /// [`SectionKind::Nobits`] section itself, which takes no space in the file. `align` is the
+    /// alignment of the template.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if
+    /// * `tdata` isn't a section added with [`ElfBuilder::add_section`] or is the null section,
+    ///   in which case the error is for `p_offset`,
+    /// * the size in memory doesn't fit in a [`u64`], or
+    /// * one of the values is invalid for [`ElfBuilder::add_segment`].
+    pub fn add_tls_segment(
+        &mut self,
+        tdata: SectionId,
+        tbss_size: u64,
+        align: u64,
+    ) -> Result<(), BuildError> {
+        let section = self
+            .section(tdata)
+            .filter(|section| section.kind != SectionKind::Null)
+            .ok_or(BuildError::InvalidValue("p_offset"))?;
+        let memsz = section
+            .size()
+            .checked_add(tbss_size)
+            .ok_or(BuildError::ValueTooLarge("p_memsz"))?;
+
+        self.add_segment(Segment {
+            section: tdata,
+            kind: SegmentKind::Tls,
+            vaddr: section.vaddr,
+            paddr: section.vaddr,
+            filesz: section.file_size(),
+            memsz,
+            flags: SegmentFlag::Read.into(),
+            align,
+            last_section: None,
+        })
     }
 
     /// Adds a string to the string table if it doesn't exist already and returns its index.
     pub fn add_string(&mut self, string: impl Into<String> + AsRef<str>) -> StringId {
-        let mut found = false;
-        let mut offset = 0;
-        for s in &self.strings {
-            if s == string.as_ref() {
-                found = true;
-                break;
-            }
-
-            offset += s.len() + 1; // 1 for the null byte
-        }
-
-        if !found {
-            self.strings.push(string.into());
-        }
+        add_string_to(&mut self.strings, string)
+    }
 
-        StringId {
-            offset: offset.try_into().unwrap(),
-        }
+    /// Adds a string to the dynamic string table if it doesn't exist already and returns its
+    /// index.
+    pub fn add_dynamic_string(&mut self, string: impl Into<String> + AsRef<str>) -> StringId {
+        add_string_to(&mut self.dynamic_strings, string)
     }
 
     /// Adds a symbol to the symbol table. The name is added to the string table. Returns the index
     /// of the symbol in the symbol table.
     ///
-    /// # Panics
+    /// For compatibility, the binding may also be given as a `bool`, where `true` means
+    /// [`SymbolBinding::Global`] and `false` means [`SymbolBinding::Local`].
     ///
-    /// Panics if the value or size is greater than [`u32::MAX`] and the ELF file is 32-bit.
+    /// # Errors
+    ///
+    /// Returns an error if the value or size is greater than [`u32::MAX`] and the ELF file is
+    /// 32-bit.
     pub fn add_symbol(
         &mut self,
         name: impl Into<String> + AsRef<str>,
         value: u64,
         size: u64,
-        global: bool,
+        binding: impl Into<SymbolBinding>,
         kind: SymbolKind,
         section: SectionId,
-    ) -> SymbolId {
-        let name_index = self.add_string(name);
+    ) -> Result<SymbolId, BuildError> {
+        self.check_word(value, "st_value")?;
+        self.check_word(size, "st_size")?;
 
-        if !self.is_64bit {
-            assert!(value <= u32::MAX.into());
-            assert!(size <= u32::MAX.into());
-        }
+        let name_index = self.add_string(name);
 
         self.symbols.push(Symbol {
             name: name_index,
             value,
             size,
-            global,
+            binding: binding.into(),
             kind,
             section,
         });
 
-        SymbolId {
+        Ok(SymbolId {
             index: (self.symbols.len() - 1).try_into().unwrap(),
+        })
+    }
+
+    /// Adds a symbol to the symbol table like [`ElfBuilder::add_symbol`], in the section with the
+    /// given name, as found by [`ElfBuilder::find_section`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if there is no section with the name, or if the value or size is greater
+    /// than [`u32::MAX`] and the ELF file is 32-bit.
+    pub fn add_symbol_in(
+        &mut self,
+        name: impl Into<String> + AsRef<str>,
+        value: u64,
+        size: u64,
+        binding: impl Into<SymbolBinding>,
+        kind: SymbolKind,
+        section_name: &str,
+    ) -> Result<SymbolId, BuildError> {
+        let section = self
+            .find_section(section_name)
+            .ok_or(BuildError::InvalidValue("st_shndx"))?;
+
+        self.add_symbol(name, value, size, binding, kind, section)
+    }
+
+    /// Adds a symbol to the dynamic symbol table. The name is added to the dynamic string table.
+    /// Returns the index of the symbol in the dynamic symbol table.
+    ///
+    /// Local symbols must precede all other symbols in the table, so a local symbol is inserted
+    /// after the local symbols added before it, and the indices of the non-local symbols added
+    /// before it increase by one. Unlike the symbols of the symbol table, which are referred to by
+    /// their IDs, the indices of dynamic symbols are usually written into section data, so add the
+    /// local symbols first to keep the returned indices valid.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the value or size is greater than [`u32::MAX`] and the ELF file is
+    /// 32-bit.
+    pub fn add_dynamic_symbol(
+        &mut self,
+        name: impl Into<String> + AsRef<str>,
+        value: u64,
+        size: u64,
+        binding: impl Into<SymbolBinding>,
+        kind: SymbolKind,
+        section: SectionId,
+    ) -> Result<SymbolId, BuildError> {
+        self.check_word(value, "st_value")?;
+        self.check_word(size, "st_size")?;
+
+        let name_index = self.add_dynamic_string(name);
+        let binding = binding.into();
+        let index = match binding {
+            SymbolBinding::Local => self
+                .dynamic_symbols
+                .iter()
+                .take_while(|symbol| symbol.binding == SymbolBinding::Local)
+                .count(),
+            _ => self.dynamic_symbols.len(),
+        };
+
+        self.dynamic_symbols.insert(
+            index,
+            Symbol {
+                name: name_index,
+                value,
+                size,
+                binding,
+                kind,
+                section,
+            },
+        );
+
+        Ok(SymbolId {
+            index: index.try_into().unwrap(),
+        })
+    }
+
+    /// Returns a section added with [`ElfBuilder::add_section`], or [`None`] if the ID doesn't
+    /// belong to such a section. The symbol tables and string tables are generated when building,
+    /// so they can't be accessed.
+    pub fn section(&self, id: SectionId) -> Option<&Section<'data>> {
+        match id.inner {
+            SectionIdInner::Id(index) => self.sections.get(usize::try_from(index).unwrap()),
+            _ => None,
         }
     }
 
+    /// Appends data to a section added with [`ElfBuilder::add_section`], so that its contents can
+    /// be written in several parts, such as when emitting code one function at a time. Borrowed
+    /// data is copied into an owned buffer on the first append. The data is appended as is, so
+    /// it shouldn't be used with compressed sections.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if
+    /// * the ID doesn't belong to such a section, or the section is the null section or a
+    ///   [`SectionKind::Nobits`] section, in which case the error is for `sh_type`, or
+    /// * the size of the section would be greater than [`u32::MAX`] and the ELF file is 32-bit.
+    pub fn append_to_section(&mut self, id: SectionId, data: &[u8]) -> Result<(), BuildError> {
+        let index = match id.inner {
+            SectionIdInner::Id(index) if index != 0 => usize::try_from(index).unwrap(),
+            _ => return Err(BuildError::InvalidValue("sh_type")),
+        };
+
+        let section = self
+            .sections
+            .get(index)
+            .filter(|section| !matches!(section.kind, SectionKind::Null | SectionKind::Nobits))
+            .ok_or(BuildError::InvalidValue("sh_type"))?;
+        self.check_word(
+            section.size() + u64::try_from(data.len()).unwrap(),
+            "sh_size",
+        )?;
+
+        self.sections[index].data.to_mut().extend_from_slice(data);
+
+        Ok(())
+    }
+
+    /// Returns the segments added with [`ElfBuilder::add_segment`], in the order they were added.
+    pub fn segments(&self) -> &[Segment] {
+        &self.segments
+    }
+
     /// Finds the index of a section in the section table by name. If it doesn't exist, [`None`] is
     /// returned.
     pub fn find_section(&self, name: &str) -> Option<SectionId> {
@@ -395,7 +1553,8 @@ impl<'data> ElfBuilder<'data> {
     }
 
     /// Creates a new Rel-type relocation table. The table is not added; it must be added with
-    /// [`ElfBuilder::add_relocation_table`]
+    /// [`ElfBuilder::add_relocation_table`]. The relocations apply to `section`, which must be a
+    /// section added with [`ElfBuilder::add_section`], or building fails.
     pub fn create_rel_table(
         &mut self,
         name: impl Into<String> + AsRef<str>,
@@ -411,7 +1570,8 @@ impl<'data> ElfBuilder<'data> {
     }
 
     /// Creates a new Rela-type relocation table. The table is not added; it must be added with
-    /// [`ElfBuilder::add_relocation_table`]
+    /// [`ElfBuilder::add_relocation_table`]. The relocations apply to `section`, which must be a
+    /// section added with [`ElfBuilder::add_section`], or building fails.
     pub fn create_rela_table(
         &mut self,
         name: impl Into<String> + AsRef<str>,
@@ -433,18 +1593,7 @@ impl<'data> ElfBuilder<'data> {
 
     /// Finds the index of a string in the string table. If it doesn't exist, [`None`] is returned.
     pub fn find_string(&self, string: &str) -> Option<StringId> {
-        let mut offset = 0;
-        for s in &self.strings {
-            if s == string {
-                return Some(StringId {
-                    offset: offset.try_into().unwrap(),
-                });
-            }
-
-            offset += s.len() + 1; // 1 for the null byte
-        }
-
-        None
+        find_string_in(&self.strings, string)
     }
 
     /// Finds the index of a symbol in the symbol table. If it doesn't exist, [`None`] is returned.
@@ -461,24 +1610,131 @@ impl<'data> ElfBuilder<'data> {
 
     /// Sets the address the ELF file, if executable, will start executing at.
     ///
-    /// # Panics
+    /// # Errors
     ///
-    /// Panics if the entrypoint is greater than [`u32::MAX`] for 32-bit files.
-    pub fn set_entrypoint(&mut self, entrypoint: u64) {
-        if !self.is_64bit {
-            assert!(entrypoint <= u32::MAX.into());
-        }
+    /// Returns an error if the entrypoint is greater than [`u32::MAX`] for 32-bit files.
+    pub fn set_entrypoint(&mut self, entrypoint: u64) -> Result<(), BuildError> {
+        self.check_word(entrypoint, "e_entry")?;
 
         self.entrypoint = entrypoint;
+
+        Ok(())
+    }
+
+    /// Sets the processor-specific flags of the ELF file, such as the float ABI on RISC-V.
+    /// `e_flags` in the specification.
+    pub fn set_flags(&mut self, flags: u32) {
+        self.flags = flags;
+    }
+
+    /// Sets the operating system or ABI the ELF file targets. `ei_osabi` in the specification.
+    pub fn set_osabi(&mut self, osabi: OsAbi) {
+        self.osabi = osabi;
+    }
+
+    /// Sets the version of the ABI the ELF file targets. `ei_abiversion` in the specification.
+    pub fn set_abiversion(&mut self, abiversion: u8) {
+        self.abiversion = abiversion;
+    }
+
+    /// Sets a byte in the padding at the end of the identification bytes, `ei_pad` in the
+    /// specification, which is zero by default. `index` is the index in the identification bytes,
+    /// from 9 to 15. The padding should be zero, so this is only useful for nonstandard markers and
+    /// for testing how other programs handle them.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the index is outside of the padding.
+    pub fn set_ident_byte(&mut self, index: usize, value: u8) -> Result<(), BuildError> {
+        *index
+            .checked_sub(EI_PAD)
+            .and_then(|index| self.ident_padding.get_mut(index))
+            .ok_or(BuildError::InvalidValue("e_ident"))? = value;
+
+        Ok(())
+    }
+
+    /// Sets the version of the ELF file format, 1 by default. `e_version` in the specification.
+    ///
+    /// 1 is the only valid version, so other values are only useful for testing how other programs
+    /// handle invalid files.
+    pub fn set_version(&mut self, version: u32) {
+        self.version = version;
+    }
+
+    /// Sets the version of the ELF file format in the identification bytes, 1 by default.
+    /// `ei_version` in the specification.
+    ///
+    /// 1 is the only valid version, and [`ElfReader::new`] rejects files with other versions.
+    pub fn set_ei_version(&mut self, ei_version: u8) {
+        self.ei_version = ei_version;
+    }
+
+    /// Sets whether the string table `.strtab` is tail-merged when building: strings that are
+    /// suffixes of other strings, such as `.text` and `.rela.text`, are stored only as a part of
+    /// the longer string. The names of the sections and symbols are updated to the merged offsets,
+    /// but [`StringId`]s stored in section data are not. The dynamic string table is never merged,
+    /// because its offsets are usually stored in section data, such as in the dynamic section.
+    pub fn set_tail_merge_strings(&mut self, merge: bool) {
+        self.tail_merge_strings = merge;
+    }
+
+    /// Sets whether the file is stripped when building, like with `strip --strip-all`: sections
+    /// that aren't [`SectionFlag::Alloc`], such as `.comment` and `.debug_info`, are omitted along
+    /// with the symbol table and the relocation tables, and the string table `.strtab` only
+    /// contains the section names. Sections covered by segments and sections linked to by the
+    /// remaining sections are kept. The dynamic symbol table is allocated, so it is kept.
+    ///
+    /// The IDs of the remaining sections are updated in section links, segments, and dynamic
+    /// symbols, but section indices stored in section data are not. Building fails if a remaining
+    /// section is linked to the symbol table.
+    pub fn set_strip(&mut self, strip: bool) {
+        self.strip = strip;
+    }
+
+    /// Allows building files whose contents don't make sense for their type, which are most
+    /// likely mistakes. By default, building fails if
+    /// * a [`ElfKind::Relocatable`] file has program headers, in which case the error is for
+    ///   `e_phnum`, or an entry point, in which case the error is for `e_entry`, or
+    /// * an [`ElfKind::Executable`] or [`ElfKind::Dynamic`] file has no [`SegmentKind::Load`]
+    ///   segment, so nothing would be loaded. The error is for `e_phnum`.
+    ///
+    /// Files imported with [`ElfBuilder::from_reader`] allow these, so that they can be rebuilt
+    /// as they are.
+    pub fn allow_unusual(&mut self) {
+        self.allow_unusual = true;
+    }
+
+    /// Sets where the section header table is placed in the built file. By default it is placed
+    /// at the end of the file, after the section data.
+    pub fn set_section_header_placement(&mut self, placement: SectionHeaderPlacement) {
+        self.section_header_placement = placement;
     }
 
-    /// Returns the section ID of the first section, the null section.
+    /// Returns the section ID of the first section, the null section. Symbols in the null section
+    /// are undefined, `SHN_UNDEF`.
     pub fn null_section(&self) -> SectionId {
         SectionId {
             inner: SectionIdInner::Id(0),
         }
     }
 
+    /// Returns the section ID used for absolute symbols, `SHN_ABS`. Their values are not affected
+    /// by relocation.
+    pub fn absolute_section(&self) -> SectionId {
+        SectionId {
+            inner: SectionIdInner::Reserved(SHN_ABS),
+        }
+    }
+
+    /// Returns the section ID used for common symbols, `SHN_COMMON`, which are allocated by the
+    /// linker. The value of a common symbol is its alignment.
+    pub fn common_section(&self) -> SectionId {
+        SectionId {
+            inner: SectionIdInner::Reserved(SHN_COMMON),
+        }
+    }
+
     /// Returns the section ID of the symbol table.
     pub fn symbol_table(&mut self) -> SectionId {
         self.symbol_table_needed = true;
@@ -494,12 +1750,272 @@ impl<'data> ElfBuilder<'data> {
             inner: SectionIdInner::StringTable,
         }
     }
+
+    /// Returns the section ID of the dynamic symbol table, `.dynsym`. The dynamic symbol table and
+    /// its string table `.dynstr` are allocated, so they can be loaded at runtime.
+    pub fn dynamic_symbol_table(&mut self) -> SectionId {
+        self.dynamic_symbol_table_needed = true;
+
+        SectionId {
+            inner: SectionIdInner::DynamicSymbolTable,
+        }
+    }
+
+    /// Returns the section ID of the dynamic string table, `.dynstr`.
+    pub fn dynamic_string_table(&mut self) -> SectionId {
+        self.dynamic_symbol_table_needed = true;
+
+        SectionId {
+            inner: SectionIdInner::DynamicStringTable,
+        }
+    }
+}
+
+fn write_padding<W: Write>(mut target: W, size: u64) -> std::io::Result<()> {
+    std::io::copy(&mut std::io::repeat(0).take(size), &mut target)?;
+
+    Ok(())
+}
+
+fn add_string_to(strings: &mut Vec<String>, string: impl Into<String> + AsRef<str>) -> StringId {
+    let mut found = false;
+    let mut offset = 0;
+    for s in strings.iter() {
+        if s == string.as_ref() {
+            found = true;
+            break;
+        }
+
+        offset += s.len() + 1; // 1 for the null byte
+    }
+
+    if !found {
+        strings.push(string.into());
+    }
+
+    StringId {
+        offset: offset.try_into().unwrap(),
+    }
+}
+
+fn find_string_in(strings: &[String], string: &str) -> Option<StringId> {
+    let mut offset = 0;
+    for s in strings {
+        if s == string {
+            return Some(StringId {
+                offset: offset.try_into().unwrap(),
+            });
+        }
+
+        offset += s.len() + 1; // 1 for the null byte
+    }
+
+    None
+}
+
+/// Tail-merges a string table, so that the strings that are suffixes of other strings are stored
+/// as a part of them. Returns the remaining strings in their original order, and a map from the
+/// offsets of all of the original strings to their offsets in the merged table.
+fn tail_merge(strings: &[String]) -> (Vec<String>, BTreeMap<u64, u64>) {
+    // sorting by the reversed strings in descending order places every string right after the
+    // strings it is a suffix of. The empty string at offset 0 is kept as is.
+    let mut order = (1..strings.len()).collect::<Vec<_>>();
+    order.sort_by(|&a, &b| strings[b].bytes().rev().cmp(strings[a].bytes().rev()));
+
+    // the index of the string containing each string and the position of the string in it
+    let mut containers = vec![(0, 0); strings.len()];
+    let mut last_kept: Option<usize> = None;
+    for index in order {
+        containers[index] = match last_kept {
+            Some(kept) if strings[kept].ends_with(&strings[index]) => {
+                (kept, strings[kept].len() - strings[index].len())
+            }
+            _ => {
+                last_kept = Some(index);
+                (index, 0)
+            }
+        };
+    }
+
+    let mut merged = Vec::new();
+    let mut merged_offsets = vec![0; strings.len()];
+    let mut offset = 0;
+    for (index, string) in strings.iter().enumerate() {
+        if containers[index].0 == index {
+            merged_offsets[index] = offset;
+            offset += string.len() + 1; // 1 for the null byte
+            merged.push(string.clone());
+        }
+    }
+
+    let mut offsets = BTreeMap::new();
+    let mut original_offset = 0;
+    for (index, string) in strings.iter().enumerate() {
+        let (container, position) = containers[index];
+        let merged_offset = merged_offsets[container] + position;
+        offsets.insert(
+            original_offset.try_into().unwrap(),
+            merged_offset.try_into().unwrap(),
+        );
+        original_offset += string.len() + 1;
+    }
+
+    (merged, offsets)
+}
+
+/// Computes the order of the symbols in the built symbol table, where local symbols precede all
+/// other symbols, as required by the specification. Returns a table mapping the symbol IDs to the
+/// indices in the built symbol table, and the index of the first non-local symbol.
+fn symbol_order(symbols: &[Symbol]) -> (Vec<u64>, usize) {
+    let first_global = symbols
+        .iter()
+        .filter(|symbol| symbol.binding == SymbolBinding::Local)
+        .count();
+    let (mut next_local, mut next_global) = (0, first_global);
+
+    let symbol_indices = symbols
+        .iter()
+        .map(|symbol| {
+            let index = if symbol.binding == SymbolBinding::Local {
+                &mut next_local
+            } else {
+                &mut next_global
+            };
+            *index += 1;
+
+            u64::try_from(*index - 1).unwrap()
+        })
+        .collect();
+
+    (symbol_indices, first_global)
+}
+
+/// Returns the symbols in the order computed by [`symbol_order`].
+fn ordered_symbols(symbols: &[Symbol]) -> impl Iterator<Item = &Symbol> {
+    let locals = symbols
+        .iter()
+        .filter(|symbol| symbol.binding == SymbolBinding::Local);
+    let globals = symbols
+        .iter()
+        .filter(|symbol| symbol.binding != SymbolBinding::Local);
+
+    locals.chain(globals)
+}
+
+/// Writes the symbols as symbol table section bytes, in the order computed by [`symbol_order`].
+/// `shndx` returns the `st_shndx` value of a section.
+fn write_symbol_table<W: Write>(
+    writer: &mut Writer<W>,
+    symbols: &[Symbol],
+    shndx: impl Fn(SectionId) -> Result<u16, BuildError>,
+) -> std::io::Result<()> {
+    for symbol in ordered_symbols(symbols) {
+        let info = (symbol.binding.to_u8().unwrap() << 4) | symbol.kind.to_u8().unwrap();
+        let section = shndx(symbol.section)?;
+
+        writer.write_u32(symbol.name.try_into().unwrap())?;
+
+        // the fields are in a different order in 64-bit files to keep them aligned
+        if writer.is_64bit() {
+            writer.write_bytes(&[info, 0])?; // other, always 0
+            writer.write_half(section)?;
+            writer.write_addr(symbol.value, "st_value")?;
+            writer.write_addr(symbol.size, "st_size")?;
+        } else {
+            writer.write_addr(symbol.value, "st_value")?;
+            writer.write_addr(symbol.size, "st_size")?;
+            writer.write_bytes(&[info, 0])?; // other, always 0
+            writer.write_half(section)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Writes the section indices of the symbols as `.symtab_shndx` section bytes, in the order
+/// computed by [`symbol_order`]. `index` returns the index of a section that doesn't fit in
+/// `st_shndx`, or 0 if it fits.
+fn write_symbol_section_indices<W: Write>(
+    writer: &mut Writer<W>,
+    symbols: &[Symbol],
+    index: impl Fn(SectionId) -> u32,
+) -> std::io::Result<()> {
+    for symbol in ordered_symbols(symbols) {
+        writer.write_u32(index(symbol.section))?;
+    }
+
+    Ok(())
+}
+
+/// Returns the size of the string table section containing the strings.
+fn string_table_size(strings: &[String]) -> u64 {
+    strings
+        .iter()
+        .map(|string| u64::try_from(string.len()).unwrap() + 1) // 1 for the null byte
+        .sum()
+}
+
+fn write_string_table<W: Write>(mut target: W, strings: &[String]) -> std::io::Result<()> {
+    for string in strings {
+        target.write_all(string.as_bytes())?;
+        target.write_all(&[0])?;
+    }
+
+    Ok(())
+}
+
+/// The contents of a section generated by [`ElfBuilder::build`], which are written directly to
+/// the target instead of being stored in the section's data.
+#[derive(Debug, Clone, Copy)]
+enum GeneratedSection {
+    SymbolTable,
+    DynamicSymbolTable,
+    StringTable,
+    DynamicStringTable,
+    /// The section indices of the symbols in the symbol table
+    SymbolSectionIndices,
+    /// The relocation table at the index in [`ElfBuilder::relocations`]
+    Relocations(usize),
+}
+
+/// The builder with the generated sections added, the indices and the contents of the generated
+/// sections, and the indices of the symbols in the symbol table, as returned by
+/// [`ElfBuilder::prepare`].
+type PreparedBuilder<'data> = (ElfBuilder<'data>, Vec<(usize, GeneratedSection)>, Vec<u64>);
+
+/// The file offsets at which [`ElfBuilder::build`] places the parts of the file, as returned by
+/// [`ElfBuilder::layout`]. The ELF header is always at offset 0.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Layout {
+    /// The offset of the program header table, or 0 if there are no program headers. `e_phoff`
+    /// in the specification.
+    pub program_headers: u64,
+    /// The offset of every section, in the order of the section header table. A section that
+    /// occupies no space in the file, like a [`SectionKind::Nobits`] section, has the offset it
+    /// would start at. `sh_offset` in the specification, except that it is written as 0 for
+    /// [`SectionKind::Null`] sections.
+    pub sections: Vec<u64>,
+    /// The offset of the section header table. `e_shoff` in the specification.
+    pub section_headers: u64,
+    /// The size of the file.
+    pub size: u64,
+}
+
+/// Where [`ElfBuilder::build`] places the section header table in the file
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SectionHeaderPlacement {
+    /// After the section data, at the end of the file
+    #[default]
+    End,
+    /// Right after the program headers, before the section data
+    AfterProgramHeaders,
 }
 
 /// A section in an ELF file
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Section<'a> {
-    /// The data the section contains.
+    /// The data the section contains. The data of a [`SectionKind::Nobits`] section isn't written
+    /// to the file.
     pub data: Cow<'a, [u8]>,
     /// The name of the section
     pub name: StringId,
@@ -507,21 +2023,78 @@ pub struct Section<'a> {
     pub kind: SectionKind,
     /// Section flags
     pub flags: FlagSet<SectionFlag>,
+    /// Flags that aren't in [`SectionFlag`], such as OS-specific or processor-specific flags like
+    /// `SHF_X86_64_LARGE`, which are combined with `flags` when building
+    pub extra_flags: u64,
     /// The virtual address the section is loaded at
     pub vaddr: u64,
-    /// Extra information
+    /// Extra information. For [`SectionKind::Group`] sections, the [`SymbolId`] of the group's
+    /// signature symbol in the symbol table, which is converted to the symbol's final index when
+    /// building. The group's `link` should then be [`ElfBuilder::symbol_table`].
     pub info: u32,
     /// If the section contains an array of entries, the size of a single entry in bytes
     pub entsize: u64,
     /// The required alignment of the virtual address
     pub alignment: u64,
+    /// The size of a [`SectionKind::Nobits`] section, such as `.bss`, in memory. Nobits sections
+    /// don't occupy space in the file, so `data` should be empty. If [`None`], the length of
+    /// `data` is used.
+    pub size: Option<u64>,
+    /// The section this section is linked to, such as the symbol table of a hash table. If
+    /// [`None`], the link is derived from the section type for symbol tables and relocation
+    /// tables, and is 0 for other sections. `sh_link` in the specification.
+    pub link: Option<SectionId>,
+}
+
+impl Section<'_> {
+    /// The section at index 0 of every section table.
+    fn null() -> Self {
+        Section {
+            data: Cow::Borrowed(&[]),
+            name: StringId::empty(),
+            kind: SectionKind::Null,
+            flags: Default::default(),
+            extra_flags: 0,
+            vaddr: 0,
+            info: 0,
+            entsize: 0,
+            alignment: 0,
+            size: None,
+            link: None,
+        }
+    }
+
+    /// The size of the section in memory, which is `size` if set and the length of `data`
+    /// otherwise. `sh_size` in the specification.
+    pub fn size(&self) -> u64 {
+        self.size
+            .unwrap_or_else(|| self.data.len().try_into().unwrap())
+    }
+
+    /// The flags of the section, combining `flags` and `extra_flags`. `sh_flags` in the
+    /// specification.
+    pub fn raw_flags(&self) -> u64 {
+        u64::from(self.flags.bits()) | self.extra_flags
+    }
+
+    /// The number of bytes the section occupies in the file.
+    fn file_size(&self) -> u64 {
+        match self.kind {
+            SectionKind::Nobits => 0,
+            _ => self.size(),
+        }
+    }
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 enum SectionIdInner {
     SymbolTable,
     StringTable,
-    Id(u16),
+    DynamicSymbolTable,
+    DynamicStringTable,
+    Id(u32),
+    /// A reserved section index, such as `SHN_ABS`, imported from an existing file
+    Reserved(u16),
 }
 
 /// Represents the ID of a section in an ELF file.
@@ -570,7 +2143,7 @@ impl From<SymbolId> for u64 {
 }
 
 /// A segment in the program header of an ELF file
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Segment {
     /// The index of the section the segment refers to
     pub section: SectionId,
@@ -586,8 +2159,15 @@ pub struct Segment {
     pub memsz: u64,
     /// Segment flags
     pub flags: FlagSet<SegmentFlag>,
-    /// The required alignment of the virtual address
+    /// The required alignment of the virtual address. If the segment is a [`SegmentKind::Load`]
+    /// segment and the alignment is a power of two, padding is inserted before the segment's first
+    /// section so that its offset in the file is congruent to `vaddr` modulo `align`, allowing the
+    /// segment to be mapped into memory.
     pub align: u64,
+    /// The last section the segment covers, if it spans several contiguous sections, such as a
+    /// [`SegmentKind::Load`] segment containing `.text` and `.rodata`. If set, the size in the
+    /// file is computed from the offsets of the sections and `filesz` is ignored.
+    pub last_section: Option<SectionId>,
 }
 
 /// A table containing relocations of a specific type of a section
@@ -599,6 +2179,40 @@ pub enum RelocationTable {
     Rela(RelaTable),
 }
 
+impl RelocationTable {
+    /// Returns an error if is_64bit is false and one of the relocation entries does not fit in 32
+    /// bits after remapping the symbol indices, so that the error is found before anything is
+    /// written.
+    fn check(&self, is_64bit: bool, symbol_indices: &[u64]) -> Result<(), BuildError> {
+        if is_64bit {
+            return Ok(());
+        }
+
+        let entries: Box<dyn Iterator<Item = (u64, u64, u64)>> = match self {
+            RelocationTable::Rela(table) => Box::new(
+                table
+                    .relocations
+                    .iter()
+                    .map(|relocation| (relocation.offset, relocation.info, relocation.addend)),
+            ),
+            RelocationTable::Rel(table) => Box::new(
+                table
+                    .relocations
+                    .iter()
+                    .map(|relocation| (relocation.offset, relocation.info, 0)),
+            ),
+        };
+
+        for (offset, info, addend) in entries {
+            word(offset, "r_offset")?;
+            word(remap_relocation_info(info, false, symbol_indices), "r_info")?;
+            word(addend, "r_addend")?;
+        }
+
+        Ok(())
+    }
+}
+
 /// A table containing the Rela-type relocations for a section
 #[derive(Debug, Clone)]
 pub struct RelaTable {
@@ -613,35 +2227,23 @@ impl RelaTable {
         self.relocations.push(relocation);
     }
 
-    /// Converts the relocation table to ELF section bytes.
-    ///
-    /// # Panics
-    ///
-    /// Panics if is_64bit is false and one of the relocation entries does not fit in 32 bits.
-    fn to_bytes(&self, endianness: Endianness, is_64bit: bool) -> Vec<u8> {
-        let mut relocation_table = Vec::new();
-
-        if is_64bit {
-            for relocation in &self.relocations {
-                relocation_table.extend_from_slice(&endianness.u64_to_bytes(relocation.offset));
-                relocation_table.extend_from_slice(&endianness.u64_to_bytes(relocation.info));
-                relocation_table.extend_from_slice(&endianness.u64_to_bytes(relocation.addend));
-            }
-        } else {
-            for relocation in &self.relocations {
-                relocation_table.extend_from_slice(
-                    &endianness.u32_to_bytes(relocation.offset.try_into().unwrap()),
-                );
-                relocation_table.extend_from_slice(
-                    &endianness.u32_to_bytes(relocation.info.try_into().unwrap()),
-                );
-                relocation_table.extend_from_slice(
-                    &endianness.u32_to_bytes(relocation.addend.try_into().unwrap()),
-                );
-            }
+    /// Writes the relocation table as ELF section bytes. The symbol indices are remapped using
+    /// `symbol_indices`. Returns an error if the file is 32-bit and one of the relocation entries
+    /// does not fit in 32 bits.
+    fn write<W: Write>(
+        &self,
+        writer: &mut Writer<W>,
+        symbol_indices: &[u64],
+    ) -> std::io::Result<()> {
+        for relocation in &self.relocations {
+            let info = remap_relocation_info(relocation.info, writer.is_64bit(), symbol_indices);
+
+            writer.write_addr(relocation.offset, "r_offset")?;
+            writer.write_addr(info, "r_info")?;
+            writer.write_addr(relocation.addend, "r_addend")?;
         }
 
-        relocation_table
+        Ok(())
     }
 }
 
@@ -659,50 +2261,80 @@ impl RelTable {
         self.relocations.push(relocation);
     }
 
-    /// Converts the relocation table to ELF section bytes.
-    ///
-    /// # Panics
-    ///
-    /// Panics if is_64bit is false and one of the relocation entries does not fit in 32 bits.
-    fn to_bytes(&self, endianness: Endianness, is_64bit: bool) -> Vec<u8> {
-        let mut relocation_table = Vec::new();
-
-        if is_64bit {
-            for relocation in &self.relocations {
-                relocation_table.extend_from_slice(&endianness.u64_to_bytes(relocation.offset));
-                relocation_table.extend_from_slice(&endianness.u64_to_bytes(relocation.info));
-            }
-        } else {
-            for relocation in &self.relocations {
-                relocation_table.extend_from_slice(
-                    &endianness.u32_to_bytes(relocation.offset.try_into().unwrap()),
-                );
-                relocation_table.extend_from_slice(
-                    &endianness.u32_to_bytes(relocation.info.try_into().unwrap()),
-                );
-            }
+    /// Writes the relocation table as ELF section bytes. The symbol indices are remapped using
+    /// `symbol_indices`. Returns an error if the file is 32-bit and one of the relocation entries
+    /// does not fit in 32 bits.
+    fn write<W: Write>(
+        &self,
+        writer: &mut Writer<W>,
+        symbol_indices: &[u64],
+    ) -> std::io::Result<()> {
+        for relocation in &self.relocations {
+            let info = remap_relocation_info(relocation.info, writer.is_64bit(), symbol_indices);
+
+            writer.write_addr(relocation.offset, "r_offset")?;
+            writer.write_addr(info, "r_info")?;
         }
 
-        relocation_table
+        Ok(())
     }
 }
 
+/// Converts a value of a field of a 32-bit ELF file into a [`u32`], or returns an error if it is
+/// too large.
+fn word(value: u64, field: &'static str) -> Result<u32, BuildError> {
+    value
+        .try_into()
+        .map_err(|_| BuildError::ValueTooLarge(field))
+}
+
+/// Replaces the symbol index in a relocation's `r_info` according to `symbol_indices`. Indices
+/// outside of the symbol table are left as is.
+fn remap_relocation_info(info: u64, is_64bit: bool, symbol_indices: &[u64]) -> u64 {
+    let (shift, kind_mask) = if is_64bit {
+        (32, 0xffffffff)
+    } else {
+        (8, 0xff)
+    };
+
+    usize::try_from(info >> shift)
+        .ok()
+        .and_then(|symbol| symbol_indices.get(symbol))
+        .map_or(info, |&symbol| (symbol << shift) | (info & kind_mask))
+}
+
 #[derive(Debug, Clone)]
 struct Symbol {
     name: StringId,
     value: u64,
     size: u64,
-    global: bool,
+    binding: SymbolBinding,
     kind: SymbolKind,
     section: SectionId,
 }
 
+impl Symbol {
+    /// The symbol at index 0 of every symbol table.
+    fn null() -> Self {
+        Symbol {
+            name: StringId::empty(),
+            value: 0,
+            size: 0,
+            binding: SymbolBinding::Local,
+            kind: SymbolKind::NoType,
+            section: SectionId {
+                inner: SectionIdInner::Id(0),
+            },
+        }
+    }
+}
+
 /// An `Elf_Rela`-type relocation entry
 #[derive(Debug, Clone)]
 pub struct RelaEntry {
     /// The offset which the relocation should be applied at
     pub offset: u64,
-    /// Symbol table index and type of relocation
+    /// Symbol table index and type of relocation, as packed by [`RelaEntry::new`]
     pub info: u64,
     /// Constant addend to be used in the calculation
     pub addend: u64,
@@ -713,6 +2345,82 @@ pub struct RelaEntry {
 pub struct RelEntry {
     /// The offset which the relocation should be applied at
     pub offset: u64,
-    /// Symbol table index and type of relocation
+    /// Symbol table index and type of relocation, as packed by [`RelEntry::new`]
     pub info: u64,
 }
+
+impl RelaEntry {
+    /// Creates a relocation entry, packing the symbol and the relocation type into `r_info` as
+    /// required by the class of the file: `symbol << 32 | kind` in 64-bit files, and
+    /// `symbol << 8 | kind` in 32-bit files.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the symbol index or the type doesn't fit in its part of `r_info`.
+    pub fn new(
+        offset: u64,
+        symbol: SymbolId,
+        kind: u32,
+        addend: u64,
+        is_64bit: bool,
+    ) -> Result<Self, BuildError> {
+        Ok(Self {
+            offset,
+            info: relocation_info(symbol, kind, is_64bit)?,
+            addend,
+        })
+    }
+}
+
+impl RelEntry {
+    /// Creates a relocation entry, packing the symbol and the relocation type into `r_info` as
+    /// described in [`RelaEntry::new`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the symbol index or the type doesn't fit in its part of `r_info`.
+    pub fn new(
+        offset: u64,
+        symbol: SymbolId,
+        kind: u32,
+        is_64bit: bool,
+    ) -> Result<Self, BuildError> {
+        Ok(Self {
+            offset,
+            info: relocation_info(symbol, kind, is_64bit)?,
+        })
+    }
+}
+
+/// Packs a symbol index and a relocation type into `r_info`.
+fn relocation_info(symbol: SymbolId, kind: u32, is_64bit: bool) -> Result<u64, BuildError> {
+    let (shift, symbol_limit, kind_limit) = if is_64bit {
+        (32, u32::MAX.into(), u32::MAX)
+    } else {
+        (8, 0xff_ffff, 0xff)
+    };
+
+    if symbol.index > symbol_limit || kind > kind_limit {
+        return Err(BuildError::ValueTooLarge("r_info"));
+    }
+
+    Ok(symbol.index << shift | u64::from(kind))
+}
+
+/// Represents an error that can occur when building an ELF file.
+#[derive(Debug, Error, Clone, Copy, PartialEq, Eq)]
+pub enum BuildError {
+    /// A value does not fit in the field it would be written to, such as a 64-bit address in a
+    /// 32-bit ELF file. The string contains the name of the field.
+    #[error("value too large for field {0}")]
+    ValueTooLarge(&'static str),
+    /// A field had an invalid value. The string contains the name of the field.
+    #[error("invalid value in field {0}")]
+    InvalidValue(&'static str),
+}
+
+impl From<BuildError> for std::io::Error {
+    fn from(error: BuildError) -> Self {
+        std::io::Error::new(std::io::ErrorKind::InvalidInput, error)
+    }
+}
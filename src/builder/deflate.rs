@@ -0,0 +1,143 @@
+//! A minimal encoder for zlib streams (RFC 1950) containing DEFLATE data (RFC 1951), used for
+//! compressed sections. Repeated strings are found with a single-entry hash table and encoded with
+//! the fixed Huffman codes, which is fast and simple but doesn't compress as well as zlib.
+
+use crate::reader::inflate::{adler32, DISTANCE_BASE, DISTANCE_EXTRA, LENGTH_BASE, LENGTH_EXTRA};
+
+const WINDOW_SIZE: usize = 32768;
+const MIN_MATCH: usize = 3;
+const MAX_MATCH: usize = 258;
+const HASH_BITS: u32 = 15;
+
+/// Compresses `data` into a zlib stream.
+pub(super) fn zlib_compress(data: &[u8]) -> Vec<u8> {
+    // deflate with a 32 KiB window, and the default compression level
+    let mut writer = BitWriter::new(vec![0x78, 0x9c]);
+
+    // a single final block compressed with the fixed codes
+    writer.bits(1, 1);
+    writer.bits(1, 2);
+
+    let mut head = vec![usize::MAX; 1 << HASH_BITS];
+    let mut position = 0;
+
+    while position < data.len() {
+        let (length, distance) = match data.get(position..position + MIN_MATCH) {
+            Some(prefix) => {
+                let hash = hash(prefix);
+                let candidate = head[hash];
+                head[hash] = position;
+
+                if candidate != usize::MAX && position - candidate <= WINDOW_SIZE {
+                    let end = data.len().min(position + MAX_MATCH);
+                    let length = data[candidate..]
+                        .iter()
+                        .zip(&data[position..end])
+                        .take_while(|(a, b)| a == b)
+                        .count();
+
+                    (length, position - candidate)
+                } else {
+                    (0, 0)
+                }
+            }
+            None => (0, 0),
+        };
+
+        if length < MIN_MATCH {
+            writer.symbol(data[position].into());
+            position += 1;
+            continue;
+        }
+
+        let index = LENGTH_BASE.partition_point(|&base| usize::from(base) <= length) - 1;
+        writer.symbol(257 + u16::try_from(index).unwrap());
+        writer.bits(
+            u32::try_from(length).unwrap() - u32::from(LENGTH_BASE[index]),
+            LENGTH_EXTRA[index],
+        );
+
+        let index = DISTANCE_BASE.partition_point(|&base| usize::from(base) <= distance) - 1;
+        writer.code(u16::try_from(index).unwrap(), 5);
+        writer.bits(
+            u32::try_from(distance).unwrap() - u32::from(DISTANCE_BASE[index]),
+            DISTANCE_EXTRA[index],
+        );
+
+        for start in position + 1..position + length {
+            if let Some(prefix) = data.get(start..start + MIN_MATCH) {
+                head[hash(prefix)] = start;
+            }
+        }
+
+        position += length;
+    }
+
+    // end of block
+    writer.symbol(256);
+
+    let mut output = writer.finish();
+    output.extend_from_slice(&adler32(data).to_be_bytes());
+    output
+}
+
+fn hash(prefix: &[u8]) -> usize {
+    let value = u32::from(prefix[0]) << 16 | u32::from(prefix[1]) << 8 | u32::from(prefix[2]);
+
+    usize::try_from(value.wrapping_mul(0x9e37_79b1) >> (32 - HASH_BITS)).unwrap()
+}
+
+/// Writes bits starting from the least significant bit of each byte, as required by DEFLATE.
+struct BitWriter {
+    output: Vec<u8>,
+    buffer: u64,
+    count: u8,
+}
+
+impl BitWriter {
+    fn new(output: Vec<u8>) -> Self {
+        Self {
+            output,
+            buffer: 0,
+            count: 0,
+        }
+    }
+
+    fn bits(&mut self, value: u32, count: u8) {
+        self.buffer |= u64::from(value) << self.count;
+        self.count += count;
+
+        while self.count >= 8 {
+            self.output.push(self.buffer as u8);
+            self.buffer >>= 8;
+            self.count -= 8;
+        }
+    }
+
+    /// Writes a Huffman code, which is stored starting from its most significant bit.
+    fn code(&mut self, code: u16, length: u8) {
+        self.bits(
+            u32::from(code).reverse_bits() >> (32 - u32::from(length)),
+            length,
+        );
+    }
+
+    /// Writes a symbol of the literal/length alphabet with its fixed Huffman code.
+    fn symbol(&mut self, symbol: u16) {
+        match symbol {
+            0..=143 => self.code(0x30 + symbol, 8),
+            144..=255 => self.code(0x190 + symbol - 144, 9),
+            256..=279 => self.code(symbol - 256, 7),
+            _ => self.code(0xc0 + symbol - 280, 8),
+        }
+    }
+
+    /// Pads the last byte with zero bits and returns the output.
+    fn finish(mut self) -> Vec<u8> {
+        if self.count > 0 {
+            self.output.push(self.buffer as u8);
+        }
+
+        self.output
+    }
+}
@@ -2,89 +2,84 @@ use std::io::Write;
 
 use num_traits::ToPrimitive;
 
-use crate::{Endianness, SectionKind};
+use crate::{Endianness, SectionKind, SegmentKind};
 
 use super::{
-    ElfBuilder, ELF64_HEADER_SIZE, ELF64_PROGRAM_HEADER_SIZE, ELF64_SECTION_HEADER_SIZE, ELF_MAGIC,
+    ElfBuilder, Layout, ELF64_HEADER_SIZE, ELF64_PROGRAM_HEADER_SIZE, ELF64_SECTION_HEADER_SIZE,
+    ELF_MAGIC,
 };
 
-pub(super) fn write_header<W: Write>(builder: &ElfBuilder, mut target: W) -> std::io::Result<()> {
-    let endianness = builder.endianness;
-    let string_table_index = builder.sections.len() - 1;
+pub(super) fn write_header<W: Write>(
+    builder: &ElfBuilder,
+    layout: &Layout,
+    target: W,
+) -> std::io::Result<()> {
+    let mut writer = builder.writer(target);
 
-    target.write_all(ELF_MAGIC)?;
-    target.write_all(&[2])?; // 64-bit
-    target.write_all(match builder.endianness {
-        Endianness::Little => &[1],
-        Endianness::Big => &[2],
+    writer.write_bytes(ELF_MAGIC)?;
+    writer.write_u8(2)?; // 64-bit
+    writer.write_u8(match builder.endianness {
+        Endianness::Little => 1,
+        Endianness::Big => 2,
     })?;
-    target.write_all(&[1])?; // elf version 1
-    target.write_all(&[0, 0, 0, 0, 0, 0, 0, 0, 0])?; // padding
-
-    target.write_all(&endianness.u16_to_bytes(builder.kind.to_u16().unwrap()))?;
-    target.write_all(&endianness.u16_to_bytes(builder.machine.to_u16().unwrap()))?;
-    target.write_all(&endianness.u32_to_bytes(1))?; // elf version 1
-    target.write_all(&endianness.u64_to_bytes(builder.entrypoint))?;
-    target.write_all(&if builder.segments.is_empty() {
-        [0, 0, 0, 0, 0, 0, 0, 0]
-    } else {
-        endianness.u64_to_bytes(ELF64_HEADER_SIZE.into())
-    })?; // program headers right after the header if there are segments, 0 otherwise
-    target.write_all(
-        &endianness.u64_to_bytes(
-            u64::try_from(
-                builder
-                    .sections
-                    .iter()
-                    .map(|section| section.data.len())
-                    .sum::<usize>()
-                    + usize::from(ELF64_HEADER_SIZE)
-                    + usize::from(ELF64_PROGRAM_HEADER_SIZE) * builder.segments.len(),
-            )
-            .unwrap(),
-        ),
-    )?; // section header table offset
-    target.write_all(&[0, 0, 0, 0])?; // empty flags
-    target.write_all(&endianness.u16_to_bytes(ELF64_HEADER_SIZE))?;
-    target.write_all(&endianness.u16_to_bytes(ELF64_PROGRAM_HEADER_SIZE))?;
-    target.write_all(&endianness.u16_to_bytes(builder.segments.len().try_into().unwrap()))?;
-    target.write_all(&endianness.u16_to_bytes(ELF64_SECTION_HEADER_SIZE))?;
-    target.write_all(&endianness.u16_to_bytes(builder.sections.len().try_into().unwrap()))?;
-    target.write_all(&endianness.u16_to_bytes(u16::try_from(string_table_index).unwrap()))?;
+    writer.write_u8(builder.ei_version)?;
+    writer.write_bytes(&[builder.osabi.to_u8().unwrap(), builder.abiversion])?;
+    writer.write_bytes(&builder.ident_padding)?;
+
+    writer.write_half(builder.kind.to_u16().unwrap())?;
+    writer.write_half(builder.machine.to_u16().unwrap())?;
+    writer.write_u32(builder.version)?;
+    writer.write_addr(builder.entrypoint, "e_entry")?;
+    writer.write_addr(layout.program_headers, "e_phoff")?;
+    writer.write_addr(layout.section_headers, "e_shoff")?;
+    writer.write_u32(builder.flags)?;
+    writer.write_half(ELF64_HEADER_SIZE)?;
+    writer.write_half(ELF64_PROGRAM_HEADER_SIZE)?;
+    writer.write_half(builder.program_header_count().try_into().unwrap())?;
+    writer.write_half(ELF64_SECTION_HEADER_SIZE)?;
+    writer.write_half(builder.shnum())?;
+    writer.write_half(builder.shstrndx())?;
 
     Ok(())
 }
 
-pub(super) fn write_phdrs<W: Write>(builder: &ElfBuilder, mut target: W) -> std::io::Result<()> {
-    let endianness = builder.endianness;
-
-    let init_offset = u64::from(ELF64_HEADER_SIZE)
-        + u64::from(ELF64_PROGRAM_HEADER_SIZE) * u64::try_from(builder.segments.len()).unwrap();
-    let sections = builder
-        .sections
-        .iter()
-        .scan(init_offset, |state, section| {
-            let offset = *state;
-            *state += u64::try_from(section.data.len()).unwrap();
-            Some((offset, section))
-        })
-        .collect::<Vec<_>>(); // create a Vec of (offset, section)
+pub(super) fn write_phdrs<W: Write>(
+    builder: &ElfBuilder,
+    layout: &Layout,
+    target: W,
+) -> std::io::Result<()> {
+    let mut writer = builder.writer(target);
+
     let mut segments = builder.segments.iter().collect::<Vec<_>>();
-    segments.sort_by(|a, b| a.vaddr.cmp(&b.vaddr));
+    // the interpreter must come before any loadable segment
+    segments.sort_by_key(|segment| (segment.kind != SegmentKind::Interp, segment.vaddr));
+
+    if let Some((flags, vaddr)) = builder.phdr_segment {
+        let size = u64::from(ELF64_PROGRAM_HEADER_SIZE)
+            * u64::try_from(builder.program_header_count()).unwrap();
+
+        writer.write_u32(SegmentKind::Phdr.to_u32().unwrap())?;
+        writer.write_u32(flags.bits())?;
+        writer.write_addr(layout.program_headers, "p_offset")?;
+        writer.write_addr(vaddr, "p_vaddr")?;
+        writer.write_addr(vaddr, "p_paddr")?;
+        writer.write_addr(size, "p_filesz")?;
+        writer.write_addr(size, "p_memsz")?;
+        writer.write_addr(8, "p_align")?;
+    }
 
     for segment in &segments {
-        target.write_all(&endianness.u32_to_bytes(segment.kind.to_u32().unwrap()))?;
-        target.write_all(&endianness.u32_to_bytes(segment.flags.bits()))?;
+        writer.write_u32(segment.kind.to_u32().unwrap())?;
+        writer.write_u32(segment.flags.bits())?;
 
-        target.write_all(
-            &endianness
-                .u64_to_bytes(sections[usize::from(builder.section_index(segment.section))].0),
-        )?;
-        target.write_all(&endianness.u64_to_bytes(segment.vaddr))?;
-        target.write_all(&endianness.u64_to_bytes(segment.paddr))?;
-        target.write_all(&endianness.u64_to_bytes(segment.filesz))?;
-        target.write_all(&endianness.u64_to_bytes(segment.memsz))?;
-        target.write_all(&endianness.u64_to_bytes(segment.align))?;
+        let (offset, filesz) = builder.segment_range(segment, &layout.sections)?;
+
+        writer.write_addr(offset, "p_offset")?;
+        writer.write_addr(segment.vaddr, "p_vaddr")?;
+        writer.write_addr(segment.paddr, "p_paddr")?;
+        writer.write_addr(filesz, "p_filesz")?;
+        writer.write_addr(segment.memsz, "p_memsz")?;
+        writer.write_addr(segment.align, "p_align")?;
     }
 
     Ok(())
@@ -92,44 +87,32 @@ pub(super) fn write_phdrs<W: Write>(builder: &ElfBuilder, mut target: W) -> std:
 
 pub(super) fn write_section_headers<W: Write>(
     builder: &ElfBuilder,
-    mut target: W,
+    layout: &Layout,
+    target: W,
 ) -> std::io::Result<()> {
-    let endianness = builder.endianness;
-    let mut offset = u64::from(ELF64_HEADER_SIZE)
-        + u64::from(ELF64_PROGRAM_HEADER_SIZE) * u64::try_from(builder.segments.len()).unwrap();
-    for section in &builder.sections {
-        target.write_all(&endianness.u32_to_bytes(section.name.try_into().unwrap()))?;
-        target.write_all(&endianness.u32_to_bytes(section.kind.to_u32().unwrap()))?;
-        target.write_all(&endianness.u64_to_bytes(section.flags.bits().into()))?;
-        target.write_all(&endianness.u64_to_bytes(section.vaddr))?;
-        target.write_all(
-            &endianness.u64_to_bytes(if section.kind == SectionKind::Null {
+    let mut writer = builder.writer(target);
+
+    for (index, (section, &offset)) in builder.sections.iter().zip(&layout.sections).enumerate() {
+        let (size, link) = builder.section_size_and_link(index, section);
+
+        writer.write_u32(section.name.try_into().unwrap())?;
+        writer.write_u32(section.kind.to_u32().unwrap())?;
+        writer.write_addr(section.raw_flags(), "sh_flags")?;
+        writer.write_addr(section.vaddr, "sh_addr")?;
+        writer.write_addr(
+            if section.kind == SectionKind::Null {
                 0
             } else {
                 offset
-            }),
+            },
+            "sh_offset",
         )?;
-        target.write_all(&endianness.u64_to_bytes(section.data.len().try_into().unwrap()))?;
-
-        let link = match section.kind {
-            SectionKind::SymbolTable => builder
-                .section_index(builder.find_section(".strtab").unwrap())
-                .into(),
-            SectionKind::Rela => builder
-                .section_index(builder.find_section(".symtab").unwrap())
-                .into(),
-            SectionKind::Rel => builder
-                .section_index(builder.find_section(".symtab").unwrap())
-                .into(),
-            _ => 0,
-        };
-
-        target.write_all(&endianness.u32_to_bytes(link))?;
-        target.write_all(&endianness.u32_to_bytes(section.info))?;
-        target.write_all(&endianness.u64_to_bytes(section.alignment))?;
-        target.write_all(&endianness.u64_to_bytes(section.entsize))?;
-
-        offset += u64::try_from(section.data.len()).unwrap();
+        writer.write_addr(size, "sh_size")?;
+
+        writer.write_u32(link)?;
+        writer.write_u32(section.info)?;
+        writer.write_addr(section.alignment, "sh_addralign")?;
+        writer.write_addr(section.entsize, "sh_entsize")?;
     }
 
     Ok(())
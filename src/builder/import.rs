@@ -0,0 +1,345 @@
+use std::borrow::Cow;
+
+use crate::{
+    consts::{SHN_LORESERVE, SHN_XINDEX},
+    reader::{self, ElfValue, Strings},
+    ElfReader, ParseError, SectionKind, SegmentKind,
+};
+
+use super::{
+    ElfBuilder, RelEntry, RelaEntry, RelocationTable, Section, SectionId, SectionIdInner, Segment,
+};
+
+pub(super) fn from_reader<'data>(
+    reader: &ElfReader<'data>,
+) -> Result<ElfBuilder<'data>, ParseError> {
+    let header = reader.header()?;
+    let mut builder = ElfBuilder::new(
+        known(header.kind(), "e_type")?,
+        known(header.machine(), "e_machine")?,
+        reader.is_64bit(),
+        reader.endianness(),
+    );
+
+    // values read from a file of the same class always fit, so they need no checking
+    builder.entrypoint = header.entry();
+    builder.flags = header.flags();
+    builder.osabi = known(header.osabi(), "ei_osabi")?;
+    builder.abiversion = header.abiversion();
+    builder.ident_padding = header.ident_padding().try_into().unwrap();
+    builder.version = header.version();
+    builder.allow_unusual = true;
+
+    let sections = reader.sections()?;
+    let section_names = reader.strings()?;
+    let count = sections.len();
+
+    let find_section = |kind| {
+        sections
+            .clone()
+            .into_iter()
+            .position(|section| section.kind() == ElfValue::Known(kind))
+    };
+    let symbol_table = find_section(SectionKind::SymbolTable);
+    let dynamic_symbol_table = find_section(SectionKind::DynSym);
+
+    // the symbol tables, their string tables and section indices, and the section name string
+    // table are generated by the builder
+    let mut generated = vec![false; count];
+    generated[0] = true;
+    if let Some(shstrtab) = generated.get_mut(reader.shstrndx()?) {
+        *shstrtab = true;
+    }
+    for index in [symbol_table, dynamic_symbol_table].into_iter().flatten() {
+        generated[index] = true;
+
+        if let Some(link) =
+            generated.get_mut(usize::try_from(sections.get(index).unwrap().link()).unwrap())
+        {
+            *link = true;
+        }
+    }
+    for (index, section) in sections.clone().into_iter().enumerate() {
+        if section.kind() == ElfValue::Known(SectionKind::SymTabShndx)
+            && symbol_table.is_some_and(|table| section.link() == u32::try_from(table).unwrap())
+        {
+            generated[index] = true;
+        }
+    }
+
+    // relocations against the symbol table are turned into relocation tables, so that the symbol
+    // indices are kept up to date
+    let is_relocation_table = |section: &reader::Section| {
+        matches!(
+            section.kind(),
+            ElfValue::Known(SectionKind::Rel | SectionKind::Rela)
+        ) && symbol_table.is_some_and(|index| section.link() == u32::try_from(index).unwrap())
+            && usize::try_from(section.info())
+                .ok()
+                .and_then(|target| generated.get(target))
+                == Some(&false)
+    };
+
+    let mut section_ids = vec![None; count];
+    let mut section_offsets = Vec::new();
+
+    for (index, section) in sections.clone().into_iter().enumerate() {
+        if generated[index] || is_relocation_table(&section) {
+            continue;
+        }
+
+        let kind = known(section.kind(), "sh_type")?;
+        let (data, size) = match kind {
+            SectionKind::Nobits => (Cow::Borrowed(&[][..]), Some(section.size())),
+            _ => (Cow::Borrowed(section.data()?), None),
+        };
+
+        // flags unknown to this crate, such as OS-specific ones, are kept as extra flags
+        let flags = section.known_flags();
+        let extra_flags = match section.flags() {
+            ElfValue::Known(_) => 0,
+            ElfValue::Unknown(bits) => bits & !u64::from(flags.bits()),
+        };
+
+        let name = string(&section_names, section.name(), "sh_name")?;
+        let name = builder.add_string(name);
+
+        let id = builder
+            .add_section(Section {
+                data,
+                name,
+                kind,
+                flags,
+                extra_flags,
+                vaddr: section.addr(),
+                info: section.info(),
+                entsize: section.entsize(),
+                alignment: section.addralign(),
+                size,
+                link: None,
+            })
+            .unwrap();
+
+        section_ids[index] = Some(id);
+        let file_size = match kind {
+            SectionKind::Nobits => 0,
+            _ => section.size(),
+        };
+        section_offsets.push((section.offset(), file_size, id));
+    }
+
+    let mut generated_ids = Vec::new();
+
+    for (index, is_dynamic) in [(symbol_table, false), (dynamic_symbol_table, true)] {
+        let Some(index) = index else {
+            continue;
+        };
+
+        let section = sections.get(index).unwrap();
+        let symbols = section.symbols()?;
+        let strings = symbols.strings()?;
+        let string_table = sections
+            .get(usize::try_from(section.link()).unwrap())
+            .unwrap();
+
+        let (symbol_table_id, string_table_id) = if is_dynamic {
+            (
+                builder.dynamic_symbol_table(),
+                builder.dynamic_string_table(),
+            )
+        } else {
+            (builder.symbol_table(), builder.string_table())
+        };
+
+        section_offsets.push((section.offset(), section.size(), symbol_table_id));
+        section_offsets.push((string_table.offset(), string_table.size(), string_table_id));
+        generated_ids.push((index, symbol_table_id));
+        generated_ids.push((usize::try_from(section.link()).unwrap(), string_table_id));
+
+        for symbol in symbols.into_iter().skip(1) {
+            let name = string(&strings, symbol.name(), "st_name")?;
+            let binding = known(symbol.binding(), "st_info")?;
+            let kind = known(symbol.kind(), "st_info")?;
+            let section = symbol_section_id(&section_ids, &symbol)?;
+
+            if is_dynamic {
+                builder.add_dynamic_symbol(
+                    name,
+                    symbol.value(),
+                    symbol.size(),
+                    binding,
+                    kind,
+                    section,
+                )
+            } else {
+                builder.add_symbol(name, symbol.value(), symbol.size(), binding, kind, section)
+            }
+            .unwrap();
+        }
+    }
+
+    // links are resolved once the IDs of all sections, including the generated ones, are known
+    for (index, id) in generated_ids {
+        section_ids[index] = Some(id);
+    }
+
+    for (index, section) in sections.clone().into_iter().enumerate() {
+        let Some(SectionId {
+            inner: SectionIdInner::Id(id),
+        }) = section_ids[index]
+        else {
+            continue;
+        };
+
+        if section.link() != 0 {
+            builder.sections[usize::try_from(id).unwrap()].link =
+                Some(section_id(&section_ids, section.link()));
+        }
+    }
+
+    for section in sections.clone() {
+        if !is_relocation_table(&section) {
+            continue;
+        }
+
+        let name = string(&section_names, section.name(), "sh_name")?;
+        let target = section_id(&section_ids, section.info());
+        let relocations = section.relocations()?;
+
+        let table = if relocations.is_rela() {
+            let mut table = builder.create_rela_table(name, target);
+
+            for relocation in relocations {
+                // 32-bit addends are stored as their two's complement representation
+                let addend = relocation.addend().unwrap();
+                let addend = match reader.is_64bit() {
+                    true => addend as u64,
+                    false => u64::from(addend as u32),
+                };
+
+                table.add(RelaEntry {
+                    offset: relocation.offset(),
+                    info: relocation.info(),
+                    addend,
+                });
+            }
+
+            RelocationTable::Rela(table)
+        } else {
+            let mut table = builder.create_rel_table(name, target);
+
+            for relocation in relocations {
+                table.add(RelEntry {
+                    offset: relocation.offset(),
+                    info: relocation.info(),
+                });
+            }
+
+            RelocationTable::Rel(table)
+        };
+
+        builder.add_relocation_table(table);
+    }
+
+    for segment in reader.segments()? {
+        // unknown segment types, such as PT_GNU_STACK, can't be represented by the builder
+        let kind = match segment.kind() {
+            ElfValue::Known(SegmentKind::Phdr) | ElfValue::Unknown(_) => continue,
+            ElfValue::Known(kind) => kind,
+        };
+
+        if segment.memsz() < segment.filesz() {
+            return Err(ParseError::InvalidValue("p_memsz"));
+        }
+
+        // segments are tied to the section they start with, preferring non-empty sections
+        let section = section_offsets
+            .iter()
+            .filter(|(offset, _, _)| *offset == segment.offset())
+            .min_by_key(|(_, size, _)| *size == 0)
+            .map(|(_, _, id)| *id);
+
+        let Some(section) = section else {
+            continue;
+        };
+
+        // segments spanning several sections are tied to the last non-empty section they cover
+        let end = segment
+            .offset()
+            .checked_add(segment.filesz())
+            .ok_or(ParseError::InvalidValue("p_filesz"))?;
+        let last_section = section_offsets
+            .iter()
+            .filter(|(offset, size, _)| {
+                *offset > segment.offset() && *size != 0 && offset.checked_add(*size) == Some(end)
+            })
+            .max_by_key(|(offset, _, _)| *offset)
+            .map(|(_, _, id)| *id);
+
+        builder
+            .add_segment(Segment {
+                section,
+                kind,
+                vaddr: segment.vaddr(),
+                paddr: segment.paddr(),
+                filesz: segment.filesz(),
+                memsz: segment.memsz(),
+                flags: known(segment.flags(), "p_flags")?,
+                align: segment.align(),
+                last_section,
+            })
+            .unwrap();
+    }
+
+    Ok(builder)
+}
+
+/// Returns the ID of the section at the specified index in the original file. Sections that
+/// weren't imported are replaced with the null section.
+fn section_id(section_ids: &[Option<SectionId>], index: u32) -> SectionId {
+    usize::try_from(index)
+        .ok()
+        .and_then(|index| section_ids.get(index))
+        .copied()
+        .flatten()
+        .unwrap_or(SectionId {
+            inner: SectionIdInner::Id(0),
+        })
+}
+
+/// Returns the ID of the section a symbol is defined in. Reserved indices, such as `SHN_ABS`, are
+/// kept as is, and indices that don't fit in `st_shndx` are read from the extended section index
+/// table.
+fn symbol_section_id(
+    section_ids: &[Option<SectionId>],
+    symbol: &reader::Symbol,
+) -> Result<SectionId, ParseError> {
+    match symbol.shndx() {
+        shndx @ SHN_LORESERVE.. if shndx != SHN_XINDEX => Ok(SectionId {
+            inner: SectionIdInner::Reserved(shndx),
+        }),
+        _ => Ok(section_id(section_ids, symbol.section_index()?)),
+    }
+}
+
+/// Returns the known value, or an [`ParseError::InvalidValue`] error with the field name if the
+/// value is unknown.
+fn known<K, U>(value: ElfValue<K, U>, field: &'static str) -> Result<K, ParseError> {
+    match value {
+        ElfValue::Known(value) => Ok(value),
+        ElfValue::Unknown(_) => Err(ParseError::InvalidValue(field)),
+    }
+}
+
+/// Returns the string at the specified index, or an error with the field name if it doesn't exist
+/// or isn't valid UTF-8.
+fn string<'data>(
+    strings: &Strings<'data>,
+    index: u32,
+    field: &'static str,
+) -> Result<&'data str, ParseError> {
+    match strings.get_str(index.into()) {
+        Some(Ok(string)) => Ok(string),
+        _ => Err(ParseError::InvalidValue(field)),
+    }
+}
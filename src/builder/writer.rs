@@ -0,0 +1,61 @@
+use std::io::Write;
+
+use crate::Endianness;
+
+use super::word;
+
+/// Writes the fields of ELF structures to a target in the endianness and class of the built file.
+#[derive(Debug)]
+pub(super) struct Writer<W> {
+    target: W,
+    endianness: Endianness,
+    is_64bit: bool,
+}
+
+impl<W: Write> Writer<W> {
+    pub(super) fn new(target: W, endianness: Endianness, is_64bit: bool) -> Self {
+        Self {
+            target,
+            endianness,
+            is_64bit,
+        }
+    }
+
+    /// Whether the file is 64-bit, which determines the size of [`Writer::write_addr`].
+    pub(super) fn is_64bit(&self) -> bool {
+        self.is_64bit
+    }
+
+    pub(super) fn write_bytes(&mut self, bytes: &[u8]) -> std::io::Result<()> {
+        self.target.write_all(bytes)
+    }
+
+    pub(super) fn write_u8(&mut self, value: u8) -> std::io::Result<()> {
+        self.target.write_all(&[value])
+    }
+
+    /// Writes a 16-bit field, `ElfN_Half` in the specification.
+    pub(super) fn write_half(&mut self, value: u16) -> std::io::Result<()> {
+        self.target.write_all(&self.endianness.u16_to_bytes(value))
+    }
+
+    /// Writes a 32-bit field, `ElfN_Word` in the specification.
+    pub(super) fn write_u32(&mut self, value: u32) -> std::io::Result<()> {
+        self.target.write_all(&self.endianness.u32_to_bytes(value))
+    }
+
+    pub(super) fn write_u64(&mut self, value: u64) -> std::io::Result<()> {
+        self.target.write_all(&self.endianness.u64_to_bytes(value))
+    }
+
+    /// Writes a field whose size depends on the class of the file, such as an address, an offset,
+    /// or `ElfN_Xword`: 64 bits in 64-bit files and 32 bits in 32-bit files. Returns a
+    /// [`super::BuildError::ValueTooLarge`] error with the field name if the value doesn't fit.
+    pub(super) fn write_addr(&mut self, value: u64, field: &'static str) -> std::io::Result<()> {
+        if self.is_64bit {
+            self.write_u64(value)
+        } else {
+            self.write_u32(word(value, field)?)
+        }
+    }
+}
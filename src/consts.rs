@@ -1,14 +1,20 @@
-use flagset::flags;
+use alloc::{format, string::String};
+use core::fmt;
+
+use flagset::{flags, FlagSet};
 use num_derive::{FromPrimitive, ToPrimitive};
-use num_traits::ToPrimitive;
+use num_traits::{FromPrimitive, ToPrimitive};
 use phf::phf_map;
 
+use crate::reader::ElfValue;
+
 pub(crate) const ELF_MAGIC: &[u8] = &[0x7f, b'E', b'L', b'F'];
 pub(crate) const EI_CLASS: usize = 4;
 pub(crate) const EI_DATA: usize = 5;
 pub(crate) const EI_VERSION: usize = 6;
 pub(crate) const EI_OSABI: usize = 7;
 pub(crate) const EI_ABIVERSION: usize = 8;
+pub(crate) const EI_PAD: usize = 9;
 pub(crate) const EI_NIDENT: usize = 16;
 
 pub(crate) const ELF32_HEADER_SIZE: u16 = 52;
@@ -17,9 +23,52 @@ pub(crate) const ELF32_SECTION_HEADER_SIZE: u16 = 40;
 pub(crate) const ELF64_SECTION_HEADER_SIZE: u16 = 64;
 pub(crate) const ELF32_PROGRAM_HEADER_SIZE: u16 = 32;
 pub(crate) const ELF64_PROGRAM_HEADER_SIZE: u16 = 56;
+pub(crate) const ELF32_REL_SIZE: u16 = 8;
+pub(crate) const ELF64_REL_SIZE: u16 = 16;
+pub(crate) const ELF32_RELA_SIZE: u16 = 12;
+pub(crate) const ELF64_RELA_SIZE: u16 = 24;
+pub(crate) const ELF32_SYMBOL_SIZE: u16 = 16;
+pub(crate) const ELF64_SYMBOL_SIZE: u16 = 24;
+pub(crate) const ELF32_DYN_SIZE: u16 = 8;
+pub(crate) const ELF64_DYN_SIZE: u16 = 16;
+pub(crate) const ELF32_CHDR_SIZE: usize = 12;
+pub(crate) const ELF64_CHDR_SIZE: usize = 24;
+/// The lowest reserved section index. Section indices from here on don't fit in `e_shnum`,
+/// `e_shstrndx`, or `st_shndx`, and are stored elsewhere.
+#[cfg(feature = "std")]
+pub(crate) const SHN_LORESERVE: u16 = 0xff00;
+/// The `st_shndx` of absolute symbols, whose values don't change with relocation
+#[cfg(feature = "std")]
+pub(crate) const SHN_ABS: u16 = 0xfff1;
+/// The `st_shndx` of common symbols, which are allocated by the linker
+#[cfg(feature = "std")]
+pub(crate) const SHN_COMMON: u16 = 0xfff2;
+/// The value of `e_shstrndx` or `st_shndx` indicating that the real index is stored elsewhere: in
+/// `sh_link` of section 0, or in the [`crate::SectionKind::SymTabShndx`] section of the symbol
+/// table
+pub(crate) const SHN_XINDEX: u16 = 0xffff;
+/// The version index of local symbols, which have no version
+pub(crate) const VER_NDX_LOCAL: u16 = 0;
+/// The version index of global symbols of the base version, the version of the file itself
+pub(crate) const VER_NDX_GLOBAL: u16 = 1;
+/// The bit of a version index marking the version as hidden
+pub(crate) const VERSYM_HIDDEN: u16 = 0x8000;
+/// The value of `e_phnum` indicating that the real count is in `sh_info` of section 0
+pub(crate) const PN_XNUM: u16 = 0xffff;
+/// The segment type of `PT_GNU_EH_FRAME`, which contains the `.eh_frame_hdr` section
+pub(crate) const PT_GNU_EH_FRAME: u32 = 0x6474_e550;
+/// The type of a `GNU` note containing the ABI tag
+pub(crate) const NT_GNU_ABI_TAG: u32 = 1;
+/// The type of a `GNU` note containing the build ID
+pub(crate) const NT_GNU_BUILD_ID: u32 = 3;
+/// The type of a `CORE` note containing the status of a thread
+pub(crate) const NT_PRSTATUS: u32 = 1;
+/// The type of a `CORE` note containing the auxiliary vector
+pub(crate) const NT_AUXV: u32 = 6;
 
 flags! {
     /// ELF section flag. Directly corresponds to the sh_flags field.
+    #[cfg_attr(feature = "serde", derive(serde::Serialize))]
     pub enum SectionFlag: u32 {
         /// The data is writable by the program.
         Write = 0x01,
@@ -47,6 +96,7 @@ flags! {
     }
 
     /// Permission a segment is loaded with
+    #[cfg_attr(feature = "serde", derive(serde::Serialize))]
     pub enum SegmentFlag: u32 {
         /// The segment's contents can be executed as instructions.
         Execute,
@@ -55,10 +105,19 @@ flags! {
         /// The segment's contents can be read by the program.
         Read,
     }
+
+    /// Flag of a section group, stored in the first word of an `SHT_GROUP` section
+    #[cfg_attr(feature = "serde", derive(serde::Serialize))]
+    pub enum GroupFlag: u32 {
+        /// The group is a COMDAT group. Only one group with the same signature is kept by the
+        /// linker. `GRP_COMDAT` in the specification.
+        Comdat = 0x1,
+    }
 }
 
 /// ELF file type
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, FromPrimitive, ToPrimitive)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub enum ElfKind {
     /// No file type
     None,
@@ -72,9 +131,23 @@ pub enum ElfKind {
     Core,
 }
 
+impl fmt::Display for ElfKind {
+    /// Formats the file type like `readelf`, such as `DYN (Shared object file)`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.pad(match self {
+            ElfKind::None => "NONE (None)",
+            ElfKind::Relocatable => "REL (Relocatable file)",
+            ElfKind::Executable => "EXEC (Executable file)",
+            ElfKind::Dynamic => "DYN (Shared object file)",
+            ElfKind::Core => "CORE (Core file)",
+        })
+    }
+}
+
 /// Represents the endianness of a system, i.e. the order in which order bytes of an integer are
 /// stored.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub enum Endianness {
     /// Little endian. Least significant byte is stored first.
     Little,
@@ -134,6 +207,7 @@ impl Endianness {
 
 /// ELF segment type
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, FromPrimitive, ToPrimitive)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub enum SegmentKind {
     /// Unused entry
     Null,
@@ -153,8 +227,33 @@ pub enum SegmentKind {
     Tls,
 }
 
+impl SegmentKind {
+    /// Interprets a raw `p_type` value the way the reader does, returning
+    /// [`ElfValue::Unknown`] for values that aren't a known segment type.
+    pub fn from_raw(value: u32) -> ElfValue<Self, u32> {
+        Self::from_u32(value).map_or(ElfValue::Unknown(value), ElfValue::Known)
+    }
+}
+
+impl fmt::Display for SegmentKind {
+    /// Formats the segment type like `readelf`, such as `LOAD`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.pad(match self {
+            SegmentKind::Null => "NULL",
+            SegmentKind::Load => "LOAD",
+            SegmentKind::Dynamic => "DYNAMIC",
+            SegmentKind::Interp => "INTERP",
+            SegmentKind::Note => "NOTE",
+            SegmentKind::Shlib => "SHLIB",
+            SegmentKind::Phdr => "PHDR",
+            SegmentKind::Tls => "TLS",
+        })
+    }
+}
+
 /// ELF section type
 #[derive(Debug, Clone, Copy, PartialEq, Eq, FromPrimitive, ToPrimitive)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub enum SectionKind {
     /// Inactive
     Null = 0,
@@ -190,10 +289,311 @@ pub enum SectionKind {
     Group = 17,
     /// Contains section header indices for a symbol table
     SymTabShndx = 18,
+    /// Relative relocations in the packed RELR format
+    Relr = 19,
+    /// GNU-style hash table
+    GnuHash = 0x6ffffff6,
+    /// Symbol versions defined by the file, `SHT_GNU_verdef`
+    GnuVerDef = 0x6ffffffd,
+    /// Symbol versions required from other files, `SHT_GNU_verneed`
+    GnuVerNeed = 0x6ffffffe,
+    /// The version index of every symbol in the dynamic symbol table, `SHT_GNU_versym`
+    GnuVerSym = 0x6fffffff,
+}
+
+impl SectionKind {
+    /// Interprets a raw `sh_type` value the way the reader does, returning
+    /// [`ElfValue::Unknown`] for values that aren't a known section type, including
+    /// processor-specific ones.
+    pub fn from_raw(value: u32) -> ElfValue<Self, u32> {
+        Self::from_u32(value).map_or(ElfValue::Unknown(value), ElfValue::Known)
+    }
+}
+
+impl fmt::Display for SectionKind {
+    /// Formats the section type like `readelf`, such as `PROGBITS`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.pad(match self {
+            SectionKind::Null => "NULL",
+            SectionKind::Progbits => "PROGBITS",
+            SectionKind::SymbolTable => "SYMTAB",
+            SectionKind::StringTable => "STRTAB",
+            SectionKind::Rela => "RELA",
+            SectionKind::Hash => "HASH",
+            SectionKind::Dynamic => "DYNAMIC",
+            SectionKind::Note => "NOTE",
+            SectionKind::Nobits => "NOBITS",
+            SectionKind::Rel => "REL",
+            SectionKind::Shlib => "SHLIB",
+            SectionKind::DynSym => "DYNSYM",
+            SectionKind::InitArray => "INIT_ARRAY",
+            SectionKind::FiniArray => "FINI_ARRAY",
+            SectionKind::PreinitArray => "PREINIT_ARRAY",
+            SectionKind::Group => "GROUP",
+            SectionKind::SymTabShndx => "SYMTAB SECTION INDICES",
+            SectionKind::Relr => "RELR",
+            SectionKind::GnuHash => "GNU_HASH",
+            SectionKind::GnuVerDef => "VERDEF",
+            SectionKind::GnuVerNeed => "VERNEED",
+            SectionKind::GnuVerSym => "VERSYM",
+        })
+    }
+}
+
+/// A processor-specific section type, between `SHT_LOPROC` (`0x70000000`) and `SHT_HIPROC`
+/// (`0x7fffffff`). The meaning of a section type number in this range depends on the target
+/// architecture of the ELF file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[non_exhaustive]
+pub enum ProcessorSectionKind {
+    /// ARM section type
+    Arm(ArmSectionKind),
+    /// MIPS section type
+    Mips(MipsSectionKind),
+    /// x86-64 section type
+    X86_64(X86_64SectionKind),
+    /// RISC-V section type
+    RiscV(RiscvSectionKind),
+}
+
+impl ProcessorSectionKind {
+    /// Interprets a section type number for the given machine. Returns [`None`] if the machine's
+    /// section types are not known or the type number is not recognized.
+    pub fn from_machine(machine: MachineKind, value: u32) -> Option<Self> {
+        match machine {
+            MachineKind::Arm => ArmSectionKind::from_u32(value).map(ProcessorSectionKind::Arm),
+            MachineKind::Mips | MachineKind::MipsRs3Le => {
+                MipsSectionKind::from_u32(value).map(ProcessorSectionKind::Mips)
+            }
+            MachineKind::X86_64 => {
+                X86_64SectionKind::from_u32(value).map(ProcessorSectionKind::X86_64)
+            }
+            MachineKind::RiscV => {
+                RiscvSectionKind::from_u32(value).map(ProcessorSectionKind::RiscV)
+            }
+            _ => None,
+        }
+    }
+}
+
+impl ToPrimitive for ProcessorSectionKind {
+    fn to_i64(&self) -> Option<i64> {
+        match self {
+            ProcessorSectionKind::Arm(kind) => kind.to_i64(),
+            ProcessorSectionKind::Mips(kind) => kind.to_i64(),
+            ProcessorSectionKind::X86_64(kind) => kind.to_i64(),
+            ProcessorSectionKind::RiscV(kind) => kind.to_i64(),
+        }
+    }
+
+    fn to_u64(&self) -> Option<u64> {
+        match self {
+            ProcessorSectionKind::Arm(kind) => kind.to_u64(),
+            ProcessorSectionKind::Mips(kind) => kind.to_u64(),
+            ProcessorSectionKind::X86_64(kind) => kind.to_u64(),
+            ProcessorSectionKind::RiscV(kind) => kind.to_u64(),
+        }
+    }
+}
+
+impl fmt::Display for ProcessorSectionKind {
+    /// Formats the section type like `readelf`, such as `ARM_EXIDX`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.pad(match self {
+            ProcessorSectionKind::Arm(kind) => match kind {
+                ArmSectionKind::Exidx => "ARM_EXIDX",
+                ArmSectionKind::PreemptMap => "ARM_PREEMPTMAP",
+                ArmSectionKind::Attributes => "ARM_ATTRIBUTES",
+                ArmSectionKind::DebugOverlay => "ARM_DEBUGOVERLAY",
+                ArmSectionKind::OverlaySection => "ARM_OVERLAYSECTION",
+            },
+            ProcessorSectionKind::Mips(kind) => match kind {
+                MipsSectionKind::Liblist => "MIPS_LIBLIST",
+                MipsSectionKind::Msym => "MIPS_MSYM",
+                MipsSectionKind::Conflict => "MIPS_CONFLICT",
+                MipsSectionKind::Gptab => "MIPS_GPTAB",
+                MipsSectionKind::Ucode => "MIPS_UCODE",
+                MipsSectionKind::Debug => "MIPS_DEBUG",
+                MipsSectionKind::Reginfo => "MIPS_REGINFO",
+                MipsSectionKind::Options => "MIPS_OPTIONS",
+                MipsSectionKind::Dwarf => "MIPS_DWARF",
+                MipsSectionKind::AbiFlags => "MIPS_ABIFLAGS",
+            },
+            ProcessorSectionKind::X86_64(kind) => match kind {
+                X86_64SectionKind::Unwind => "X86_64_UNWIND",
+            },
+            ProcessorSectionKind::RiscV(kind) => match kind {
+                RiscvSectionKind::Attributes => "RISCV_ATTRIBUTES",
+            },
+        })
+    }
+}
+
+/// ARM section type
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, FromPrimitive, ToPrimitive)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[non_exhaustive]
+pub enum ArmSectionKind {
+    /// Exception index table, `.ARM.exidx`
+    Exidx = 0x70000001,
+    /// BPABI DLL dynamic linking pre-emption map
+    PreemptMap = 0x70000002,
+    /// Object file compatibility attributes, `.ARM.attributes`
+    Attributes = 0x70000003,
+    /// Debugging information for overlays
+    DebugOverlay = 0x70000004,
+    /// Overlay table
+    OverlaySection = 0x70000005,
+}
+
+/// MIPS section type
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, FromPrimitive, ToPrimitive)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[non_exhaustive]
+pub enum MipsSectionKind {
+    /// Shared objects used in Quickstart linking
+    Liblist = 0x70000000,
+    /// Symbol table for the `.msym` section
+    Msym = 0x70000001,
+    /// Symbols conflicting with symbols defined in shared objects
+    Conflict = 0x70000002,
+    /// Global data area sizes, `.gptab`
+    Gptab = 0x70000003,
+    /// Reserved for the use of the ucode compiler
+    Ucode = 0x70000004,
+    /// Debugging information in the mdebug format, `.mdebug`
+    Debug = 0x70000005,
+    /// Register usage information, `.reginfo`
+    Reginfo = 0x70000006,
+    /// Miscellaneous options, `.MIPS.options`
+    Options = 0x7000000d,
+    /// DWARF debugging information
+    Dwarf = 0x7000001e,
+    /// ABI flags, `.MIPS.abiflags`
+    AbiFlags = 0x7000002a,
+}
+
+/// x86-64 section type
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, FromPrimitive, ToPrimitive)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[non_exhaustive]
+pub enum X86_64SectionKind {
+    /// Unwind information, such as `.eh_frame`
+    Unwind = 0x70000001,
+}
+
+/// RISC-V section type
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, FromPrimitive, ToPrimitive)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[non_exhaustive]
+pub enum RiscvSectionKind {
+    /// Object file compatibility attributes, `.riscv.attributes`
+    Attributes = 0x70000003,
+}
+
+/// Dynamic array tag, the type of an entry in the dynamic section
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, FromPrimitive, ToPrimitive)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[non_exhaustive]
+pub enum DynTag {
+    /// Marks the end of the dynamic array
+    Null = 0,
+    /// String table offset of the name of a needed library
+    Needed = 1,
+    /// Total size of the relocation entries associated with the procedure linkage table
+    PltRelSz = 2,
+    /// Address associated with the procedure linkage table or the global offset table
+    PltGot = 3,
+    /// Address of the symbol hash table
+    Hash = 4,
+    /// Address of the dynamic string table
+    StrTab = 5,
+    /// Address of the dynamic symbol table
+    SymTab = 6,
+    /// Address of the Rela relocation table
+    Rela = 7,
+    /// Total size of the Rela relocation table
+    RelaSz = 8,
+    /// Size of a Rela relocation entry
+    RelaEnt = 9,
+    /// Total size of the dynamic string table
+    StrSz = 10,
+    /// Size of a symbol table entry
+    SymEnt = 11,
+    /// Address of the initialization function
+    Init = 12,
+    /// Address of the termination function
+    Fini = 13,
+    /// String table offset of the name of the shared object
+    SoName = 14,
+    /// String table offset of the library search path, deprecated
+    RPath = 15,
+    /// Symbol resolution starts from the shared object itself
+    Symbolic = 16,
+    /// Address of the Rel relocation table
+    Rel = 17,
+    /// Total size of the Rel relocation table
+    RelSz = 18,
+    /// Size of a Rel relocation entry
+    RelEnt = 19,
+    /// Type of relocation used by the procedure linkage table
+    PltRel = 20,
+    /// Used for debugging, contents not specified
+    Debug = 21,
+    /// Relocations may modify non-writable segments
+    TextRel = 22,
+    /// Address of the relocation entries associated with the procedure linkage table
+    JmpRel = 23,
+    /// All relocations must be processed before returning control to the program
+    BindNow = 24,
+    /// Address of the array of pointers to initialization functions
+    InitArray = 25,
+    /// Address of the array of pointers to termination functions
+    FiniArray = 26,
+    /// Size of the array of pointers to initialization functions
+    InitArraySz = 27,
+    /// Size of the array of pointers to termination functions
+    FiniArraySz = 28,
+    /// String table offset of the library search path
+    RunPath = 29,
+    /// Flags for the object being loaded
+    Flags = 30,
+    /// Address of the array of pointers to pre-initialization functions
+    PreinitArray = 32,
+    /// Size of the array of pointers to pre-initialization functions
+    PreinitArraySz = 33,
+    /// Address of the `SHT_SYMTAB_SHNDX` section associated with the dynamic symbol table
+    SymTabShndx = 34,
+    /// Total size of the packed RELR relative relocations
+    RelrSz = 35,
+    /// Address of the packed RELR relative relocations
+    Relr = 36,
+    /// Size of an entry of the packed RELR relative relocations
+    RelrEnt = 37,
+    /// Address of the GNU-style symbol hash table
+    GnuHash = 0x6ffffef5,
+    /// Address of the symbol version table
+    VerSym = 0x6ffffff0,
+    /// Number of relative Rela relocations
+    RelaCount = 0x6ffffff9,
+    /// Number of relative Rel relocations
+    RelCount = 0x6ffffffa,
+    /// State flags
+    Flags1 = 0x6ffffffb,
+    /// Address of the version definition table
+    VerDef = 0x6ffffffc,
+    /// Number of version definitions
+    VerDefNum = 0x6ffffffd,
+    /// Address of the version dependency table
+    VerNeed = 0x6ffffffe,
+    /// Number of version dependencies
+    VerNeedNum = 0x6fffffff,
 }
 
 /// ELF symbol type
 #[derive(Debug, Clone, Copy, PartialEq, Eq, FromPrimitive, ToPrimitive)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub enum SymbolKind {
     /// Unspecefied type
     NoType = 0,
@@ -211,8 +611,155 @@ pub enum SymbolKind {
     Tls = 6,
 }
 
+impl fmt::Display for SymbolKind {
+    /// Formats the symbol type like `readelf`, such as `FUNC`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.pad(match self {
+            SymbolKind::NoType => "NOTYPE",
+            SymbolKind::Object => "OBJECT",
+            SymbolKind::Func => "FUNC",
+            SymbolKind::Section => "SECTION",
+            SymbolKind::File => "FILE",
+            SymbolKind::Common => "COMMON",
+            SymbolKind::Tls => "TLS",
+        })
+    }
+}
+
+/// The algorithm used to compress the data of a section with the [`SectionFlag::Compressed`] flag
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, FromPrimitive, ToPrimitive)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[non_exhaustive]
+pub enum CompressionKind {
+    /// zlib-compressed DEFLATE data
+    Zlib = 1,
+    /// Zstandard-compressed data
+    Zstd = 2,
+}
+
+/// The type of a note in a core file, stored in the [`SegmentKind::Note`] segments with the owner
+/// name `CORE` or `LINUX`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, FromPrimitive, ToPrimitive)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[non_exhaustive]
+pub enum CoreNoteKind {
+    /// Process status, including the general-purpose registers of a thread (`NT_PRSTATUS`)
+    PrStatus = 1,
+    /// Floating-point registers of a thread (`NT_PRFPREG`)
+    PrFpReg = 2,
+    /// Process information, such as the command line (`NT_PRPSINFO`)
+    PrPsInfo = 3,
+    /// The kernel's task structure (`NT_TASKSTRUCT`)
+    TaskStruct = 4,
+    /// The auxiliary vector of the process (`NT_AUXV`)
+    Auxv = 6,
+    /// Extended x86 state saved by `XSAVE` (`NT_X86_XSTATE`)
+    X86Xstate = 0x202,
+    /// The signal information of the signal that caused the dump (`NT_SIGINFO`)
+    SigInfo = 0x5349_4749,
+    /// The files mapped into the address space of the process (`NT_FILE`)
+    File = 0x4649_4c45,
+    /// Extended floating-point registers of an x86 thread (`NT_PRXFPREG`)
+    PrxFpReg = 0x46e6_2b7f,
+}
+
+/// The type of an entry in the auxiliary vector passed to a process by the kernel, stored in core
+/// files in an `NT_AUXV` note
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, FromPrimitive, ToPrimitive)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[non_exhaustive]
+pub enum AuxType {
+    /// The end of the vector (`AT_NULL`)
+    Null = 0,
+    /// The entry should be ignored (`AT_IGNORE`)
+    Ignore = 1,
+    /// The file descriptor of the program (`AT_EXECFD`)
+    ExecFd = 2,
+    /// The address of the program headers of the program (`AT_PHDR`)
+    Phdr = 3,
+    /// The size of a program header entry (`AT_PHENT`)
+    Phent = 4,
+    /// The number of program headers (`AT_PHNUM`)
+    Phnum = 5,
+    /// The system page size (`AT_PAGESZ`)
+    PageSz = 6,
+    /// The base address of the program interpreter (`AT_BASE`)
+    Base = 7,
+    /// Flags (`AT_FLAGS`)
+    Flags = 8,
+    /// The entry point of the program (`AT_ENTRY`)
+    Entry = 9,
+    /// The program is not ELF (`AT_NOTELF`)
+    NotElf = 10,
+    /// The real user ID (`AT_UID`)
+    Uid = 11,
+    /// The effective user ID (`AT_EUID`)
+    Euid = 12,
+    /// The real group ID (`AT_GID`)
+    Gid = 13,
+    /// The effective group ID (`AT_EGID`)
+    Egid = 14,
+    /// The address of a string identifying the CPU (`AT_PLATFORM`)
+    Platform = 15,
+    /// Architecture-specific hints about the capabilities of the CPU (`AT_HWCAP`)
+    Hwcap = 16,
+    /// The frequency of `times()` (`AT_CLKTCK`)
+    ClkTck = 17,
+    /// Whether the program is run in secure mode, such as setuid (`AT_SECURE`)
+    Secure = 23,
+    /// The address of a string identifying the real platform (`AT_BASE_PLATFORM`)
+    BasePlatform = 24,
+    /// The address of 16 random bytes (`AT_RANDOM`)
+    Random = 25,
+    /// Further architecture-specific hints about the capabilities of the CPU (`AT_HWCAP2`)
+    Hwcap2 = 26,
+    /// The address of the path of the program (`AT_EXECFN`)
+    ExecFn = 31,
+    /// The address of the vDSO (`AT_SYSINFO_EHDR`)
+    SysinfoEhdr = 33,
+    /// The minimal stack size for signal delivery (`AT_MINSIGSTKSZ`)
+    MinSigStkSz = 51,
+}
+
+/// ELF symbol binding, which determines the linkage visibility and behavior of a symbol
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, FromPrimitive, ToPrimitive)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub enum SymbolBinding {
+    /// Not visible outside the object file containing the symbol's definition
+    Local = 0,
+    /// Visible to all object files being combined
+    Global = 1,
+    /// Like global, but with lower precedence
+    Weak = 2,
+}
+
+impl fmt::Display for SymbolBinding {
+    /// Formats the symbol binding like `readelf`, such as `GLOBAL`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.pad(match self {
+            SymbolBinding::Local => "LOCAL",
+            SymbolBinding::Global => "GLOBAL",
+            SymbolBinding::Weak => "WEAK",
+        })
+    }
+}
+
+impl From<bool> for SymbolBinding {
+    /// Converts the boolean previously used by
+    /// [`ElfBuilder::add_symbol`](crate::ElfBuilder::add_symbol) into a binding: `true` is
+    /// [`SymbolBinding::Global`] and `false` is [`SymbolBinding::Local`].
+    fn from(global: bool) -> Self {
+        if global {
+            SymbolBinding::Global
+        } else {
+            SymbolBinding::Local
+        }
+    }
+}
+
 /// Operating system or ABI of an ELF file. Determines which ELF extensions are used by the file.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, FromPrimitive, ToPrimitive)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 #[non_exhaustive]
 pub enum OsAbi {
     /// No extensions or unspecified
@@ -251,8 +798,34 @@ pub enum OsAbi {
     OpenVos = 18,
 }
 
+impl fmt::Display for OsAbi {
+    /// Formats the OS/ABI like `readelf`, such as `UNIX - System V`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.pad(match self {
+            OsAbi::None => "UNIX - System V",
+            OsAbi::HpUx => "UNIX - HP-UX",
+            OsAbi::NetBsd => "UNIX - NetBSD",
+            OsAbi::Gnu => "UNIX - GNU",
+            OsAbi::Solaris => "UNIX - Solaris",
+            OsAbi::Aix => "UNIX - AIX",
+            OsAbi::Irix => "UNIX - IRIX",
+            OsAbi::FreeBsd => "UNIX - FreeBSD",
+            OsAbi::Tru64 => "UNIX - TRU64",
+            OsAbi::Modesto => "Novell - Modesto",
+            OsAbi::OpenBsd => "UNIX - OpenBSD",
+            OsAbi::OpenVms => "VMS - OpenVMS",
+            OsAbi::Nsk => "HP - Non-Stop Kernel",
+            OsAbi::Aros => "AROS",
+            OsAbi::FenixOs => "FenixOS",
+            OsAbi::CloudAbi => "Nuxi CloudABI",
+            OsAbi::OpenVos => "Stratus Technologies OpenVOS",
+        })
+    }
+}
+
 /// The target architecture of an ELF file.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, FromPrimitive, ToPrimitive)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 #[non_exhaustive]
 pub enum MachineKind {
     /// No machine
@@ -640,12 +1213,65 @@ pub enum MachineKind {
 }
 
 impl MachineKind {
-    /// Returns the human-readable name of the machine
+    /// Returns the human-readable name of the machine, or `"unknown"` if it has no name.
     pub fn name(&self) -> &'static str {
-        MACHINE_NAMES.get(&self.to_u16().unwrap()).unwrap()
+        machine_name(self.to_u16().unwrap()).unwrap_or("unknown")
+    }
+
+    /// Finds a machine by name, or returns [`None`] if the name is not recognized. The name is
+    /// compared case-insensitively, ignoring `-`, `_`, and spaces, against
+    /// * the name of the variant, such as `X86_64`, `x86-64`, `RISC-V`, or `aarch64`,
+    /// * the human-readable name returned by [`MachineKind::name`], such as `Intel 80386`, and
+    /// * the common aliases `amd64`, `x64`, `i386`, `x86`, `arm64`, and `riscv64`.
+    pub fn from_name(name: &str) -> Option<MachineKind> {
+        fn normalize(string: &str) -> String {
+            string
+                .chars()
+                .filter(|c| !matches!(c, '-' | '_' | ' '))
+                .flat_map(char::to_lowercase)
+                .collect()
+        }
+
+        let name = normalize(name);
+
+        match name.as_str() {
+            "amd64" | "x64" => return Some(MachineKind::X86_64),
+            "i386" | "x86" => return Some(MachineKind::Ia386),
+            "arm64" => return Some(MachineKind::Aarch64),
+            "riscv64" => return Some(MachineKind::RiscV),
+            _ => {}
+        }
+
+        MACHINE_NAMES
+            .entries()
+            .filter_map(|(&value, &full_name)| Some((MachineKind::from_u16(value)?, full_name)))
+            .find(|(machine, full_name)| {
+                normalize(&format!("{machine:?}")) == name || normalize(full_name) == name
+            })
+            .map(|(machine, _)| machine)
     }
 }
 
+/// Returns the human-readable name of a machine from its `e_machine` value, or [`None`] if the
+/// value is unknown. See also [`MachineKind::name`].
+pub fn machine_name(value: u16) -> Option<&'static str> {
+    MACHINE_NAMES.get(&value).copied()
+}
+
+/// Formats segment flags like `readelf`, as three characters in the order read, write, execute,
+/// with a space for each missing flag, such as `R E`. The numeric value of the flags is
+/// [`FlagSet::bits`].
+pub fn segment_flags_str(flags: FlagSet<SegmentFlag>) -> String {
+    [
+        (SegmentFlag::Read, 'R'),
+        (SegmentFlag::Write, 'W'),
+        (SegmentFlag::Execute, 'E'),
+    ]
+    .into_iter()
+    .map(|(flag, c)| if flags.contains(flag) { c } else { ' ' })
+    .collect()
+}
+
 static MACHINE_NAMES: phf::Map<u16, &'static str> = phf_map! {
     0u16 => "No machine",
     1u16 => "AT&T WE 32100",
@@ -839,3 +1465,305 @@ static MACHINE_NAMES: phf::Map<u16, &'static str> = phf_map! {
     267u16 => "Loongson Loongarch",
     0x9026u16 => "Alpha",
 };
+
+/// x86-64 relocation type, stored in the `r_info` field of relocation entries.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, FromPrimitive, ToPrimitive)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[non_exhaustive]
+pub enum X86_64Reloc {
+    /// `R_X86_64_NONE`, no relocation
+    None = 0,
+    /// `R_X86_64_64`, direct 64-bit
+    R64 = 1,
+    /// `R_X86_64_PC32`, PC-relative 32-bit signed
+    Pc32 = 2,
+    /// `R_X86_64_GOT32`, 32-bit GOT entry
+    Got32 = 3,
+    /// `R_X86_64_PLT32`, 32-bit PLT address
+    Plt32 = 4,
+    /// `R_X86_64_COPY`, copy symbol at runtime
+    Copy = 5,
+    /// `R_X86_64_GLOB_DAT`, create GOT entry
+    GlobDat = 6,
+    /// `R_X86_64_JUMP_SLOT`, create PLT entry
+    JumpSlot = 7,
+    /// `R_X86_64_RELATIVE`, adjust by program base
+    Relative = 8,
+    /// `R_X86_64_GOTPCREL`, 32-bit signed PC-relative offset to GOT
+    GotPcRel = 9,
+    /// `R_X86_64_32`, direct 32-bit zero-extended
+    R32 = 10,
+    /// `R_X86_64_32S`, direct 32-bit sign-extended
+    R32S = 11,
+    /// `R_X86_64_16`, direct 16-bit zero-extended
+    R16 = 12,
+    /// `R_X86_64_PC16`, 16-bit sign-extended PC-relative
+    Pc16 = 13,
+    /// `R_X86_64_8`, direct 8-bit sign-extended
+    R8 = 14,
+    /// `R_X86_64_PC8`, 8-bit sign-extended PC-relative
+    Pc8 = 15,
+    /// `R_X86_64_DTPMOD64`, ID of module containing symbol
+    DtpMod64 = 16,
+    /// `R_X86_64_DTPOFF64`, offset in module's TLS block
+    DtpOff64 = 17,
+    /// `R_X86_64_TPOFF64`, offset in initial TLS block
+    TpOff64 = 18,
+    /// `R_X86_64_TLSGD`, 32-bit signed PC-relative offset to two GOT entries for GD symbol
+    TlsGd = 19,
+    /// `R_X86_64_TLSLD`, 32-bit signed PC-relative offset to two GOT entries for LD symbol
+    TlsLd = 20,
+    /// `R_X86_64_DTPOFF32`, offset in TLS block
+    DtpOff32 = 21,
+    /// `R_X86_64_GOTTPOFF`, 32-bit signed PC-relative offset to GOT entry for IE symbol
+    GotTpOff = 22,
+    /// `R_X86_64_TPOFF32`, offset in initial TLS block
+    TpOff32 = 23,
+    /// `R_X86_64_PC64`, PC-relative 64-bit
+    Pc64 = 24,
+    /// `R_X86_64_GOTOFF64`, 64-bit offset to GOT
+    GotOff64 = 25,
+    /// `R_X86_64_GOTPC32`, 32-bit signed PC-relative offset to GOT
+    GotPc32 = 26,
+    /// `R_X86_64_SIZE32`, size of symbol plus 32-bit addend
+    Size32 = 32,
+    /// `R_X86_64_SIZE64`, size of symbol plus 64-bit addend
+    Size64 = 33,
+    /// `R_X86_64_GOTPC32_TLSDESC`, GOT offset for TLS descriptor
+    GotPc32TlsDesc = 34,
+    /// `R_X86_64_TLSDESC_CALL`, marker for call through TLS descriptor
+    TlsDescCall = 35,
+    /// `R_X86_64_TLSDESC`, TLS descriptor
+    TlsDesc = 36,
+    /// `R_X86_64_IRELATIVE`, adjust indirectly by program base
+    IRelative = 37,
+    /// `R_X86_64_GOTPCRELX`, load from 32-bit signed PC-relative offset to GOT entry without REX
+    /// prefix, relaxable
+    GotPcRelX = 41,
+    /// `R_X86_64_REX_GOTPCRELX`, load from 32-bit signed PC-relative offset to GOT entry with REX
+    /// prefix, relaxable
+    RexGotPcRelX = 42,
+}
+
+/// RISC-V relocation type, stored in the `r_info` field of relocation entries.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, FromPrimitive, ToPrimitive)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[non_exhaustive]
+pub enum RiscvReloc {
+    /// `R_RISCV_NONE`, no relocation
+    None = 0,
+    /// `R_RISCV_32`, 32-bit absolute address
+    R32 = 1,
+    /// `R_RISCV_64`, 64-bit absolute address
+    R64 = 2,
+    /// `R_RISCV_RELATIVE`, adjust by program base
+    Relative = 3,
+    /// `R_RISCV_COPY`, copy symbol at runtime
+    Copy = 4,
+    /// `R_RISCV_JUMP_SLOT`, create PLT entry
+    JumpSlot = 5,
+    /// `R_RISCV_TLS_DTPMOD32`, ID of module containing symbol
+    TlsDtpMod32 = 6,
+    /// `R_RISCV_TLS_DTPMOD64`, ID of module containing symbol
+    TlsDtpMod64 = 7,
+    /// `R_RISCV_TLS_DTPREL32`, offset in module's TLS block
+    TlsDtpRel32 = 8,
+    /// `R_RISCV_TLS_DTPREL64`, offset in module's TLS block
+    TlsDtpRel64 = 9,
+    /// `R_RISCV_TLS_TPREL32`, offset in initial TLS block
+    TlsTpRel32 = 10,
+    /// `R_RISCV_TLS_TPREL64`, offset in initial TLS block
+    TlsTpRel64 = 11,
+    /// `R_RISCV_TLSDESC`, TLS descriptor
+    TlsDesc = 12,
+    /// `R_RISCV_BRANCH`, 12-bit PC-relative branch offset
+    Branch = 16,
+    /// `R_RISCV_JAL`, 20-bit PC-relative jump offset
+    Jal = 17,
+    /// `R_RISCV_CALL`, 32-bit PC-relative function call, `auipc` and `jalr`
+    Call = 18,
+    /// `R_RISCV_CALL_PLT`, 32-bit PC-relative function call through the PLT
+    CallPlt = 19,
+    /// `R_RISCV_GOT_HI20`, high 20 bits of 32-bit PC-relative GOT access
+    GotHi20 = 20,
+    /// `R_RISCV_TLS_GOT_HI20`, high 20 bits of 32-bit PC-relative TLS IE GOT access
+    TlsGotHi20 = 21,
+    /// `R_RISCV_TLS_GD_HI20`, high 20 bits of 32-bit PC-relative TLS GD GOT reference
+    TlsGdHi20 = 22,
+    /// `R_RISCV_PCREL_HI20`, high 20 bits of 32-bit PC-relative reference
+    PcrelHi20 = 23,
+    /// `R_RISCV_PCREL_LO12_I`, low 12 bits of a 32-bit PC-relative, I-type
+    PcrelLo12I = 24,
+    /// `R_RISCV_PCREL_LO12_S`, low 12 bits of a 32-bit PC-relative, S-type
+    PcrelLo12S = 25,
+    /// `R_RISCV_HI20`, high 20 bits of 32-bit absolute address
+    Hi20 = 26,
+    /// `R_RISCV_LO12_I`, low 12 bits of 32-bit absolute address, I-type
+    Lo12I = 27,
+    /// `R_RISCV_LO12_S`, low 12 bits of 32-bit absolute address, S-type
+    Lo12S = 28,
+    /// `R_RISCV_TPREL_HI20`, high 20 bits of TLS LE thread pointer offset
+    TprelHi20 = 29,
+    /// `R_RISCV_TPREL_LO12_I`, low 12 bits of TLS LE thread pointer offset, I-type
+    TprelLo12I = 30,
+    /// `R_RISCV_TPREL_LO12_S`, low 12 bits of TLS LE thread pointer offset, S-type
+    TprelLo12S = 31,
+    /// `R_RISCV_TPREL_ADD`, TLS LE thread pointer usage
+    TprelAdd = 32,
+    /// `R_RISCV_ADD8`, 8-bit label addition
+    Add8 = 33,
+    /// `R_RISCV_ADD16`, 16-bit label addition
+    Add16 = 34,
+    /// `R_RISCV_ADD32`, 32-bit label addition
+    Add32 = 35,
+    /// `R_RISCV_ADD64`, 64-bit label addition
+    Add64 = 36,
+    /// `R_RISCV_SUB8`, 8-bit label subtraction
+    Sub8 = 37,
+    /// `R_RISCV_SUB16`, 16-bit label subtraction
+    Sub16 = 38,
+    /// `R_RISCV_SUB32`, 32-bit label subtraction
+    Sub32 = 39,
+    /// `R_RISCV_SUB64`, 64-bit label subtraction
+    Sub64 = 40,
+    /// `R_RISCV_ALIGN`, alignment statement
+    Align = 43,
+    /// `R_RISCV_RVC_BRANCH`, 8-bit PC-relative branch offset
+    RvcBranch = 44,
+    /// `R_RISCV_RVC_JUMP`, 11-bit PC-relative jump offset
+    RvcJump = 45,
+    /// `R_RISCV_RELAX`, instruction can be relaxed
+    Relax = 51,
+    /// `R_RISCV_SUB6`, local label subtraction
+    Sub6 = 52,
+    /// `R_RISCV_SET6`, local label assignment
+    Set6 = 53,
+    /// `R_RISCV_SET8`, local label assignment
+    Set8 = 54,
+    /// `R_RISCV_SET16`, local label assignment
+    Set16 = 55,
+    /// `R_RISCV_SET32`, local label assignment
+    Set32 = 56,
+    /// `R_RISCV_32_PCREL`, 32-bit PC-relative
+    R32Pcrel = 57,
+    /// `R_RISCV_IRELATIVE`, adjust indirectly by program base
+    IRelative = 58,
+    /// `R_RISCV_PLT32`, 32-bit relative offset to a function or its PLT entry
+    Plt32 = 59,
+    /// `R_RISCV_SET_ULEB128`, local label assignment in ULEB128 encoding
+    SetUleb128 = 60,
+    /// `R_RISCV_SUB_ULEB128`, local label subtraction in ULEB128 encoding
+    SubUleb128 = 61,
+}
+
+/// AArch64 relocation type, stored in the `r_info` field of relocation entries.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, FromPrimitive, ToPrimitive)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[non_exhaustive]
+pub enum AArch64Reloc {
+    /// `R_AARCH64_NONE`, no relocation
+    None = 0,
+    /// `R_AARCH64_ABS64`, direct 64-bit
+    Abs64 = 257,
+    /// `R_AARCH64_ABS32`, direct 32-bit
+    Abs32 = 258,
+    /// `R_AARCH64_ABS16`, direct 16-bit
+    Abs16 = 259,
+    /// `R_AARCH64_PREL64`, PC-relative 64-bit
+    Prel64 = 260,
+    /// `R_AARCH64_PREL32`, PC-relative 32-bit
+    Prel32 = 261,
+    /// `R_AARCH64_PREL16`, PC-relative 16-bit
+    Prel16 = 262,
+    /// `R_AARCH64_ADR_PREL_LO21`, PC-relative `adr` immediate
+    AdrPrelLo21 = 274,
+    /// `R_AARCH64_ADR_PREL_PG_HI21`, page-relative `adrp` immediate
+    AdrPrelPgHi21 = 275,
+    /// `R_AARCH64_ADD_ABS_LO12_NC`, `add` immediate from bits 11:0
+    AddAbsLo12Nc = 277,
+    /// `R_AARCH64_LDST8_ABS_LO12_NC`, 8-bit load/store immediate from bits 11:0
+    Ldst8AbsLo12Nc = 278,
+    /// `R_AARCH64_TSTBR14`, PC-relative `tbz`/`tbnz` immediate
+    TstBr14 = 279,
+    /// `R_AARCH64_CONDBR19`, PC-relative conditional branch immediate
+    CondBr19 = 280,
+    /// `R_AARCH64_JUMP26`, PC-relative `b` immediate
+    Jump26 = 282,
+    /// `R_AARCH64_CALL26`, PC-relative `bl` immediate
+    Call26 = 283,
+    /// `R_AARCH64_LDST16_ABS_LO12_NC`, 16-bit load/store immediate from bits 11:1
+    Ldst16AbsLo12Nc = 284,
+    /// `R_AARCH64_LDST32_ABS_LO12_NC`, 32-bit load/store immediate from bits 11:2
+    Ldst32AbsLo12Nc = 285,
+    /// `R_AARCH64_LDST64_ABS_LO12_NC`, 64-bit load/store immediate from bits 11:3
+    Ldst64AbsLo12Nc = 286,
+    /// `R_AARCH64_LDST128_ABS_LO12_NC`, 128-bit load/store immediate from bits 11:4
+    Ldst128AbsLo12Nc = 299,
+    /// `R_AARCH64_ADR_GOT_PAGE`, page-relative GOT entry address
+    AdrGotPage = 311,
+    /// `R_AARCH64_LD64_GOT_LO12_NC`, GOT entry offset within page
+    Ld64GotLo12Nc = 312,
+    /// `R_AARCH64_COPY`, copy symbol at runtime
+    Copy = 1024,
+    /// `R_AARCH64_GLOB_DAT`, create GOT entry
+    GlobDat = 1025,
+    /// `R_AARCH64_JUMP_SLOT`, create PLT entry
+    JumpSlot = 1026,
+    /// `R_AARCH64_RELATIVE`, adjust by program base
+    Relative = 1027,
+    /// `R_AARCH64_TLS_DTPMOD`, module number
+    TlsDtpMod = 1028,
+    /// `R_AARCH64_TLS_DTPREL`, module-relative offset
+    TlsDtpRel = 1029,
+    /// `R_AARCH64_TLS_TPREL`, TP-relative offset
+    TlsTpRel = 1030,
+    /// `R_AARCH64_TLSDESC`, TLS descriptor
+    TlsDesc = 1031,
+    /// `R_AARCH64_IRELATIVE`, adjust indirectly by program base
+    IRelative = 1032,
+}
+
+/// A processor-specific relocation type. The meaning of a relocation type number depends on the
+/// target architecture of the ELF file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[non_exhaustive]
+pub enum RelocationKind {
+    /// x86-64 relocation
+    X86_64(X86_64Reloc),
+    /// RISC-V relocation
+    RiscV(RiscvReloc),
+    /// AArch64 relocation
+    AArch64(AArch64Reloc),
+}
+
+impl RelocationKind {
+    /// Interprets a relocation type number for the given machine. Returns [`None`] if the
+    /// machine's relocation types are not known or the type number is not recognized.
+    pub fn from_machine(machine: MachineKind, value: u32) -> Option<Self> {
+        match machine {
+            MachineKind::X86_64 => X86_64Reloc::from_u32(value).map(RelocationKind::X86_64),
+            MachineKind::RiscV => RiscvReloc::from_u32(value).map(RelocationKind::RiscV),
+            MachineKind::Aarch64 => AArch64Reloc::from_u32(value).map(RelocationKind::AArch64),
+            _ => None,
+        }
+    }
+}
+
+impl ToPrimitive for RelocationKind {
+    fn to_i64(&self) -> Option<i64> {
+        match self {
+            RelocationKind::X86_64(kind) => kind.to_i64(),
+            RelocationKind::RiscV(kind) => kind.to_i64(),
+            RelocationKind::AArch64(kind) => kind.to_i64(),
+        }
+    }
+
+    fn to_u64(&self) -> Option<u64> {
+        match self {
+            RelocationKind::X86_64(kind) => kind.to_u64(),
+            RelocationKind::RiscV(kind) => kind.to_u64(),
+            RelocationKind::AArch64(kind) => kind.to_u64(),
+        }
+    }
+}
@@ -0,0 +1,32 @@
+use thiserror::Error;
+
+use crate::{BuildError, ParseError};
+
+/// Any error that can occur when reading, modifying, or writing an ELF file, so that the errors
+/// of the reader and the builder can be propagated with `?` from the same function.
+#[derive(Debug, Error)]
+pub enum Error {
+    /// The file could not be read
+    #[error(transparent)]
+    Parse(#[from] ParseError),
+    /// The file could not be built
+    #[error(transparent)]
+    Build(#[from] BuildError),
+    /// Writing the built file failed
+    #[error(transparent)]
+    Io(std::io::Error),
+}
+
+impl From<std::io::Error> for Error {
+    /// Converts an I/O error, such as one returned by [`crate::ElfBuilder::build`]. Build errors
+    /// wrapped in I/O errors are unwrapped into [`Error::Build`].
+    fn from(error: std::io::Error) -> Self {
+        match error
+            .get_ref()
+            .and_then(|inner| inner.downcast_ref::<BuildError>())
+        {
+            Some(&inner) => Error::Build(inner),
+            None => Error::Io(error),
+        }
+    }
+}
@@ -7,23 +7,51 @@
 //!
 //! The builder only supports one symbol table through its easy interface.
 //!
+//! # Features
+//!
+//! * `std` (default): enables the [`builder`], which writes to [`std::io::Write`]. Without it, the
+//!   crate is `no_std` and only requires `alloc`.
+//! * `serde`: implements `Serialize` for the constants, the header, section, and segment readers,
+//!   and their owned snapshots such as [`reader::SectionSnapshot`].
+//!
 //! # Examples
 //!
 //! See [`reader`] and [`builder`].
 
+#![cfg_attr(not(feature = "std"), no_std)]
 #![warn(missing_docs)]
 
+extern crate alloc;
+
+#[cfg(feature = "std")]
 pub mod builder;
 mod consts;
+#[cfg(feature = "std")]
+mod error;
 pub mod reader;
 
 pub use flagset;
 
+#[cfg(feature = "std")]
 #[doc(inline)]
-pub use builder::ElfBuilder;
+pub use builder::{BuildError, ElfBuilder};
 pub use consts::{
-    ElfKind, Endianness, MachineKind, OsAbi, SectionFlag, SectionKind, SegmentFlag, SegmentKind,
-    SymbolKind,
+    machine_name, segment_flags_str, AArch64Reloc, ArmSectionKind, AuxType, CompressionKind,
+    CoreNoteKind, DynTag, ElfKind, Endianness, GroupFlag, MachineKind, MipsSectionKind, OsAbi,
+    ProcessorSectionKind, RelocationKind, RiscvReloc, RiscvSectionKind, SectionFlag, SectionKind,
+    SegmentFlag, SegmentKind, SymbolBinding, SymbolKind, X86_64Reloc, X86_64SectionKind,
 };
+#[cfg(feature = "std")]
+pub use error::Error;
 #[doc(inline)]
 pub use reader::{ElfReader, ParseError};
+
+/// Re-exports the types needed by most users of the crate, for importing with
+/// `use eelf::prelude::*`.
+pub mod prelude {
+    pub use crate::{
+        reader::ElfValue, ElfReader, Endianness, ParseError, SectionKind, SegmentKind,
+    };
+    #[cfg(feature = "std")]
+    pub use crate::{BuildError, ElfBuilder, Error};
+}
@@ -16,20 +16,22 @@
 //! Also check out [eelf-cli](https://github.com/renshyle/eelf/tree/main/eelf-cli) for a
 //! readelf-like program using eelf.
 
-use core::str;
-use std::{ffi::CStr, str::Utf8Error};
+use alloc::{borrow::Cow, vec, vec::Vec};
+use core::{ffi::CStr, str, str::Utf8Error};
 
 use num_traits::{FromPrimitive, ToPrimitive};
 use thiserror::Error;
 
 use crate::{
     consts::{
-        OsAbi, SectionKind, SegmentKind, EI_ABIVERSION, EI_CLASS, EI_DATA, EI_NIDENT, EI_OSABI,
-        EI_VERSION, ELF32_SECTION_HEADER_SIZE, ELF64_HEADER_SIZE, ELF64_PROGRAM_HEADER_SIZE,
-        ELF64_SECTION_HEADER_SIZE,
+        CompressionKind, CoreNoteKind, OsAbi, SectionKind, SegmentKind, EI_ABIVERSION, EI_CLASS,
+        EI_DATA, EI_NIDENT, EI_OSABI, EI_PAD, EI_VERSION, ELF32_CHDR_SIZE,
+        ELF32_SECTION_HEADER_SIZE, ELF64_CHDR_SIZE, ELF64_HEADER_SIZE, ELF64_PROGRAM_HEADER_SIZE,
+        ELF64_SECTION_HEADER_SIZE, NT_GNU_ABI_TAG, NT_GNU_BUILD_ID, PN_XNUM, PT_GNU_EH_FRAME,
+        SHN_XINDEX,
     },
     flagset::FlagSet,
-    Endianness, SectionFlag,
+    Endianness, ProcessorSectionKind, SectionFlag, SymbolBinding, SymbolKind,
 };
 
 use super::{
@@ -37,6 +39,34 @@ use super::{
     ElfKind, SegmentFlag,
 };
 
+mod coredump;
+mod dynamic;
+mod eh_frame_hdr;
+mod group;
+mod hash;
+pub(crate) mod inflate;
+mod notes;
+mod relocations;
+mod relr;
+mod snapshot;
+mod symbols;
+mod versions;
+
+pub use coredump::{PrStatus, Registers, RiscvRegisters, X86_64Registers};
+pub use dynamic::{Dynamic, DynamicEntry, DynamicIter};
+pub use eh_frame_hdr::EhFrameHdr;
+pub use group::{Group, GroupIter};
+pub use hash::{GnuHashTable, HashTable};
+pub use notes::{Note, Notes, NotesIter};
+pub use relocations::{Relocation, Relocations, RelocationsIter};
+pub use relr::{Relr, RelrIter};
+pub use snapshot::{HeaderSnapshot, SectionSnapshot, SegmentSnapshot};
+pub use symbols::{Symbol, Symbols, SymbolsIter};
+pub use versions::{
+    VersionDefinition, VersionDefinitions, VersionDefinitionsIter, VersionRequirement,
+    VersionRequirements, VersionRequirementsIter,
+};
+
 /// Reads data specified in the ELF specification from an ELF file.
 ///
 /// Most data is read lazily; the objects themselves do not store the data but only act as readers.
@@ -62,20 +92,20 @@ impl<'reader, 'data> ElfReader<'data> {
             Some(1) => false,
             Some(2) => true,
             Some(_) => return Err(ParseError::InvalidValue("ei_class")),
-            None => return Err(ParseError::UnexpectedEof),
+            None => return Err(ParseError::UnexpectedEof { offset: EI_CLASS }),
         };
 
         let endianness = match bytes.get(EI_DATA) {
             Some(1) => Endianness::Little,
             Some(2) => Endianness::Big,
             Some(_) => return Err(ParseError::InvalidValue("ei_data")),
-            None => return Err(ParseError::UnexpectedEof),
+            None => return Err(ParseError::UnexpectedEof { offset: EI_DATA }),
         };
 
         match bytes.get(EI_VERSION) {
             Some(1) => {}
             Some(_) => return Err(ParseError::InvalidValue("ei_version")),
-            None => return Err(ParseError::UnexpectedEof),
+            None => return Err(ParseError::UnexpectedEof { offset: EI_VERSION }),
         }
 
         Ok(Self {
@@ -85,6 +115,32 @@ impl<'reader, 'data> ElfReader<'data> {
         })
     }
 
+    /// Creates a new [`ElfReader`] like [`ElfReader::new`], and also checks that the ELF header,
+    /// the section header table, the program header table, and the data of every section and
+    /// segment are within `bytes`. Afterwards, [`ElfReader::header`], [`ElfReader::sections`],
+    /// [`ElfReader::segments`], [`Section::data`], and [`Segment::data`] won't return errors,
+    /// except for the data of [`SectionKind::Nobits`] sections which isn't stored in the file.
+    ///
+    /// The values of the fields are not validated, so accessors can still return
+    /// [`ElfValue::Unknown`] for unrecognized values, and the readers of section contents, such as
+    /// [`Section::symbols`], can still return errors.
+    pub fn parse_checked(bytes: &'data [u8]) -> Result<Self, ParseError> {
+        let reader = Self::new(bytes)?;
+        reader.header()?;
+
+        for section in reader.sections()? {
+            if section.kind() != ElfValue::Known(SectionKind::Nobits) {
+                section.data()?;
+            }
+        }
+
+        for segment in reader.segments()? {
+            segment.data()?;
+        }
+
+        Ok(reader)
+    }
+
     /// Returns the endianness of the ELF file as specified in the header.
     pub fn endianness(&self) -> Endianness {
         self.endianness
@@ -129,6 +185,62 @@ impl<'reader, 'data> ElfReader<'data> {
             .map(|bytes| self.endianness.u64_from_bytes(bytes.try_into().unwrap()))
     }
 
+    /// Reads a [`u32`] at the virtual address `vaddr` using the endianness specified in the header.
+    /// Returns [`None`] if the address isn't backed by file data, see
+    /// [`ElfReader::vaddr_to_offset`].
+    pub fn read_u32_at_vaddr(&'reader self, vaddr: u64) -> Option<u32> {
+        self.bytes_at_vaddr(vaddr, 4)
+            .map(|bytes| self.endianness.u32_from_bytes(bytes.try_into().unwrap()))
+    }
+
+    /// Reads a [`u64`] at the virtual address `vaddr` using the endianness specified in the header.
+    /// Returns [`None`] if the address isn't backed by file data, see
+    /// [`ElfReader::vaddr_to_offset`].
+    pub fn read_u64_at_vaddr(&'reader self, vaddr: u64) -> Option<u64> {
+        self.bytes_at_vaddr(vaddr, 8)
+            .map(|bytes| self.endianness.u64_from_bytes(bytes.try_into().unwrap()))
+    }
+
+    /// Returns the file offset of the data at the virtual address `vaddr`, or [`None`] if the
+    /// address isn't within `p_vaddr..p_vaddr + p_filesz` of the [`SegmentKind::Load`] segment
+    /// returned by [`Segments::covering`]. The bytes of a segment past its `p_filesz` are zero in
+    /// memory and aren't backed by file data. Returns [`None`] if the program headers could not be
+    /// read or the offset doesn't fit in a [`u64`].
+    pub fn vaddr_to_offset(&'reader self, vaddr: u64) -> Option<u64> {
+        let segment = self.segments().ok()?.covering(vaddr)?;
+        let start = vaddr - segment.vaddr();
+
+        if start >= segment.filesz() {
+            return None;
+        }
+
+        segment.offset().checked_add(start)
+    }
+
+    /// Returns the `size` bytes at the virtual address `vaddr`, or [`None`] if they aren't all
+    /// backed by the file data of the segment covering `vaddr`.
+    fn bytes_at_vaddr(&'reader self, vaddr: u64, size: u64) -> Option<&'data [u8]> {
+        let segment = self.segments().ok()?.covering(vaddr)?;
+        let start = vaddr - segment.vaddr();
+        if start.checked_add(size)? > segment.filesz() {
+            return None;
+        }
+
+        self.range(segment.offset().checked_add(start)?, size)
+    }
+
+    /// Returns the `size` bytes at `offset`, or [`None`] if they aren't all within the file.
+    fn range<O: TryInto<usize>, S: TryInto<usize>>(
+        &self,
+        offset: O,
+        size: S,
+    ) -> Option<&'data [u8]> {
+        let offset = offset.try_into().ok()?;
+        let end = offset.checked_add(size.try_into().ok()?)?;
+
+        self.bytes.get(offset..end)
+    }
+
     /// Returns a [`Header`] object, or an error if the header could not be read, such as if the
     /// data is shorter than an ELF header's length.
     pub fn header(&'reader self) -> Result<Header<'reader, 'data>, ParseError> {
@@ -147,10 +259,407 @@ impl<'reader, 'data> ElfReader<'data> {
         Sections::new(self)
     }
 
+    /// Returns the bytes of the program header table, [`Segments::len`] entries of `e_phentsize`
+    /// bytes starting at `e_phoff`, or an error if the table is not within the file.
+    pub fn phdr_bytes(&'reader self) -> Result<&'data [u8], ParseError> {
+        let segments = self.segments()?;
+
+        // the range has been checked when creating the segments
+        Ok(self
+            .range(segments.phoff, segments.phnum * segments.header_size)
+            .unwrap())
+    }
+
+    /// Returns the bytes of the section header table, [`Sections::len`] entries of `e_shentsize`
+    /// bytes starting at `e_shoff`, or an error if the table is not within the file.
+    pub fn shdr_bytes(&'reader self) -> Result<&'data [u8], ParseError> {
+        let sections = self.sections()?;
+
+        // the range has been checked when creating the sections
+        Ok(self
+            .range(sections.shoff, sections.shnum * sections.header_size)
+            .unwrap())
+    }
+
+    /// Returns the offset of the end of the ELF file: the end of the ELF header, the program header
+    /// table, the section header table, or the data of a section or segment, whichever is last.
+    /// The data of [`SectionKind::Nobits`] sections isn't stored in the file, so it isn't counted.
+    ///
+    /// This is useful for carving ELF files out of larger blobs, such as firmware images, since
+    /// any data after this offset isn't part of the ELF file. Returns an error if any of the tables
+    /// or the data of a section or segment is not within the file, as it would be if an offset is
+    /// corrupt.
+    pub fn image_end(&'reader self) -> Result<u64, ParseError> {
+        let mut end = u64::from(if self.is_64bit {
+            ELF64_HEADER_SIZE
+        } else {
+            ELF32_HEADER_SIZE
+        });
+        let mut extend = |offset: u64, data: &[u8]| {
+            // the offsets of empty ranges aren't meaningful, such as e_phoff without segments
+            if !data.is_empty() {
+                end = end.max(offset + u64::try_from(data.len()).unwrap());
+            }
+        };
+
+        let header = self.header()?;
+        extend(header.phoff(), self.phdr_bytes()?);
+        extend(header.shoff(), self.shdr_bytes()?);
+
+        for section in self.sections()? {
+            if section.kind() != ElfValue::Known(SectionKind::Nobits) {
+                extend(section.offset(), section.data()?);
+            }
+        }
+        for segment in self.segments()? {
+            extend(segment.offset(), segment.data()?);
+        }
+
+        Ok(end)
+    }
+
     /// Returns a [`Strings`] object based on the header's `e_shstrndx` value, or an error if the
-    /// section could not be read.
+    /// section could not be read. This is a shortcut for calling [`ElfReader::string_table`] with
+    /// [`ElfReader::shstrndx`].
     pub fn strings(&self) -> Result<Strings<'data>, ParseError> {
-        Strings::new(self)
+        Strings::new(self, self.shstrndx()?, "e_shstrndx")
+    }
+
+    /// Returns a [`Strings`] object for the string table at the specified section index, such as
+    /// `.strtab` or `.dynstr`, or an error if the section could not be read. Returns a
+    /// [`ParseError::InvalidValue`] error for `e_shnum` if there is no section at the index. The
+    /// type of the section is not checked.
+    pub fn string_table(&self, section_index: usize) -> Result<Strings<'data>, ParseError> {
+        Strings::new(self, section_index, "e_shnum")
+    }
+
+    /// Returns the index of the section containing the section names. This is `e_shstrndx`,
+    /// unless it is `SHN_XINDEX` (`0xffff`), in which case the index doesn't fit in the header and
+    /// is stored in `sh_link` of the first section header instead.
+    pub fn shstrndx(&self) -> Result<usize, ParseError> {
+        let shstrndx = self.header()?.shstrndx();
+
+        if shstrndx != SHN_XINDEX {
+            return Ok(shstrndx.into());
+        }
+
+        let link = self
+            .sections()?
+            .get(0)
+            .ok_or(ParseError::InvalidValue("e_shstrndx"))?
+            .link();
+
+        Ok(usize::try_from(link).unwrap())
+    }
+
+    /// Returns the path of the program interpreter, the dynamic loader, stored in the
+    /// [`SegmentKind::Interp`] segment. Returns [`None`] if there is no such segment, as in
+    /// statically linked executables, or an error if the segment could not be read or its data is
+    /// not null-terminated.
+    pub fn interpreter(&'reader self) -> Result<Option<&'data CStr>, ParseError> {
+        let Some(segment) = self.segments()?.of_kind(SegmentKind::Interp).next() else {
+            return Ok(None);
+        };
+
+        CStr::from_bytes_until_nul(segment.data()?)
+            .map(Some)
+            .map_err(|_| ParseError::InvalidValue("p_filesz"))
+    }
+
+    /// Returns the build ID stored in a `GNU` note of type `NT_GNU_BUILD_ID`, usually in the
+    /// `.note.gnu.build-id` section. Returns [`None`] if there is no build ID, or an error if a
+    /// note section or segment could not be read.
+    pub fn build_id(&'reader self) -> Result<Option<&'data [u8]>, ParseError> {
+        self.gnu_note(NT_GNU_BUILD_ID)
+    }
+
+    /// Returns the data of the `.comment` section, which contains null-terminated strings
+    /// identifying the tools that produced the file. Returns [`None`] if there is no such section,
+    /// or an error if the section headers or the section names could not be read.
+    pub fn comment(&'reader self) -> Result<Option<&'data [u8]>, ParseError> {
+        // with_names treats an unreadable string table as missing names, but that is an error here
+        self.strings()?;
+
+        self.sections()?
+            .with_names()
+            .find(|(_, name)| *name == Some(".comment"))
+            .map(|(section, _)| section.data())
+            .transpose()
+    }
+
+    /// Returns the [`EhFrameHdr`] of the `PT_GNU_EH_FRAME` segment, or of the `.eh_frame_hdr`
+    /// section if there is no such segment, as in relocatable objects. Returns [`None`] if there
+    /// is neither, or an error if the headers, the section names, or the data could not be read.
+    pub fn eh_frame_hdr(&'reader self) -> Result<Option<EhFrameHdr<'data>>, ParseError> {
+        if let Some(segment) = self
+            .segments()?
+            .into_iter()
+            .find(|segment| segment.kind() == ElfValue::Unknown(PT_GNU_EH_FRAME))
+        {
+            return EhFrameHdr::new(self, segment.data()?, segment.vaddr(), segment.offset())
+                .map(Some);
+        }
+
+        // with_names treats an unreadable string table as missing names, but that is an error here
+        self.strings()?;
+
+        self.sections()?
+            .with_names()
+            .find(|(_, name)| *name == Some(".eh_frame_hdr"))
+            .map(|(section, _)| section.eh_frame_hdr())
+            .transpose()
+    }
+
+    /// Returns the ABI tag stored in a `GNU` note of type `NT_GNU_ABI_TAG`, usually in the
+    /// `.note.ABI-tag` section, as the operating system (0 for Linux) and the earliest compatible
+    /// kernel version as major, minor, and patch numbers. Returns [`None`] if there is no ABI tag,
+    /// or an error if a note section or segment could not be read or the note is too short.
+    pub fn abi_tag(&'reader self) -> Result<Option<(u32, u32, u32, u32)>, ParseError> {
+        let Some(desc) = self.gnu_note(NT_GNU_ABI_TAG)? else {
+            return Ok(None);
+        };
+
+        let word = |index: usize| {
+            desc.get(index * 4..index * 4 + 4)
+                .map(|bytes| self.endianness.u32_from_bytes(bytes.try_into().unwrap()))
+                .ok_or(ParseError::InvalidValue("n_descsz"))
+        };
+
+        Ok(Some((word(0)?, word(1)?, word(2)?, word(3)?)))
+    }
+
+    /// Returns true if the file is a core dump, i.e. its type is [`ElfKind::Core`]. Returns false
+    /// if the header could not be read.
+    pub fn is_core(&'reader self) -> bool {
+        self.header()
+            .is_ok_and(|header| header.kind() == ElfValue::Known(ElfKind::Core))
+    }
+
+    /// Returns true if the file has no [`SectionKind::SymbolTable`] section, so that only the
+    /// dynamic symbols, if any, are available. Files without a section header table are
+    /// considered stripped. Returns an error if the section headers could not be read.
+    pub fn is_stripped(&'reader self) -> Result<bool, ParseError> {
+        if self.header()?.shoff() == 0 {
+            return Ok(true);
+        }
+
+        Ok(self
+            .sections()?
+            .first_of_kind(SectionKind::SymbolTable)
+            .is_none())
+    }
+
+    /// Returns the address and the data of every executable section, [`Section::is_executable`],
+    /// in the order of the section header table, such as to pass them to a disassembler. If the
+    /// file has no sections, the executable [`SegmentKind::Load`] segments are returned instead,
+    /// with the data stored in the file. [`SectionKind::Nobits`] sections and empty ranges are
+    /// skipped. Returns an error if the headers or the data could not be read.
+    pub fn executable_ranges(&'reader self) -> Result<Vec<(u64, &'data [u8])>, ParseError> {
+        let sections = match self.header()?.shoff() {
+            0 => None,
+            _ => Some(self.sections()?).filter(|sections| sections.len() > 1),
+        };
+
+        let Some(sections) = sections else {
+            return self
+                .segments()?
+                .of_kind(SegmentKind::Load)
+                .filter(|segment| segment.is_executable() && segment.filesz() != 0)
+                .map(|segment| Ok((segment.vaddr(), segment.data()?)))
+                .collect();
+        };
+
+        sections
+            .into_iter()
+            .filter(|section| {
+                section.is_executable()
+                    && section.kind() != ElfValue::Known(SectionKind::Nobits)
+                    && section.size() != 0
+            })
+            .map(|section| Ok((section.addr(), section.data()?)))
+            .collect()
+    }
+
+    /// Returns the types of the notes in all of the [`SegmentKind::Note`] segments, in the order
+    /// they appear in the file. The types are interpreted as core file note types, so this is
+    /// mostly useful for files for which [`ElfReader::is_core`] returns true. Returns an error if
+    /// the program headers or a note segment could not be read.
+    pub fn core_note_kinds(&'reader self) -> Result<Vec<ElfValue<CoreNoteKind, u32>>, ParseError> {
+        let mut kinds = Vec::new();
+
+        for segment in self.segments()?.of_kind(SegmentKind::Note) {
+            kinds.extend(segment.notes()?.into_iter().map(|note| {
+                CoreNoteKind::from_u32(note.kind())
+                    .map_or(ElfValue::Unknown(note.kind()), ElfValue::Known)
+            }));
+        }
+
+        Ok(kinds)
+    }
+
+    /// Returns the notes in all of the [`SectionKind::Note`] sections, followed by the notes in
+    /// the [`SegmentKind::Note`] segments that aren't in any of the sections, such as in files
+    /// without section headers. A note that is both in a section and in a segment covering it is
+    /// only returned once. Returns an error if the headers or the data of a section or segment
+    /// could not be read.
+    pub fn notes(&'reader self) -> Result<Vec<Note<'data>>, ParseError> {
+        let mut notes = Vec::new();
+
+        for section in self.sections()?.of_kind(SectionKind::Note) {
+            notes.extend(section.notes()?);
+        }
+
+        // the notes are borrowed from the file data, so a note in both a section and a segment is
+        // at the same address in both
+        let section_notes = notes.len();
+        for segment in self.segments()?.of_kind(SegmentKind::Note) {
+            for note in segment.notes()? {
+                if !notes[..section_notes]
+                    .iter()
+                    .any(|seen| core::ptr::eq(seen.name().as_ptr(), note.name().as_ptr()))
+                {
+                    notes.push(note);
+                }
+            }
+        }
+
+        Ok(notes)
+    }
+
+    /// Returns the descriptor of the first `GNU` note of the given type. The note sections are
+    /// searched first, then the [`SegmentKind::Note`] segments in case the file has no section
+    /// headers.
+    fn gnu_note(&'reader self, kind: u32) -> Result<Option<&'data [u8]>, ParseError> {
+        let is_match = |note: &Note| note.name() == b"GNU" && note.kind() == kind;
+
+        for section in self.sections()?.of_kind(SectionKind::Note) {
+            if let Some(note) = section.notes()?.into_iter().find(is_match) {
+                return Ok(Some(note.desc()));
+            }
+        }
+
+        for segment in self.segments()?.of_kind(SegmentKind::Note) {
+            if let Some(note) = segment.notes()?.into_iter().find(is_match) {
+                return Ok(Some(note.desc()));
+            }
+        }
+
+        Ok(None)
+    }
+
+    /// Returns the section whose data contains the given file offset, or [`None`] if no section
+    /// does. [`SectionKind::Nobits`] sections are ignored because they occupy no space in the
+    /// file. If the offset is in multiple sections, the first one in the section header table is
+    /// returned.
+    pub fn section_at_offset(
+        &'reader self,
+        offset: u64,
+    ) -> Result<Option<Section<'reader, 'data>>, ParseError> {
+        Ok(self.sections()?.into_iter().find(|section| {
+            section.kind() != ElfValue::Known(SectionKind::Nobits)
+                && offset >= section.offset()
+                && offset - section.offset() < section.size()
+        }))
+    }
+
+    /// Returns the sections contained in a segment, in the order of the section header table, like
+    /// the "Section to Segment mapping" of `readelf -l`. A section is in the segment if its data
+    /// is within `p_offset..p_offset + p_filesz` in the file and, unless its `sh_addr` is 0, its
+    /// addresses are within `p_vaddr..p_vaddr + p_memsz` in memory. [`SectionKind::Nobits`]
+    /// sections occupy no space in the file, so only their addresses are compared, and those that
+    /// are [`Section::is_tls`], such as `.tbss`, are only in [`SegmentKind::Tls`] segments.
+    /// Empty sections are in the segment if they start inside it. The null section is never in a
+    /// segment.
+    pub fn segment_sections(
+        &'reader self,
+        segment: &Segment,
+    ) -> Result<Vec<Section<'reader, 'data>>, ParseError> {
+        // whether start..start + size is within range_start..range_start + range_size
+        let contains = |start: u64, size: u64, range_start: u64, range_size: u64| {
+            start
+                .checked_sub(range_start)
+                .is_some_and(|start| match size {
+                    0 => start < range_size,
+                    _ => start.checked_add(size).is_some_and(|end| end <= range_size),
+                })
+        };
+
+        Ok(self
+            .sections()?
+            .into_iter()
+            .skip(1)
+            .filter(|section| {
+                let in_memory = contains(
+                    section.addr(),
+                    section.size(),
+                    segment.vaddr(),
+                    segment.memsz(),
+                );
+
+                if section.kind() == ElfValue::Known(SectionKind::Nobits) {
+                    section.addr() != 0
+                        && in_memory
+                        && (!section.is_tls()
+                            || segment.kind() == ElfValue::Known(SegmentKind::Tls))
+                } else {
+                    contains(
+                        section.offset(),
+                        section.size(),
+                        segment.offset(),
+                        segment.filesz(),
+                    ) && (section.addr() == 0 || in_memory)
+                }
+            })
+            .collect())
+    }
+
+    /// Lays out the [`SegmentKind::Load`] segments in a buffer as they would be in memory. The
+    /// buffer starts at the lowest `p_vaddr` and ends at the highest `p_vaddr + p_memsz`. Gaps
+    /// between segments and the bytes of each segment past its `p_filesz` are zero. Returns an
+    /// error if the segments overlap, could not be read, or span more memory than can be
+    /// allocated.
+    pub fn load_image(&'reader self) -> Result<Vec<u8>, ParseError> {
+        let mut segments = self
+            .segments()?
+            .of_kind(SegmentKind::Load)
+            .filter(|segment| segment.memsz() != 0)
+            .collect::<Vec<_>>();
+        segments.sort_by_key(Segment::vaddr);
+
+        let Some(first) = segments.first() else {
+            return Ok(Vec::new());
+        };
+        let base = first.vaddr();
+        let mut end = base;
+
+        for segment in &segments {
+            if segment.filesz() > segment.memsz() {
+                return Err(ParseError::InvalidValue("p_filesz"));
+            } else if segment.vaddr() < end {
+                return Err(ParseError::OverlappingSegments);
+            }
+
+            end = segment
+                .vaddr()
+                .checked_add(segment.memsz())
+                .ok_or(ParseError::InvalidValue("p_memsz"))?;
+        }
+
+        let size = usize::try_from(end - base).map_err(|_| ParseError::InvalidValue("p_memsz"))?;
+        let mut image = Vec::new();
+        image
+            .try_reserve_exact(size)
+            .map_err(|_| ParseError::InvalidValue("p_memsz"))?;
+        image.resize(size, 0);
+
+        for segment in &segments {
+            let start = usize::try_from(segment.vaddr() - base).unwrap();
+            let data = segment.data()?;
+
+            image[start..start + data.len()].copy_from_slice(data);
+        }
+
+        Ok(image)
     }
 }
 
@@ -161,6 +670,8 @@ pub struct Header<'reader, 'data> {
 }
 
 impl<'reader, 'data> Header<'reader, 'data> {
+    /// Every field of the header is within the first `header_size` bytes, so once the length has
+    /// been checked here, the field accessors can't fail.
     fn new(elf: &'reader ElfReader<'data>) -> Result<Self, ParseError> {
         let header_size = match elf.is_64bit() {
             true => ELF64_HEADER_SIZE,
@@ -168,7 +679,9 @@ impl<'reader, 'data> Header<'reader, 'data> {
         };
 
         if elf.bytes().len() < header_size.into() {
-            return Err(ParseError::UnexpectedEof);
+            return Err(ParseError::UnexpectedEof {
+                offset: elf.bytes().len(),
+            });
         }
 
         Ok(Header { elf })
@@ -197,6 +710,13 @@ impl<'reader, 'data> Header<'reader, 'data> {
         self.elf.bytes()[EI_ABIVERSION]
     }
 
+    /// The padding bytes at the end of the identification bytes, after `ei_abiversion`. They
+    /// should be zero, but some ABIs store nonstandard markers in them. `ei_pad` in the
+    /// specification.
+    pub fn ident_padding(&self) -> &'data [u8] {
+        &self.ident()[EI_PAD..]
+    }
+
     /// The type of the ELF file. `e_type` in the specification.
     pub fn kind(&self) -> ElfValue<ElfKind, u16> {
         let value = self.elf.read_u16(16).unwrap();
@@ -315,23 +835,30 @@ impl<'reader, 'data> Header<'reader, 'data> {
     }
 }
 
-/// A reader for the string table section.
+/// A reader for a string table section, such as `.shstrtab`, `.strtab`, or `.dynstr`. The
+/// strings are borrowed from the file's data and are only scanned when looked up, so creating the
+/// reader and reading a string don't copy or validate the rest of the table.
 #[derive(Debug, Clone)]
 pub struct Strings<'data> {
     data: &'data [u8],
 }
 
 impl<'data> Strings<'data> {
-    fn new(elf: &ElfReader<'data>) -> Result<Self, ParseError> {
-        let shstrndx = elf.header()?.shstrndx();
-        let strtab_section = elf.sections()?;
-        let strtab_section = strtab_section
-            .get(shstrndx.into())
-            .ok_or(ParseError::InvalidValue("e_shstrndx"))?
-            .data()?;
+    /// Reads the string table at a section index. If there is no section at the index, a
+    /// [`ParseError::InvalidValue`] error with the name of the field the index was read from is
+    /// returned.
+    fn new(elf: &ElfReader<'data>, index: usize, field: &'static str) -> Result<Self, ParseError> {
+        let section = elf
+            .sections()?
+            .get(index)
+            .ok_or(ParseError::InvalidValue(field))?;
 
+        Self::from_section(&section)
+    }
+
+    fn from_section(section: &Section<'_, 'data>) -> Result<Self, ParseError> {
         Ok(Self {
-            data: strtab_section,
+            data: section.data()?,
         })
     }
 
@@ -350,6 +877,36 @@ impl<'data> Strings<'data> {
 
         CStr::from_bytes_until_nul(bytes).ok()
     }
+
+    /// Reads the raw bytes of a string from the string table using the index specified, up to but
+    /// not including the terminating null byte, or up to the end of the table if the string isn't
+    /// terminated. Returns [`None`] if the index is past the end of the table. Unlike
+    /// [`Strings::get_cstr`], the string doesn't have to be null-terminated.
+    pub fn get_bytes_until_nul(&self, index: u64) -> Option<&'data [u8]> {
+        let bytes = self.data.get(usize::try_from(index).ok()?..)?;
+        let length = bytes
+            .iter()
+            .position(|&byte| byte == 0)
+            .unwrap_or(bytes.len());
+
+        Some(&bytes[..length])
+    }
+
+    /// Returns an iterator over the strings in the string table and their indices, starting with
+    /// the empty string at index 0. A string at the end of the table without a terminating null
+    /// byte is not returned.
+    pub fn iter(&self) -> impl Iterator<Item = (u32, &'data CStr)> {
+        let data = self.data;
+        let mut offset = 0;
+
+        core::iter::from_fn(move || {
+            let string = CStr::from_bytes_until_nul(data.get(offset..)?).ok()?;
+            let index = u32::try_from(offset).ok()?;
+            offset += string.to_bytes_with_nul().len();
+
+            Some((index, string))
+        })
+    }
 }
 
 /// An object that can be used to read the section header table in an ELF file.
@@ -369,12 +926,29 @@ impl<'reader, 'data> Sections<'reader, 'data> {
         };
         let header = elf.header()?;
         let shoff = usize::try_from(header.shoff()).unwrap();
-        let shnum = usize::from(header.shnum());
+        let mut shnum = usize::from(header.shnum());
 
         if header.shentsize() != header_size {
             return Err(ParseError::InvalidValue("e_shentsize"));
-        } else if shoff + shnum * usize::from(header_size) > elf.bytes().len() {
-            return Err(ParseError::UnexpectedEof);
+        }
+
+        // if there are too many sections for e_shnum, it is 0 and the count is stored in sh_size
+        // of the first section header
+        if shnum == 0 && shoff != 0 {
+            if elf.range(shoff, header_size).is_none() {
+                return Err(ParseError::UnexpectedEof { offset: shoff });
+            }
+
+            let first = Section { elf, offset: shoff };
+            shnum =
+                usize::try_from(first.size()).map_err(|_| ParseError::InvalidValue("sh_size"))?;
+        }
+
+        if elf
+            .range(shoff, shnum.saturating_mul(usize::from(header_size)))
+            .is_none()
+        {
+            return Err(ParseError::UnexpectedEof { offset: shoff });
         }
 
         Ok(Self {
@@ -385,6 +959,18 @@ impl<'reader, 'data> Sections<'reader, 'data> {
         })
     }
 
+    /// Returns the number of sections. This is `e_shnum`, unless it is 0 and the section header
+    /// table isn't empty, in which case the count is stored in `sh_size` of the first section
+    /// header.
+    pub fn len(&self) -> usize {
+        self.shnum
+    }
+
+    /// Returns whether the section header table is empty.
+    pub fn is_empty(&self) -> bool {
+        self.shnum == 0
+    }
+
     /// Returns a [`Section`] of a section at the specified index in the section header table.
     pub fn get(&self, index: usize) -> Option<Section<'reader, 'data>> {
         if index >= self.shnum {
@@ -398,6 +984,36 @@ impl<'reader, 'data> Sections<'reader, 'data> {
             offset: start,
         })
     }
+
+    /// Returns an iterator over the sections paired with their names, which are read from the
+    /// section name string table. The string table is only looked up once. A name is [`None`] if
+    /// the string table could not be read or the name is not a valid UTF-8 string in it.
+    pub fn with_names(
+        &self,
+    ) -> impl Iterator<Item = (Section<'reader, 'data>, Option<&'data str>)> {
+        let strings = self.elf.strings().ok();
+
+        self.clone().into_iter().map(move |section| {
+            let name = strings
+                .as_ref()
+                .and_then(|strings| strings.get_str(section.name().into()))
+                .and_then(Result::ok);
+
+            (section, name)
+        })
+    }
+
+    /// Returns an iterator over the sections of the specified type.
+    pub fn of_kind(&self, kind: SectionKind) -> impl Iterator<Item = Section<'reader, 'data>> {
+        self.clone()
+            .into_iter()
+            .filter(move |section| section.kind() == ElfValue::Known(kind))
+    }
+
+    /// Returns the first section of the specified type, or [`None`] if there is no such section.
+    pub fn first_of_kind(&self, kind: SectionKind) -> Option<Section<'reader, 'data>> {
+        self.of_kind(kind).next()
+    }
 }
 
 impl<'reader, 'data> IntoIterator for Sections<'reader, 'data> {
@@ -437,7 +1053,7 @@ pub struct Section<'reader, 'data> {
     offset: usize,
 }
 
-impl<'data> Section<'_, 'data> {
+impl<'reader, 'data> Section<'reader, 'data> {
     fn read_u32(&self, offset: usize) -> u32 {
         self.elf.read_u32(self.offset + offset).unwrap()
     }
@@ -446,6 +1062,18 @@ impl<'data> Section<'_, 'data> {
         self.elf.read_u64(self.offset + offset).unwrap()
     }
 
+    /// Returns the index of the section in the section header table.
+    fn index(&self) -> usize {
+        let header_size = match self.elf.is_64bit() {
+            true => ELF64_SECTION_HEADER_SIZE,
+            false => ELF32_SECTION_HEADER_SIZE,
+        };
+        // the header was read when creating the section
+        let shoff = usize::try_from(self.elf.header().unwrap().shoff()).unwrap();
+
+        (self.offset - shoff) / usize::from(header_size)
+    }
+
     /// The string table index of the section's name. `sh_name` in the specification.
     pub fn name(&self) -> u32 {
         self.read_u32(0)
@@ -453,9 +1081,17 @@ impl<'data> Section<'_, 'data> {
 
     /// The type of the section. `sh_type` in the specification.
     pub fn kind(&self) -> ElfValue<SectionKind, u32> {
+        SectionKind::from_raw(self.read_u32(4))
+    }
+
+    /// Interprets the section type using the processor-specific section types of the given
+    /// machine, such as [`ArmSectionKind::Exidx`](crate::ArmSectionKind::Exidx). Types that
+    /// aren't processor-specific are returned as [`ElfValue::Unknown`].
+    pub fn kind_for(&self, machine: MachineKind) -> ElfValue<ProcessorSectionKind, u32> {
         let value = self.read_u32(4);
 
-        SectionKind::from_u32(value).map_or(ElfValue::Unknown(value), ElfValue::Known)
+        ProcessorSectionKind::from_machine(machine, value)
+            .map_or(ElfValue::Unknown(value), ElfValue::Known)
     }
 
     /// Section flags. `sh_flags` in the specification.
@@ -473,6 +1109,44 @@ impl<'data> Section<'_, 'data> {
             .map_or(ElfValue::Unknown(value), ElfValue::Known)
     }
 
+    /// Whether the section has a flag. Unknown flags, such as OS-specific ones, don't affect the
+    /// result.
+    pub fn has_flag(&self, flag: SectionFlag) -> bool {
+        match self.flags() {
+            ElfValue::Known(flags) => flags.contains(flag),
+            ElfValue::Unknown(flags) => flags & u64::from(FlagSet::from(flag).bits()) != 0,
+        }
+    }
+
+    /// The flags known to this crate, ignoring unknown flags such as OS-specific ones. Use
+    /// [`Section::flags`] to get all of the flags.
+    pub fn known_flags(&self) -> FlagSet<SectionFlag> {
+        match self.flags() {
+            ElfValue::Known(flags) => flags,
+            ElfValue::Unknown(flags) => FlagSet::new_truncated(flags as u32),
+        }
+    }
+
+    /// Whether the section is loaded into memory, [`SectionFlag::Alloc`].
+    pub fn is_alloc(&self) -> bool {
+        self.has_flag(SectionFlag::Alloc)
+    }
+
+    /// Whether the section is writable at runtime, [`SectionFlag::Write`].
+    pub fn is_writable(&self) -> bool {
+        self.has_flag(SectionFlag::Write)
+    }
+
+    /// Whether the section contains executable instructions, [`SectionFlag::ExecInstr`].
+    pub fn is_executable(&self) -> bool {
+        self.has_flag(SectionFlag::ExecInstr)
+    }
+
+    /// Whether the section contains thread-local storage, [`SectionFlag::Tls`].
+    pub fn is_tls(&self) -> bool {
+        self.has_flag(SectionFlag::Tls)
+    }
+
     /// The address the section will be located at during execution, or 0 if the data isn't loaded.
     /// `sh_addr` in the specification.
     pub fn addr(&self) -> u64 {
@@ -539,6 +1213,197 @@ impl<'data> Section<'_, 'data> {
         }
     }
 
+    /// Returns a [`Relocations`] reader for the section, or an error if the section is not a
+    /// relocation table or its entries could not be read.
+    pub fn relocations(&self) -> Result<Relocations<'reader, 'data>, ParseError> {
+        Relocations::new(self)
+    }
+
+    /// Returns a [`Relr`] reader for the section, or an error if the section is not a packed
+    /// relative relocation table or its data could not be read.
+    pub fn relr(&self) -> Result<Relr<'data>, ParseError> {
+        Relr::new(self)
+    }
+
+    /// Returns a [`Symbols`] reader for the section, or an error if the section is not a symbol
+    /// table or its entries could not be read.
+    pub fn symbols(&self) -> Result<Symbols<'reader, 'data>, ParseError> {
+        Symbols::new(self)
+    }
+
+    /// Returns a [`Dynamic`] reader for the section, or an error if the section is not a dynamic
+    /// section or its entries could not be read.
+    pub fn dynamic(&self) -> Result<Dynamic<'reader, 'data>, ParseError> {
+        Dynamic::new(self)
+    }
+
+    /// Returns a [`Notes`] reader for the section, or an error if the section is not a note
+    /// section or its data could not be read.
+    pub fn notes(&self) -> Result<Notes<'reader, 'data>, ParseError> {
+        if self.kind() != ElfValue::Known(SectionKind::Note) {
+            return Err(ParseError::InvalidValue("sh_type"));
+        }
+
+        Ok(Notes::new(self.elf, self.data()?, self.addralign()))
+    }
+
+    /// Returns a [`HashTable`] reader for the section, or an error if the section is not a SysV
+    /// hash table or its data or symbol table could not be read.
+    pub fn hash_table(&self) -> Result<HashTable<'reader, 'data>, ParseError> {
+        HashTable::new(self)
+    }
+
+    /// Returns a [`GnuHashTable`] reader for the section, or an error if the section is not a GNU
+    /// hash table or its data or symbol table could not be read.
+    pub fn gnu_hash_table(&self) -> Result<GnuHashTable<'reader, 'data>, ParseError> {
+        GnuHashTable::new(self)
+    }
+
+    /// Returns a [`VersionDefinitions`] reader for the section, or an error if the section is not
+    /// a `SHT_GNU_verdef` section or its data could not be read.
+    pub fn version_definitions(&self) -> Result<VersionDefinitions<'reader, 'data>, ParseError> {
+        VersionDefinitions::new(self)
+    }
+
+    /// Returns a [`VersionRequirements`] reader for the section, or an error if the section is not
+    /// a `SHT_GNU_verneed` section or its data could not be read.
+    pub fn version_requirements(&self) -> Result<VersionRequirements<'reader, 'data>, ParseError> {
+        VersionRequirements::new(self)
+    }
+
+    /// Returns a [`Group`] reader for the section, or an error if the section is not a section
+    /// group or its data could not be read.
+    pub fn group(&self) -> Result<Group<'reader, 'data>, ParseError> {
+        Group::new(self)
+    }
+
+    /// Returns an iterator over the section header indices of the members of a section group, or
+    /// an error if the section is not a section group or its data could not be read.
+    pub fn group_members(&self) -> Result<GroupIter<'data>, ParseError> {
+        Ok(self.group()?.into_iter())
+    }
+
+    /// Returns an [`EhFrameHdr`] reader for the section, which should be the `.eh_frame_hdr`
+    /// section. The type of the section is not checked, since it is [`SectionKind::Progbits`].
+    /// Returns an error if the data could not be read or is not a valid `.eh_frame_hdr`.
+    pub fn eh_frame_hdr(&self) -> Result<EhFrameHdr<'data>, ParseError> {
+        EhFrameHdr::new(self.elf, self.data()?, self.addr(), self.offset())
+    }
+
+    /// Returns a structured view of the section's contents based on its type:
+    ///
+    /// | Section type                 | Variant                               |
+    /// |------------------------------|---------------------------------------|
+    /// | `SHT_SYMTAB`, `SHT_DYNSYM`   | [`TypedSection::Symbols`]             |
+    /// | `SHT_REL`, `SHT_RELA`        | [`TypedSection::Relocations`]         |
+    /// | `SHT_RELR`                   | [`TypedSection::Relr`]                |
+    /// | `SHT_STRTAB`                 | [`TypedSection::Strings`]             |
+    /// | `SHT_NOTE`                   | [`TypedSection::Notes`]               |
+    /// | `SHT_DYNAMIC`                | [`TypedSection::Dynamic`]             |
+    /// | `SHT_HASH`                   | [`TypedSection::Hash`]                |
+    /// | `SHT_GNU_HASH`               | [`TypedSection::GnuHash`]             |
+    /// | `SHT_GROUP`                  | [`TypedSection::Group`]               |
+    /// | `SHT_GNU_verdef`             | [`TypedSection::VersionDefinitions`]  |
+    /// | `SHT_GNU_verneed`            | [`TypedSection::VersionRequirements`] |
+    /// | `SHT_NOBITS`                 | [`TypedSection::Raw`], empty          |
+    /// | any other, including unknown | [`TypedSection::Raw`]                 |
+    ///
+    /// Returns an error if the section's data could not be read or is invalid for its type.
+    pub fn typed(&self) -> Result<TypedSection<'reader, 'data>, ParseError> {
+        let kind = match self.kind() {
+            ElfValue::Known(kind) => kind,
+            ElfValue::Unknown(_) => return Ok(TypedSection::Raw(self.data()?)),
+        };
+
+        Ok(match kind {
+            SectionKind::SymbolTable | SectionKind::DynSym => {
+                TypedSection::Symbols(self.symbols()?)
+            }
+            SectionKind::Rel | SectionKind::Rela => TypedSection::Relocations(self.relocations()?),
+            SectionKind::Relr => TypedSection::Relr(self.relr()?),
+            SectionKind::StringTable => TypedSection::Strings(Strings::from_section(self)?),
+            SectionKind::Note => TypedSection::Notes(self.notes()?),
+            SectionKind::Dynamic => TypedSection::Dynamic(self.dynamic()?),
+            SectionKind::Hash => TypedSection::Hash(self.hash_table()?),
+            SectionKind::GnuHash => TypedSection::GnuHash(self.gnu_hash_table()?),
+            SectionKind::Group => TypedSection::Group(self.group()?),
+            SectionKind::GnuVerDef => TypedSection::VersionDefinitions(self.version_definitions()?),
+            SectionKind::GnuVerNeed => {
+                TypedSection::VersionRequirements(self.version_requirements()?)
+            }
+            SectionKind::Nobits => TypedSection::Raw(&[]),
+            _ => TypedSection::Raw(self.data()?),
+        })
+    }
+
+    /// Returns the data of the section, decompressed if the section has the
+    /// [`SectionFlag::Compressed`] flag, or an error if it could not be read or decompressed. Only
+    /// [`CompressionKind::Zlib`] is supported. The data is returned unchanged if the section isn't
+    /// compressed.
+    pub fn decompressed_data(&self) -> Result<Cow<'data, [u8]>, ParseError> {
+        let data = self.data()?;
+
+        let Some(header) = self.compression()? else {
+            return Ok(Cow::Borrowed(data));
+        };
+
+        match header.kind {
+            ElfValue::Known(CompressionKind::Zlib) => {}
+            kind => return Err(ParseError::UnsupportedCompression(kind.to_u32())),
+        }
+
+        let header_size = if self.elf.is_64bit() {
+            ELF64_CHDR_SIZE
+        } else {
+            ELF32_CHDR_SIZE
+        };
+        let size = usize::try_from(header.size).map_err(|_| ParseError::InvalidCompressedData)?;
+
+        inflate::zlib_decompress(&data[header_size..], size)
+            .map(Cow::Owned)
+            .ok_or(ParseError::InvalidCompressedData)
+    }
+
+    /// Returns the compression header at the start of the section's data if the section has the
+    /// [`SectionFlag::Compressed`] flag, or [`None`] if it doesn't. Returns an error if the
+    /// header could not be read.
+    pub fn compression(&self) -> Result<Option<CompressionHeader>, ParseError> {
+        if !self.has_flag(SectionFlag::Compressed) {
+            return Ok(None);
+        }
+
+        let data = self.data()?;
+        let endianness = self.elf.endianness();
+        let (kind, size, addralign) = if self.elf.is_64bit() {
+            let header = data.get(..ELF64_CHDR_SIZE).ok_or(self.unexpected_eof())?;
+
+            // ch_reserved follows ch_type
+            (
+                endianness.u32_from_bytes(header[..4].try_into().unwrap()),
+                endianness.u64_from_bytes(header[8..16].try_into().unwrap()),
+                endianness.u64_from_bytes(header[16..24].try_into().unwrap()),
+            )
+        } else {
+            let header = data.get(..ELF32_CHDR_SIZE).ok_or(self.unexpected_eof())?;
+
+            (
+                endianness.u32_from_bytes(header[..4].try_into().unwrap()),
+                endianness
+                    .u32_from_bytes(header[4..8].try_into().unwrap())
+                    .into(),
+                endianness
+                    .u32_from_bytes(header[8..12].try_into().unwrap())
+                    .into(),
+            )
+        };
+
+        Ok(Some(CompressionHeader {
+            kind: CompressionKind::from_u32(kind).map_or(ElfValue::Unknown(kind), ElfValue::Known),
+            size,
+            addralign,
+        }))
+    }
+
     /// Returns a reference to the data of the section, or an error if it could not be read.
     pub fn data(&self) -> Result<&'data [u8], ParseError> {
         if self.size() == 0 {
@@ -546,14 +1411,71 @@ impl<'data> Section<'_, 'data> {
         }
 
         self.elf
-            .bytes()
-            .get(
-                usize::try_from(self.offset()).unwrap()
-                    ..usize::try_from(self.offset()).unwrap()
-                        + usize::try_from(self.size()).unwrap(),
-            )
-            .ok_or(ParseError::UnexpectedEof)
+            .range(self.offset(), self.size())
+            .ok_or(self.unexpected_eof())
     }
+
+    /// Returns an owned copy of the data of the section, or an error if it could not be read. The
+    /// data of [`SectionKind::Nobits`] sections isn't stored in the file, so it is `sh_size` zero
+    /// bytes, as it would be in memory.
+    pub fn to_owned_data(&self) -> Result<Vec<u8>, ParseError> {
+        if self.kind() == ElfValue::Known(SectionKind::Nobits) {
+            let size =
+                usize::try_from(self.size()).map_err(|_| ParseError::InvalidValue("sh_size"))?;
+
+            return Ok(vec![0; size]);
+        }
+
+        Ok(self.data()?.to_vec())
+    }
+
+    /// Returns an [`ParseError::UnexpectedEof`] error at the offset of the section's data.
+    fn unexpected_eof(&self) -> ParseError {
+        ParseError::UnexpectedEof {
+            offset: usize::try_from(self.offset()).unwrap_or(usize::MAX),
+        }
+    }
+}
+
+/// The header of a compressed section, returned by [`Section::compression`]. `Elf_Chdr` in the
+/// specification.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CompressionHeader {
+    /// The compression algorithm. `ch_type` in the specification.
+    pub kind: ElfValue<CompressionKind, u32>,
+    /// The size of the uncompressed data. `ch_size` in the specification.
+    pub size: u64,
+    /// The alignment of the uncompressed data. `ch_addralign` in the specification.
+    pub addralign: u64,
+}
+
+/// A structured view of a section's contents, returned by [`Section::typed`].
+#[derive(Debug, Clone)]
+pub enum TypedSection<'reader, 'data> {
+    /// A symbol table
+    Symbols(Symbols<'reader, 'data>),
+    /// A relocation table with or without addends
+    Relocations(Relocations<'reader, 'data>),
+    /// A packed relative relocation table
+    Relr(Relr<'data>),
+    /// A string table
+    Strings(Strings<'data>),
+    /// Notes
+    Notes(Notes<'reader, 'data>),
+    /// Dynamic linking information
+    Dynamic(Dynamic<'reader, 'data>),
+    /// A SysV hash table
+    Hash(HashTable<'reader, 'data>),
+    /// A GNU hash table
+    GnuHash(GnuHashTable<'reader, 'data>),
+    /// A section group
+    Group(Group<'reader, 'data>),
+    /// Symbol version definitions
+    VersionDefinitions(VersionDefinitions<'reader, 'data>),
+    /// Symbol version requirements
+    VersionRequirements(VersionRequirements<'reader, 'data>),
+    /// Any other section, as raw bytes
+    Raw(&'data [u8]),
 }
 
 /// Parses the program header table of an ELF file.
@@ -573,12 +1495,26 @@ impl<'reader, 'data> Segments<'reader, 'data> {
         };
         let header = elf.header()?;
         let phoff = usize::try_from(header.phoff()).unwrap();
-        let phnum = usize::from(header.phnum());
+        let mut phnum = usize::from(header.phnum());
+
+        // if there are too many segments for e_phnum, it is PN_XNUM and the count is stored in
+        // sh_info of the first section header, so a file without sections can't use it
+        if header.phnum() == PN_XNUM {
+            let first = elf
+                .sections()?
+                .get(0)
+                .ok_or(ParseError::InvalidValue("e_phnum"))?;
+            phnum = usize::try_from(first.info()).unwrap();
+        }
 
-        if header.phentsize() != header_size {
+        // files without program headers, such as relocatable objects, may leave this as 0
+        if phnum != 0 && header.phentsize() != header_size {
             return Err(ParseError::InvalidValue("e_phentsize"));
-        } else if phoff + phnum * usize::from(header_size) > elf.bytes().len() {
-            return Err(ParseError::UnexpectedEof);
+        } else if elf
+            .range(phoff, phnum.saturating_mul(usize::from(header_size)))
+            .is_none()
+        {
+            return Err(ParseError::UnexpectedEof { offset: phoff });
         }
 
         Ok(Self {
@@ -589,6 +1525,18 @@ impl<'reader, 'data> Segments<'reader, 'data> {
         })
     }
 
+    /// Returns the number of segments. This is `e_phnum`, unless it is `PN_XNUM` (`0xffff`), in
+    /// which case the count is stored in `sh_info` of the first section header. A file using
+    /// `PN_XNUM` without a section header table is invalid.
+    pub fn len(&self) -> usize {
+        self.phnum
+    }
+
+    /// Returns whether the program header table is empty.
+    pub fn is_empty(&self) -> bool {
+        self.phnum == 0
+    }
+
     /// Returns a [`Segment`] corresponding to the given index, or None if the index is out of
     /// bounds.
     pub fn get(&self, index: usize) -> Option<Segment<'reader, 'data>> {
@@ -603,6 +1551,106 @@ impl<'reader, 'data> Segments<'reader, 'data> {
             offset: start,
         })
     }
+
+    /// Returns an iterator over the segments of the specified type.
+    pub fn of_kind(&self, kind: SegmentKind) -> impl Iterator<Item = Segment<'reader, 'data>> {
+        self.clone()
+            .into_iter()
+            .filter(move |segment| segment.kind() == ElfValue::Known(kind))
+    }
+
+    /// Returns the [`SegmentKind::Load`] segment whose memory, `p_vaddr..p_vaddr + p_memsz`,
+    /// contains the virtual address `vaddr`, or [`None`] if there is no such segment. If several
+    /// segments contain the address, the one with the lowest `p_vaddr` is returned, and of those
+    /// the first one in the program header table.
+    pub fn covering(&self, vaddr: u64) -> Option<Segment<'reader, 'data>> {
+        self.of_kind(SegmentKind::Load)
+            .filter(|segment| {
+                vaddr
+                    .checked_sub(segment.vaddr())
+                    .is_some_and(|start| start < segment.memsz())
+            })
+            .min_by_key(Segment::vaddr)
+    }
+
+    /// Checks that the program header table describes something that can be loaded, which
+    /// bounds checks alone don't catch:
+    /// * a [`SegmentKind::Phdr`] segment must cover exactly the program header table,
+    /// * [`SegmentKind::Load`] segments must be sorted by `p_vaddr` and must not overlap in
+    ///   memory, and
+    /// * the memory of every [`SegmentKind::Dynamic`], [`SegmentKind::Interp`],
+    ///   [`SegmentKind::Note`], and [`SegmentKind::Phdr`] segment, and the initialization image
+    ///   (`p_filesz`) of a [`SegmentKind::Tls`] segment, must be contained in a
+    ///   [`SegmentKind::Load`] segment. Segments of unknown types, such as `PT_GNU_STACK`, are not
+    ///   checked, since their memory isn't necessarily loaded.
+    ///
+    /// Returns [`ParseError::OverlappingSegments`] for overlapping segments, and
+    /// [`ParseError::InvalidSegment`] naming the segment and the field for the first other
+    /// violation.
+    pub fn check(&self) -> Result<(), ParseError> {
+        let invalid = |index, field| ParseError::InvalidSegment { index, field };
+        let table_size = u64::try_from(self.phnum * self.header_size).unwrap();
+        let end_of = |segment: &Segment| segment.vaddr().checked_add(segment.memsz());
+        let mut previous = None;
+
+        for (index, segment) in self.clone().into_iter().enumerate() {
+            let end = end_of(&segment).ok_or(invalid(index, "p_memsz"))?;
+
+            match segment.kind() {
+                ElfValue::Known(SegmentKind::Phdr) => {
+                    if segment.offset() != u64::try_from(self.phoff).unwrap() {
+                        return Err(invalid(index, "p_offset"));
+                    } else if segment.filesz() != table_size {
+                        return Err(invalid(index, "p_filesz"));
+                    }
+                }
+                ElfValue::Known(SegmentKind::Load) => {
+                    if let Some((vaddr, previous_end)) = previous {
+                        if segment.vaddr() < vaddr {
+                            return Err(invalid(index, "p_vaddr"));
+                        } else if segment.vaddr() < previous_end {
+                            return Err(ParseError::OverlappingSegments);
+                        }
+                    }
+
+                    previous = Some((segment.vaddr(), end));
+                }
+                _ => {}
+            }
+        }
+
+        for (index, segment) in self.clone().into_iter().enumerate() {
+            // only the initialization image of the TLS segment is in memory, the rest of it is
+            // allocated for each thread
+            let size = match segment.kind() {
+                ElfValue::Known(SegmentKind::Tls) => segment.filesz(),
+                ElfValue::Known(
+                    SegmentKind::Dynamic
+                    | SegmentKind::Interp
+                    | SegmentKind::Note
+                    | SegmentKind::Phdr,
+                ) => segment.memsz(),
+                _ => continue,
+            };
+            if size == 0 {
+                continue;
+            }
+
+            let end = segment
+                .vaddr()
+                .checked_add(size)
+                .ok_or(invalid(index, "p_filesz"))?;
+            let contained = self.of_kind(SegmentKind::Load).any(|load| {
+                load.vaddr() <= segment.vaddr()
+                    && end_of(&load).is_some_and(|load_end| end <= load_end)
+            });
+            if !contained {
+                return Err(invalid(index, "p_vaddr"));
+            }
+        }
+
+        Ok(())
+    }
 }
 
 impl<'reader, 'data> IntoIterator for Segments<'reader, 'data> {
@@ -642,7 +1690,7 @@ pub struct Segment<'reader, 'data> {
     offset: usize,
 }
 
-impl<'data> Segment<'_, 'data> {
+impl<'reader, 'data> Segment<'reader, 'data> {
     fn read_u32(&self, offset: usize) -> u32 {
         self.elf.read_u32(self.offset + offset).unwrap()
     }
@@ -653,9 +1701,7 @@ impl<'data> Segment<'_, 'data> {
 
     /// Type of segment. `p_type` in the specification.
     pub fn kind(&self) -> ElfValue<SegmentKind, u32> {
-        let value = self.read_u32(0);
-
-        SegmentKind::from_u32(value).map_or(ElfValue::Unknown(value), ElfValue::Known)
+        SegmentKind::from_raw(self.read_u32(0))
     }
 
     /// The offset at which the segment's data is located in the ELF file. This, in conjuction with
@@ -720,6 +1766,30 @@ impl<'data> Segment<'_, 'data> {
         FlagSet::new(value).map_or(ElfValue::Unknown(value), ElfValue::Known)
     }
 
+    /// Whether the segment has a flag. Unknown flags, such as OS-specific ones, don't affect the
+    /// result.
+    pub fn has_flag(&self, flag: SegmentFlag) -> bool {
+        match self.flags() {
+            ElfValue::Known(flags) => flags.contains(flag),
+            ElfValue::Unknown(flags) => flags & FlagSet::from(flag).bits() != 0,
+        }
+    }
+
+    /// Whether the segment is readable at runtime, [`SegmentFlag::Read`].
+    pub fn is_readable(&self) -> bool {
+        self.has_flag(SegmentFlag::Read)
+    }
+
+    /// Whether the segment is writable at runtime, [`SegmentFlag::Write`].
+    pub fn is_writable(&self) -> bool {
+        self.has_flag(SegmentFlag::Write)
+    }
+
+    /// Whether the segment is executable, [`SegmentFlag::Execute`].
+    pub fn is_executable(&self) -> bool {
+        self.has_flag(SegmentFlag::Execute)
+    }
+
     /// The required alignment of the virtual and physical address the segment is loaded at during
     /// execution. `p_align` in the specification.
     pub fn align(&self) -> u64 {
@@ -730,6 +1800,28 @@ impl<'data> Segment<'_, 'data> {
         }
     }
 
+    /// Returns whether the alignment of the segment is valid: `p_align` is 0 or a power of two,
+    /// and `p_vaddr` is congruent to `p_offset` modulo `p_align`, so that the segment can be
+    /// mapped from the file with pages of that size. A `p_align` of 0 or 1 means the segment has
+    /// no alignment constraint, and such a segment is always well aligned.
+    pub fn is_well_aligned(&self) -> bool {
+        let align = self.align();
+
+        align <= 1
+            || (align.is_power_of_two()
+                && self.vaddr() & (align - 1) == self.offset() & (align - 1))
+    }
+
+    /// Returns a [`Notes`] reader for the segment, or an error if the segment is not a note segment
+    /// or its data could not be read.
+    pub fn notes(&self) -> Result<Notes<'reader, 'data>, ParseError> {
+        if self.kind() != ElfValue::Known(SegmentKind::Note) {
+            return Err(ParseError::InvalidValue("p_type"));
+        }
+
+        Ok(Notes::new(self.elf, self.data()?, self.align()))
+    }
+
     /// Returns a reference to the segment's bytes stored in the ELF file, as dictated by
     /// [`Segment::offset`] and [`Segment::filesz`].
     pub fn data(&self) -> Result<&'data [u8], ParseError> {
@@ -738,13 +1830,18 @@ impl<'data> Segment<'_, 'data> {
         }
 
         self.elf
-            .bytes()
-            .get(
-                usize::try_from(self.offset()).unwrap()
-                    ..usize::try_from(self.offset()).unwrap()
-                        + usize::try_from(self.filesz()).unwrap(),
-            )
-            .ok_or(ParseError::UnexpectedEof)
+            .range(self.offset(), self.filesz())
+            .ok_or(ParseError::UnexpectedEof {
+                offset: usize::try_from(self.offset()).unwrap_or(usize::MAX),
+            })
+    }
+
+    /// Returns an [`ElfReader`] for an ELF file embedded in the segment's data, such as a payload
+    /// in a firmware image, or [`None`] if the data could not be read or isn't recognized as an ELF
+    /// file by [`ElfReader::new`]. Offsets in the embedded file are relative to the start of the
+    /// segment.
+    pub fn as_embedded_elf(&self) -> Option<ElfReader<'data>> {
+        ElfReader::new(self.data().ok()?).ok()
     }
 }
 
@@ -806,8 +1903,19 @@ impl<K: ToPrimitive> ElfValue<K, u32> {
     }
 }
 
+/// Splits a symbol's `st_info` field into the binding, stored in the high four bits, and the type,
+/// stored in the low four bits.
+pub fn split_symbol_info(info: u8) -> (ElfValue<SymbolBinding, u8>, ElfValue<SymbolKind, u8>) {
+    let (binding, kind) = (info >> 4, info & 0xf);
+
+    (
+        SymbolBinding::from_u8(binding).map_or(ElfValue::Unknown(binding), ElfValue::Known),
+        SymbolKind::from_u8(kind).map_or(ElfValue::Unknown(kind), ElfValue::Known),
+    )
+}
+
 /// Represents an error that can occur in the parsing of an ELF file.
-#[derive(Debug, Error, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, Error, PartialEq, Eq)]
 pub enum ParseError {
     /// The ELF header was invalid
     #[error("invalid header")]
@@ -815,9 +1923,31 @@ pub enum ParseError {
     /// A field in the ELF file had an invalid value. The string contains the name of the field.
     #[error("invalid value in field {0}")]
     InvalidValue(&'static str),
-    /// Data was shorter than expected
-    #[error("unexpected end of file")]
-    UnexpectedEof,
+    /// Data was shorter than expected. `offset` is the position in the file of the data that
+    /// extends past the end of the file.
+    #[error("unexpected end of file at byte 0x{offset:x}")]
+    UnexpectedEof {
+        /// The file offset of the data that could not be read
+        offset: usize,
+    },
+    /// A section was compressed with an algorithm that isn't supported. The value is `ch_type`.
+    #[error("unsupported compression type {0}")]
+    UnsupportedCompression(u32),
+    /// The compressed data of a section was invalid
+    #[error("invalid compressed data")]
+    InvalidCompressedData,
+    /// Two [`SegmentKind::Load`] segments occupied the same memory
+    #[error("overlapping segments")]
+    OverlappingSegments,
+    /// A segment was inconsistent with the program header table or with the other segments, as
+    /// found by [`Segments::check`].
+    #[error("invalid value in field {field} of segment {index}")]
+    InvalidSegment {
+        /// The index of the segment in the program header table
+        index: usize,
+        /// The name of the field
+        field: &'static str,
+    },
 }
 
 #[cfg(test)]
@@ -827,7 +1957,10 @@ mod tests {
     #[test]
     fn reader_new() {
         assert!(ElfReader::new(&[]).is_err());
-        assert!(ElfReader::new(&[0x7f, b'E', b'L', b'F']).is_err());
+        assert_eq!(
+            ElfReader::new(&[0x7f, b'E', b'L', b'F']).unwrap_err(),
+            ParseError::UnexpectedEof { offset: EI_CLASS }
+        );
         assert!(
             ElfReader::new(&[0x7f, b'E', b'L', b'F', 3, 1, 1, 0, 0, 0, 0, 0, 0, 0, 0, 0]).is_err()
         );
@@ -843,6 +1976,28 @@ mod tests {
         );
     }
 
+    #[test]
+    fn symbol_info() {
+        assert_eq!(
+            split_symbol_info(0x12),
+            (
+                ElfValue::Known(SymbolBinding::Global),
+                ElfValue::Known(SymbolKind::Func)
+            )
+        );
+        assert_eq!(
+            split_symbol_info(0x03),
+            (
+                ElfValue::Known(SymbolBinding::Local),
+                ElfValue::Known(SymbolKind::Section)
+            )
+        );
+        assert_eq!(
+            split_symbol_info(0xaf),
+            (ElfValue::Unknown(0xa), ElfValue::Unknown(0xf))
+        );
+    }
+
     #[test]
     fn header_parse() {
         let bytes = &[
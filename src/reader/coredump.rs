@@ -0,0 +1,246 @@
+use crate::{Endianness, MachineKind};
+
+/// The status of a thread in a core file, stored in an `NT_PRSTATUS` note. This is the Linux
+/// `elf_prstatus` structure. The CPU times it contains are not parsed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PrStatus {
+    /// The number of the signal that caused the dump. `si_signo` in `pr_info`.
+    pub signo: i32,
+    /// The signal code of the signal. `si_code` in `pr_info`.
+    pub code: i32,
+    /// The error number associated with the signal. `si_errno` in `pr_info`.
+    pub errno: i32,
+    /// The signal that was being delivered to the thread. `pr_cursig`.
+    pub cursig: u16,
+    /// The set of pending signals. `pr_sigpend`.
+    pub sigpend: u64,
+    /// The set of blocked signals. `pr_sighold`.
+    pub sighold: u64,
+    /// The ID of the thread. `pr_pid`.
+    pub pid: i32,
+    /// The ID of the parent process. `pr_ppid`.
+    pub ppid: i32,
+    /// The ID of the process group. `pr_pgrp`.
+    pub pgrp: i32,
+    /// The ID of the session. `pr_sid`.
+    pub sid: i32,
+    /// The general-purpose registers of the thread. `pr_reg`.
+    pub registers: Registers,
+}
+
+/// The general-purpose registers stored in an `NT_PRSTATUS` note, whose layout depends on the
+/// architecture.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Registers {
+    /// The registers of an x86-64 thread.
+    X86_64(X86_64Registers),
+    /// The registers of a RISC-V thread. The registers are 32 bits wide in 32-bit files.
+    RiscV(RiscvRegisters),
+}
+
+/// The general-purpose registers of an x86-64 thread, in the order of `user_regs_struct`.
+#[allow(missing_docs)]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct X86_64Registers {
+    pub r15: u64,
+    pub r14: u64,
+    pub r13: u64,
+    pub r12: u64,
+    pub rbp: u64,
+    pub rbx: u64,
+    pub r11: u64,
+    pub r10: u64,
+    pub r9: u64,
+    pub r8: u64,
+    pub rax: u64,
+    pub rcx: u64,
+    pub rdx: u64,
+    pub rsi: u64,
+    pub rdi: u64,
+    pub orig_rax: u64,
+    pub rip: u64,
+    pub cs: u64,
+    pub eflags: u64,
+    pub rsp: u64,
+    pub ss: u64,
+    pub fs_base: u64,
+    pub gs_base: u64,
+    pub ds: u64,
+    pub es: u64,
+    pub fs: u64,
+    pub gs: u64,
+}
+
+/// The general-purpose registers of a RISC-V thread, in the order of `user_regs_struct`. The
+/// program counter takes the place of the hardwired zero register `x0`.
+#[allow(missing_docs)]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RiscvRegisters {
+    pub pc: u64,
+    pub ra: u64,
+    pub sp: u64,
+    pub gp: u64,
+    pub tp: u64,
+    pub t0: u64,
+    pub t1: u64,
+    pub t2: u64,
+    pub s0: u64,
+    pub s1: u64,
+    pub a0: u64,
+    pub a1: u64,
+    pub a2: u64,
+    pub a3: u64,
+    pub a4: u64,
+    pub a5: u64,
+    pub a6: u64,
+    pub a7: u64,
+    pub s2: u64,
+    pub s3: u64,
+    pub s4: u64,
+    pub s5: u64,
+    pub s6: u64,
+    pub s7: u64,
+    pub s8: u64,
+    pub s9: u64,
+    pub s10: u64,
+    pub s11: u64,
+    pub t3: u64,
+    pub t4: u64,
+    pub t5: u64,
+    pub t6: u64,
+}
+
+impl PrStatus {
+    /// Parses the descriptor of an `NT_PRSTATUS` note. Returns [`None`] if the machine is not
+    /// supported or the descriptor is too short.
+    pub(super) fn parse(
+        desc: &[u8],
+        endianness: Endianness,
+        is_64bit: bool,
+        machine: MachineKind,
+    ) -> Option<Self> {
+        let word_size = if is_64bit { 8 } else { 4 };
+
+        let read_i32 = |offset: usize| {
+            desc.get(offset..offset + 4)
+                .map(|bytes| endianness.u32_from_bytes(bytes.try_into().unwrap()) as i32)
+        };
+        let read_word = |offset: usize| {
+            if is_64bit {
+                desc.get(offset..offset + 8)
+                    .map(|bytes| endianness.u64_from_bytes(bytes.try_into().unwrap()))
+            } else {
+                desc.get(offset..offset + 4)
+                    .map(|bytes| endianness.u32_from_bytes(bytes.try_into().unwrap()).into())
+            }
+        };
+        // `pr_info` and `pr_cursig` are followed by padding up to the word-sized `pr_sigpend`,
+        // and the four IDs by four `timeval`s of two words each
+        let ids_offset = 16 + 2 * word_size;
+        let registers_offset = ids_offset + 16 + 8 * word_size;
+        let register = |index: usize| read_word(registers_offset + index * word_size);
+
+        let registers = match machine {
+            MachineKind::X86_64 if is_64bit => {
+                let r: [u64; 27] = read_registers(register)?;
+
+                Registers::X86_64(X86_64Registers {
+                    r15: r[0],
+                    r14: r[1],
+                    r13: r[2],
+                    r12: r[3],
+                    rbp: r[4],
+                    rbx: r[5],
+                    r11: r[6],
+                    r10: r[7],
+                    r9: r[8],
+                    r8: r[9],
+                    rax: r[10],
+                    rcx: r[11],
+                    rdx: r[12],
+                    rsi: r[13],
+                    rdi: r[14],
+                    orig_rax: r[15],
+                    rip: r[16],
+                    cs: r[17],
+                    eflags: r[18],
+                    rsp: r[19],
+                    ss: r[20],
+                    fs_base: r[21],
+                    gs_base: r[22],
+                    ds: r[23],
+                    es: r[24],
+                    fs: r[25],
+                    gs: r[26],
+                })
+            }
+            MachineKind::RiscV => {
+                let r: [u64; 32] = read_registers(register)?;
+
+                Registers::RiscV(RiscvRegisters {
+                    pc: r[0],
+                    ra: r[1],
+                    sp: r[2],
+                    gp: r[3],
+                    tp: r[4],
+                    t0: r[5],
+                    t1: r[6],
+                    t2: r[7],
+                    s0: r[8],
+                    s1: r[9],
+                    a0: r[10],
+                    a1: r[11],
+                    a2: r[12],
+                    a3: r[13],
+                    a4: r[14],
+                    a5: r[15],
+                    a6: r[16],
+                    a7: r[17],
+                    s2: r[18],
+                    s3: r[19],
+                    s4: r[20],
+                    s5: r[21],
+                    s6: r[22],
+                    s7: r[23],
+                    s8: r[24],
+                    s9: r[25],
+                    s10: r[26],
+                    s11: r[27],
+                    t3: r[28],
+                    t4: r[29],
+                    t5: r[30],
+                    t6: r[31],
+                })
+            }
+            _ => return None,
+        };
+
+        Some(Self {
+            signo: read_i32(0)?,
+            code: read_i32(4)?,
+            errno: read_i32(8)?,
+            cursig: desc
+                .get(12..14)
+                .map(|bytes| endianness.u16_from_bytes(bytes.try_into().unwrap()))?,
+            sigpend: read_word(16)?,
+            sighold: read_word(16 + word_size)?,
+            pid: read_i32(ids_offset)?,
+            ppid: read_i32(ids_offset + 4)?,
+            pgrp: read_i32(ids_offset + 8)?,
+            sid: read_i32(ids_offset + 12)?,
+            registers,
+        })
+    }
+}
+
+/// Reads `N` consecutive registers, or returns [`None`] if any of them is out of bounds.
+fn read_registers<const N: usize>(register: impl Fn(usize) -> Option<u64>) -> Option<[u64; N]> {
+    let mut registers = [0; N];
+
+    for (index, value) in registers.iter_mut().enumerate() {
+        *value = register(index)?;
+    }
+
+    Some(registers)
+}
@@ -0,0 +1,183 @@
+use alloc::vec::Vec;
+use core::ffi::CStr;
+
+use num_traits::FromPrimitive;
+
+use crate::{
+    consts::{DynTag, ELF32_DYN_SIZE, ELF64_DYN_SIZE},
+    SectionKind, SegmentKind,
+};
+
+use super::{ElfReader, ElfValue, ParseError, Section, Strings};
+
+/// A reader for the dynamic linking information in an `SHT_DYNAMIC` section.
+#[derive(Debug, Clone)]
+pub struct Dynamic<'reader, 'data> {
+    elf: &'reader ElfReader<'data>,
+    entry_size: usize,
+    offset: usize,
+    count: usize,
+}
+
+impl<'reader, 'data> Dynamic<'reader, 'data> {
+    pub(super) fn new(section: &Section<'reader, 'data>) -> Result<Self, ParseError> {
+        let elf = section.elf;
+
+        if section.kind() != ElfValue::Known(SectionKind::Dynamic) {
+            return Err(ParseError::InvalidValue("sh_type"));
+        }
+
+        let entry_size = match elf.is_64bit() {
+            true => ELF64_DYN_SIZE,
+            false => ELF32_DYN_SIZE,
+        };
+
+        if section.entsize() != u64::from(entry_size) {
+            return Err(ParseError::InvalidValue("sh_entsize"));
+        }
+
+        let data = section.data()?;
+
+        Ok(Self {
+            elf,
+            entry_size: entry_size.into(),
+            offset: usize::try_from(section.offset()).unwrap(),
+            count: data.len() / usize::from(entry_size),
+        })
+    }
+
+    /// Returns a [`DynamicEntry`] at the specified index in the dynamic array, or [`None`] if the
+    /// index is out of bounds. Entries after the terminating `DT_NULL` entry can be accessed.
+    pub fn get(&self, index: usize) -> Option<DynamicEntry<'reader, 'data>> {
+        if index >= self.count {
+            return None;
+        }
+
+        Some(DynamicEntry {
+            elf: self.elf,
+            offset: self.offset + self.entry_size * index,
+        })
+    }
+
+    /// Returns the value of the first entry with the given tag before the terminating `DT_NULL`
+    /// entry, or [`None`] if there is no such entry.
+    pub fn find(&self, tag: DynTag) -> Option<u64> {
+        self.clone()
+            .into_iter()
+            .find(|entry| entry.tag() == ElfValue::Known(tag))
+            .map(|entry| entry.value())
+    }
+
+    /// Returns the names of the shared libraries the object depends on, the values of the
+    /// `DT_NEEDED` entries, in order. The names are read from the dynamic string table at the
+    /// address in `DT_STRTAB`, which is looked up in the [`SegmentKind::Load`] segments, and is at
+    /// most `DT_STRSZ` bytes long if that entry exists.
+    ///
+    /// Returns an error if there is no `DT_STRTAB` entry, no loadable segment contains its
+    /// address, or a name is not in the string table.
+    pub fn needed_libraries(&self) -> Result<Vec<&'data CStr>, ParseError> {
+        let strings = self.strings()?;
+
+        self.clone()
+            .into_iter()
+            .filter(|entry| entry.tag() == ElfValue::Known(DynTag::Needed))
+            .map(|entry| {
+                strings
+                    .get_cstr(entry.value())
+                    .ok_or(ParseError::InvalidValue("DT_NEEDED"))
+            })
+            .collect()
+    }
+
+    /// Returns the dynamic string table at the address in `DT_STRTAB`.
+    fn strings(&self) -> Result<Strings<'data>, ParseError> {
+        let address = self
+            .find(DynTag::StrTab)
+            .ok_or(ParseError::InvalidValue("DT_STRTAB"))?;
+
+        for segment in self.elf.segments()?.of_kind(SegmentKind::Load) {
+            let Some(start) = address
+                .checked_sub(segment.vaddr())
+                .filter(|&start| start < segment.filesz())
+            else {
+                continue;
+            };
+
+            let mut data = &segment.data()?[usize::try_from(start).unwrap()..];
+            if let Some(size) = self.find(DynTag::StrSz) {
+                data = &data[..data.len().min(usize::try_from(size).unwrap_or(usize::MAX))];
+            }
+
+            return Ok(Strings { data });
+        }
+
+        Err(ParseError::InvalidValue("DT_STRTAB"))
+    }
+}
+
+impl<'reader, 'data> IntoIterator for Dynamic<'reader, 'data> {
+    type Item = DynamicEntry<'reader, 'data>;
+    type IntoIter = DynamicIter<'reader, 'data>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        DynamicIter {
+            dynamic: self,
+            index: 0,
+        }
+    }
+}
+
+/// An iterator over the entries in a dynamic array. The iteration ends at the first `DT_NULL`
+/// entry, which is not returned.
+#[derive(Debug, Clone)]
+pub struct DynamicIter<'reader, 'data> {
+    dynamic: Dynamic<'reader, 'data>,
+    index: usize,
+}
+
+impl<'reader, 'data> Iterator for DynamicIter<'reader, 'data> {
+    type Item = DynamicEntry<'reader, 'data>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let entry = self.dynamic.get(self.index)?;
+
+        if entry.tag() == ElfValue::Known(DynTag::Null) {
+            self.index = self.dynamic.count;
+            return None;
+        }
+
+        self.index += 1;
+
+        Some(entry)
+    }
+}
+
+/// An entry in the dynamic array.
+#[derive(Debug, Clone)]
+pub struct DynamicEntry<'reader, 'data> {
+    elf: &'reader ElfReader<'data>,
+    offset: usize,
+}
+
+impl DynamicEntry<'_, '_> {
+    fn read_word(&self, index: usize) -> u64 {
+        if self.elf.is_64bit() {
+            self.elf.read_u64(self.offset + index * 8).unwrap()
+        } else {
+            self.elf.read_u32(self.offset + index * 4).unwrap().into()
+        }
+    }
+
+    /// The type of the entry, which determines how the value is interpreted. `d_tag` in the
+    /// specification.
+    pub fn tag(&self) -> ElfValue<DynTag, u64> {
+        let value = self.read_word(0);
+
+        DynTag::from_u64(value).map_or(ElfValue::Unknown(value), ElfValue::Known)
+    }
+
+    /// The value of the entry, either an integer or an address. `d_un` in the specification.
+    pub fn value(&self) -> u64 {
+        self.read_word(1)
+    }
+}
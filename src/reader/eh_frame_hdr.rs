@@ -0,0 +1,280 @@
+use crate::Endianness;
+
+use super::{ElfReader, ParseError};
+
+/// The pointer encoding of a value that is not present, `DW_EH_PE_omit`
+const DW_EH_PE_OMIT: u8 = 0xff;
+
+/// A reader for the `.eh_frame_hdr` section, which is also the data of the `PT_GNU_EH_FRAME`
+/// segment. It points to the `.eh_frame` section and usually contains a table of the initial
+/// locations of the frame description entries (FDEs) in `.eh_frame`, sorted so that the FDE of an
+/// address can be found with a binary search during stack unwinding.
+///
+/// The values are stored with DWARF pointer encodings (`DW_EH_PE_*`), which the reader decodes
+/// into addresses. Absolute values and values relative to the field (`DW_EH_PE_pcrel`) or to the
+/// start of the section (`DW_EH_PE_datarel`) are supported. The entries of the table must have a
+/// fixed size, so that they can be searched.
+#[derive(Debug, Clone)]
+pub struct EhFrameHdr<'data> {
+    data: &'data [u8],
+    vaddr: u64,
+    offset: usize,
+    endianness: Endianness,
+    is_64bit: bool,
+    eh_frame_ptr: u64,
+    fde_count: usize,
+    /// The position of the table in the data
+    table_offset: usize,
+    /// The size of each of the two values in a table entry
+    value_size: usize,
+}
+
+impl<'data> EhFrameHdr<'data> {
+    /// Parses the header from `data`, which is loaded at `vaddr` and located at `offset` in the
+    /// file.
+    pub(super) fn new(
+        elf: &ElfReader<'data>,
+        data: &'data [u8],
+        vaddr: u64,
+        offset: u64,
+    ) -> Result<Self, ParseError> {
+        let mut header = Self {
+            data,
+            vaddr,
+            offset: usize::try_from(offset).unwrap_or(usize::MAX),
+            endianness: elf.endianness(),
+            is_64bit: elf.is_64bit(),
+            eh_frame_ptr: 0,
+            fde_count: 0,
+            table_offset: 0,
+            value_size: 0,
+        };
+
+        // version and the three encodings
+        if data.len() < 4 {
+            return Err(header.unexpected_eof(data.len()));
+        } else if header.version() != 1 {
+            return Err(ParseError::InvalidValue("version"));
+        }
+
+        let (eh_frame_ptr, position) =
+            header.read_encoded(header.eh_frame_ptr_encoding(), 4, "eh_frame_ptr_enc")?;
+        header.eh_frame_ptr = eh_frame_ptr;
+
+        // without a count or a table encoding, there is no table to search
+        let table_encoding = header.table_encoding();
+        if header.fde_count_encoding() == DW_EH_PE_OMIT || table_encoding == DW_EH_PE_OMIT {
+            return Ok(header);
+        }
+
+        let (fde_count, position) =
+            header.read_encoded(header.fde_count_encoding(), position, "fde_count_enc")?;
+
+        header.value_size = match table_encoding & 0x8f {
+            0x00 => header.word_size(),
+            0x02 | 0x0a => 2,
+            0x03 | 0x0b => 4,
+            0x04 | 0x0c => 8,
+            _ => return Err(ParseError::InvalidValue("table_enc")),
+        };
+        header.table_offset = position;
+        header.fde_count = usize::try_from(fde_count)
+            .ok()
+            .filter(|&count| {
+                count
+                    .checked_mul(header.value_size * 2)
+                    .and_then(|size| size.checked_add(position))
+                    .is_some_and(|end| end <= data.len())
+            })
+            .ok_or(ParseError::InvalidValue("fde_count"))?;
+
+        Ok(header)
+    }
+
+    /// The version of the header, which is 1.
+    pub fn version(&self) -> u8 {
+        self.data[0]
+    }
+
+    /// The pointer encoding of `eh_frame_ptr`, `eh_frame_ptr_enc` in the specification.
+    pub fn eh_frame_ptr_encoding(&self) -> u8 {
+        self.data[1]
+    }
+
+    /// The pointer encoding of the number of entries in the table, `fde_count_enc` in the
+    /// specification. `DW_EH_PE_omit` (`0xff`) means there is no table.
+    pub fn fde_count_encoding(&self) -> u8 {
+        self.data[2]
+    }
+
+    /// The pointer encoding of the values in the table, `table_enc` in the specification.
+    /// `DW_EH_PE_omit` (`0xff`) means there is no table.
+    pub fn table_encoding(&self) -> u8 {
+        self.data[3]
+    }
+
+    /// The address of the `.eh_frame` section. `eh_frame_ptr` in the specification.
+    pub fn eh_frame_ptr(&self) -> u64 {
+        self.eh_frame_ptr
+    }
+
+    /// The number of entries in the binary search table. `fde_count` in the specification.
+    pub fn fde_count(&self) -> usize {
+        self.fde_count
+    }
+
+    /// Returns the entry of the table at the given index as the initial location, the first
+    /// address covered by an FDE, and the address of the FDE in `.eh_frame`. Returns [`None`] if
+    /// the index is out of bounds.
+    pub fn get(&self, index: usize) -> Option<(u64, u64)> {
+        if index >= self.fde_count {
+            return None;
+        }
+
+        let start = self.table_offset + index * self.value_size * 2;
+        let encoding = self.table_encoding();
+        let (location, position) = self.read_encoded(encoding, start, "table_enc").ok()?;
+        let (fde, _) = self.read_encoded(encoding, position, "table_enc").ok()?;
+
+        Some((location, fde))
+    }
+
+    /// Returns an iterator over the entries of the table, as returned by [`EhFrameHdr::get`].
+    pub fn entries(&self) -> impl Iterator<Item = (u64, u64)> + '_ {
+        (0..self.fde_count).map_while(|index| self.get(index))
+    }
+
+    /// Returns the address of the FDE that covers `pc`, the FDE with the highest initial location
+    /// less than or equal to `pc`, using a binary search. Returns [`None`] if `pc` is before the
+    /// first entry or there is no table. The end of the FDE's range is stored in the FDE itself, so
+    /// `pc` may still be past it.
+    pub fn lookup(&self, pc: u64) -> Option<u64> {
+        let (mut low, mut high) = (0, self.fde_count);
+
+        // finds the first entry whose initial location is after pc
+        while low < high {
+            let middle = low + (high - low) / 2;
+
+            if self.get(middle)?.0 <= pc {
+                low = middle + 1;
+            } else {
+                high = middle;
+            }
+        }
+
+        Some(self.get(low.checked_sub(1)?)?.1)
+    }
+
+    fn word_size(&self) -> usize {
+        if self.is_64bit {
+            8
+        } else {
+            4
+        }
+    }
+
+    fn byte(&self, position: usize) -> Result<u8, ParseError> {
+        self.data
+            .get(position)
+            .copied()
+            .ok_or(self.unexpected_eof(position))
+    }
+
+    fn unexpected_eof(&self, position: usize) -> ParseError {
+        ParseError::UnexpectedEof {
+            offset: self.offset.saturating_add(position),
+        }
+    }
+
+    /// Reads a value with a DWARF pointer encoding at `position` in the data. Returns the value
+    /// and the position after it. `field` names the encoding in errors.
+    fn read_encoded(
+        &self,
+        encoding: u8,
+        position: usize,
+        field: &'static str,
+    ) -> Result<(u64, usize), ParseError> {
+        let fixed = |size: usize| -> Result<(u64, usize), ParseError> {
+            let bytes = position
+                .checked_add(size)
+                .and_then(|end| self.data.get(position..end))
+                .ok_or(self.unexpected_eof(position))?;
+            let signed = encoding & 0x08 != 0;
+
+            let value = match (size, signed) {
+                (2, false) => self
+                    .endianness
+                    .u16_from_bytes(bytes.try_into().unwrap())
+                    .into(),
+                (2, true) => {
+                    i64::from(self.endianness.u16_from_bytes(bytes.try_into().unwrap()) as i16)
+                        as u64
+                }
+                (4, false) => self
+                    .endianness
+                    .u32_from_bytes(bytes.try_into().unwrap())
+                    .into(),
+                (4, true) => {
+                    i64::from(self.endianness.u32_from_bytes(bytes.try_into().unwrap()) as i32)
+                        as u64
+                }
+                _ => self.endianness.u64_from_bytes(bytes.try_into().unwrap()),
+            };
+
+            Ok((value, position + size))
+        };
+
+        let (value, end) = match encoding & 0x0f {
+            0x00 if self.is_64bit => fixed(8)?,
+            0x00 => fixed(4)?,
+            0x01 => self.leb128(position, false)?,
+            0x02 | 0x0a => fixed(2)?,
+            0x03 | 0x0b => fixed(4)?,
+            0x04 | 0x0c => fixed(8)?,
+            0x09 => self.leb128(position, true)?,
+            _ => return Err(ParseError::InvalidValue(field)),
+        };
+
+        // indirect values would have to be read from memory
+        let base = match encoding & 0xf0 {
+            0x00 => 0,
+            0x10 => self.vaddr.wrapping_add(u64::try_from(position).unwrap()),
+            0x30 => self.vaddr,
+            _ => return Err(ParseError::InvalidValue(field)),
+        };
+        let value = base.wrapping_add(value);
+
+        Ok((
+            if self.is_64bit {
+                value
+            } else {
+                value & 0xffff_ffff
+            },
+            end,
+        ))
+    }
+
+    /// Reads an unsigned or signed LEB128 value at `position`.
+    fn leb128(&self, mut position: usize, signed: bool) -> Result<(u64, usize), ParseError> {
+        let mut value = 0u64;
+        let mut shift = 0;
+
+        loop {
+            let byte = self.byte(position)?;
+            position += 1;
+
+            if shift < 64 {
+                value |= u64::from(byte & 0x7f) << shift;
+            }
+            shift += 7;
+
+            if byte & 0x80 == 0 {
+                if signed && shift < 64 && byte & 0x40 != 0 {
+                    value |= u64::MAX << shift;
+                }
+
+                return Ok((value, position));
+            }
+        }
+    }
+}
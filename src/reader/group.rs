@@ -0,0 +1,72 @@
+use flagset::FlagSet;
+
+use crate::{Endianness, GroupFlag, SectionKind};
+
+use super::{ElfReader, ElfValue, ParseError, Section};
+
+/// A reader for a section group, `SHT_GROUP`, such as a COMDAT group. The group's signature is
+/// the name of the symbol at index `sh_info` in the symbol table `sh_link` refers to.
+#[derive(Debug, Clone)]
+pub struct Group<'reader, 'data> {
+    elf: &'reader ElfReader<'data>,
+    data: &'data [u8],
+}
+
+impl<'reader, 'data> Group<'reader, 'data> {
+    pub(super) fn new(section: &Section<'reader, 'data>) -> Result<Self, ParseError> {
+        if section.kind() != ElfValue::Known(SectionKind::Group) {
+            return Err(ParseError::InvalidValue("sh_type"));
+        }
+
+        let data = section.data()?;
+
+        if data.len() < 4 || data.len() % 4 != 0 {
+            return Err(ParseError::InvalidValue("sh_size"));
+        }
+
+        Ok(Self {
+            elf: section.elf,
+            data,
+        })
+    }
+
+    /// The flags of the group, stored in the first word of the section.
+    pub fn flags(&self) -> ElfValue<FlagSet<GroupFlag>, u32> {
+        let value = self
+            .elf
+            .endianness()
+            .u32_from_bytes(self.data[..4].try_into().unwrap());
+
+        FlagSet::new(value).map_or(ElfValue::Unknown(value), ElfValue::Known)
+    }
+}
+
+impl<'data> IntoIterator for Group<'_, 'data> {
+    type Item = u32;
+    type IntoIter = GroupIter<'data>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        GroupIter {
+            endianness: self.elf.endianness(),
+            data: &self.data[4..],
+        }
+    }
+}
+
+/// An iterator over the section header indices of the members of a section group.
+#[derive(Debug, Clone)]
+pub struct GroupIter<'data> {
+    endianness: Endianness,
+    data: &'data [u8],
+}
+
+impl Iterator for GroupIter<'_> {
+    type Item = u32;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (index, rest) = self.data.split_first_chunk::<4>()?;
+        self.data = rest;
+
+        Some(self.endianness.u32_from_bytes(*index))
+    }
+}
@@ -0,0 +1,316 @@
+use crate::SectionKind;
+
+use super::{ElfReader, ElfValue, ParseError, Section, Strings, Symbols};
+
+/// A reader for a SysV hash table section, `SHT_HASH`, which is used to look up symbols in the
+/// dynamic symbol table.
+#[derive(Debug, Clone)]
+pub struct HashTable<'reader, 'data> {
+    elf: &'reader ElfReader<'data>,
+    offset: usize,
+    bucket_count: u32,
+    chain_count: u32,
+    symbols: Symbols<'reader, 'data>,
+    strings: Strings<'data>,
+}
+
+impl<'reader, 'data> HashTable<'reader, 'data> {
+    pub(super) fn new(section: &Section<'reader, 'data>) -> Result<Self, ParseError> {
+        let elf = section.elf;
+
+        if section.kind() != ElfValue::Known(SectionKind::Hash) {
+            return Err(ParseError::InvalidValue("sh_type"));
+        }
+
+        let data = section.data()?;
+        let offset = usize::try_from(section.offset()).unwrap();
+
+        if data.len() < 8 {
+            return Err(section.unexpected_eof());
+        }
+
+        let bucket_count = elf.read_u32(offset).unwrap();
+        let chain_count = elf.read_u32(offset + 4).unwrap();
+
+        if (2 + u64::from(bucket_count) + u64::from(chain_count)) * 4
+            > u64::try_from(data.len()).unwrap()
+        {
+            return Err(section.unexpected_eof());
+        }
+
+        let (symbols, strings) = linked_symbols(section)?;
+
+        Ok(Self {
+            elf,
+            offset,
+            bucket_count,
+            chain_count,
+            symbols,
+            strings,
+        })
+    }
+
+    /// The number of buckets in the hash table. `nbucket` in the specification.
+    pub fn bucket_count(&self) -> u32 {
+        self.bucket_count
+    }
+
+    /// The number of entries in the chain, which equals the number of symbols in the symbol
+    /// table. `nchain` in the specification.
+    pub fn chain_count(&self) -> u32 {
+        self.chain_count
+    }
+
+    /// Returns the symbol index in a bucket, or [`None`] if the index is out of bounds.
+    pub fn bucket(&self, index: u32) -> Option<u32> {
+        if index >= self.bucket_count {
+            return None;
+        }
+
+        self.elf
+            .read_u32(self.offset + 8 + usize::try_from(index).unwrap() * 4)
+    }
+
+    /// Returns the next symbol index in the chain of a symbol, or [`None`] if the index is out of
+    /// bounds.
+    pub fn chain(&self, index: u32) -> Option<u32> {
+        if index >= self.chain_count {
+            return None;
+        }
+
+        self.elf
+            .read_u32(self.offset + 8 + usize::try_from(self.bucket_count + index).unwrap() * 4)
+    }
+
+    /// Looks up a symbol by name, returning its index in the symbol table the hash table belongs
+    /// to, or [`None`] if it wasn't found.
+    pub fn lookup(&self, name: &str) -> Option<u32> {
+        if self.bucket_count == 0 {
+            return None;
+        }
+
+        let mut index = self.bucket(elf_hash(name.as_bytes()) % self.bucket_count)?;
+
+        // a well-formed chain visits each symbol at most once
+        for _ in 0..self.chain_count {
+            if index == 0 {
+                return None;
+            } else if symbol_name_is(&self.symbols, &self.strings, index, name) {
+                return Some(index);
+            }
+
+            index = self.chain(index)?;
+        }
+
+        None
+    }
+}
+
+/// A reader for a GNU hash table section, `SHT_GNU_HASH`, which is used to look up symbols in the
+/// dynamic symbol table.
+#[derive(Debug, Clone)]
+pub struct GnuHashTable<'reader, 'data> {
+    elf: &'reader ElfReader<'data>,
+    offset: usize,
+    size: usize,
+    bucket_count: u32,
+    symbol_offset: u32,
+    bloom_size: u32,
+    bloom_shift: u32,
+    symbols: Symbols<'reader, 'data>,
+    strings: Strings<'data>,
+}
+
+impl<'reader, 'data> GnuHashTable<'reader, 'data> {
+    pub(super) fn new(section: &Section<'reader, 'data>) -> Result<Self, ParseError> {
+        let elf = section.elf;
+
+        if section.kind() != ElfValue::Known(SectionKind::GnuHash) {
+            return Err(ParseError::InvalidValue("sh_type"));
+        }
+
+        let data = section.data()?;
+        let offset = usize::try_from(section.offset()).unwrap();
+
+        if data.len() < 16 {
+            return Err(section.unexpected_eof());
+        }
+
+        let bucket_count = elf.read_u32(offset).unwrap();
+        let symbol_offset = elf.read_u32(offset + 4).unwrap();
+        let bloom_size = elf.read_u32(offset + 8).unwrap();
+        let bloom_shift = elf.read_u32(offset + 12).unwrap();
+
+        let word_size = if elf.is_64bit() { 8 } else { 4 };
+        if 16 + u64::from(bloom_size) * word_size + u64::from(bucket_count) * 4
+            > u64::try_from(data.len()).unwrap()
+        {
+            return Err(section.unexpected_eof());
+        }
+
+        let (symbols, strings) = linked_symbols(section)?;
+
+        Ok(Self {
+            elf,
+            offset,
+            size: data.len(),
+            bucket_count,
+            symbol_offset,
+            bloom_size,
+            bloom_shift,
+            symbols,
+            strings,
+        })
+    }
+
+    /// The number of buckets in the hash table.
+    pub fn bucket_count(&self) -> u32 {
+        self.bucket_count
+    }
+
+    /// The index of the first symbol in the symbol table that can be looked up using the hash
+    /// table.
+    pub fn symbol_offset(&self) -> u32 {
+        self.symbol_offset
+    }
+
+    /// The number of words in the bloom filter.
+    pub fn bloom_size(&self) -> u32 {
+        self.bloom_size
+    }
+
+    /// The shift count used for the second hash of the bloom filter.
+    pub fn bloom_shift(&self) -> u32 {
+        self.bloom_shift
+    }
+
+    /// Returns a word of the bloom filter, or [`None`] if the index is out of bounds. 32-bit ELF
+    /// files have 32-bit words.
+    pub fn bloom(&self, index: u32) -> Option<u64> {
+        if index >= self.bloom_size {
+            return None;
+        }
+
+        let index = usize::try_from(index).unwrap();
+
+        if self.elf.is_64bit() {
+            self.elf.read_u64(self.offset + 16 + index * 8)
+        } else {
+            self.elf
+                .read_u32(self.offset + 16 + index * 4)
+                .map(u64::from)
+        }
+    }
+
+    /// Returns the first symbol index in a bucket, or [`None`] if the index is out of bounds.
+    pub fn bucket(&self, index: u32) -> Option<u32> {
+        if index >= self.bucket_count {
+            return None;
+        }
+
+        self.elf
+            .read_u32(self.buckets_offset()? + usize::try_from(index).unwrap() * 4)
+    }
+
+    /// Returns the hash value of a symbol, whose lowest bit is set if the symbol is the last one
+    /// in its chain, or [`None`] if the symbol is not in the hash table.
+    pub fn chain(&self, symbol_index: u32) -> Option<u32> {
+        let index = usize::try_from(symbol_index.checked_sub(self.symbol_offset)?).ok()?;
+        let offset = usize::try_from(self.bucket_count)
+            .ok()?
+            .checked_mul(4)?
+            .checked_add(self.buckets_offset()?)?
+            .checked_add(index.checked_mul(4)?)?;
+
+        if offset.checked_add(4)? > self.offset + self.size {
+            return None;
+        }
+
+        self.elf.read_u32(offset)
+    }
+
+    /// Looks up a symbol by name, returning its index in the symbol table the hash table belongs
+    /// to, or [`None`] if it wasn't found.
+    pub fn lookup(&self, name: &str) -> Option<u32> {
+        if self.bucket_count == 0 || self.bloom_size == 0 {
+            return None;
+        }
+
+        let hash = gnu_hash(name.as_bytes());
+
+        let bits = if self.elf.is_64bit() { 64 } else { 32 };
+        let word = self.bloom((hash / bits) % self.bloom_size)?;
+        let mask = (1u64 << (hash % bits)) | (1u64 << (hash.checked_shr(self.bloom_shift)? % bits));
+
+        if word & mask != mask {
+            return None;
+        }
+
+        let mut index = self.bucket(hash % self.bucket_count)?;
+        if index < self.symbol_offset {
+            return None;
+        }
+
+        loop {
+            let chain_hash = self.chain(index)?;
+
+            if hash | 1 == chain_hash | 1
+                && symbol_name_is(&self.symbols, &self.strings, index, name)
+            {
+                return Some(index);
+            } else if chain_hash & 1 != 0 {
+                return None;
+            }
+
+            index = index.checked_add(1)?;
+        }
+    }
+
+    fn buckets_offset(&self) -> Option<usize> {
+        let word_size = if self.elf.is_64bit() { 8 } else { 4 };
+
+        usize::try_from(self.bloom_size)
+            .ok()?
+            .checked_mul(word_size)?
+            .checked_add(self.offset + 16)
+    }
+}
+
+/// Returns the symbol table a hash table section links to, and its string table.
+fn linked_symbols<'reader, 'data>(
+    section: &Section<'reader, 'data>,
+) -> Result<(Symbols<'reader, 'data>, Strings<'data>), ParseError> {
+    let symbols = section
+        .elf
+        .sections()?
+        .get(usize::try_from(section.link()).unwrap())
+        .ok_or(ParseError::InvalidValue("sh_link"))?
+        .symbols()?;
+    let strings = symbols.strings()?;
+
+    Ok((symbols, strings))
+}
+
+fn symbol_name_is(symbols: &Symbols, strings: &Strings, index: u32, name: &str) -> bool {
+    symbols
+        .get(usize::try_from(index).unwrap())
+        .and_then(|symbol| strings.get_cstr(symbol.name().into()))
+        .is_some_and(|symbol_name| symbol_name.to_bytes() == name.as_bytes())
+}
+
+/// The hash function of SysV hash tables.
+fn elf_hash(name: &[u8]) -> u32 {
+    name.iter().fold(0u32, |hash, &byte| {
+        let hash = (hash << 4).wrapping_add(byte.into());
+        let high = hash & 0xf000_0000;
+
+        (hash ^ (high >> 24)) & !high
+    })
+}
+
+/// The hash function of GNU hash tables.
+fn gnu_hash(name: &[u8]) -> u32 {
+    name.iter().fold(5381u32, |hash, &byte| {
+        hash.wrapping_mul(33).wrapping_add(byte.into())
+    })
+}
@@ -0,0 +1,301 @@
+//! A minimal decoder for zlib streams (RFC 1950) containing DEFLATE data (RFC 1951), used for
+//! compressed sections. The tables are shared with the encoder used by the builder.
+
+use alloc::{vec, vec::Vec};
+
+/// The base lengths of the length codes 257..=285.
+pub(crate) const LENGTH_BASE: [u16; 29] = [
+    3, 4, 5, 6, 7, 8, 9, 10, 11, 13, 15, 17, 19, 23, 27, 31, 35, 43, 51, 59, 67, 83, 99, 115, 131,
+    163, 195, 227, 258,
+];
+
+/// The number of extra bits of the length codes 257..=285.
+pub(crate) const LENGTH_EXTRA: [u8; 29] = [
+    0, 0, 0, 0, 0, 0, 0, 0, 1, 1, 1, 1, 2, 2, 2, 2, 3, 3, 3, 3, 4, 4, 4, 4, 5, 5, 5, 5, 0,
+];
+
+/// The base distances of the distance codes 0..=29.
+pub(crate) const DISTANCE_BASE: [u16; 30] = [
+    1, 2, 3, 4, 5, 7, 9, 13, 17, 25, 33, 49, 65, 97, 129, 193, 257, 385, 513, 769, 1025, 1537,
+    2049, 3073, 4097, 6145, 8193, 12289, 16385, 24577,
+];
+
+/// The number of extra bits of the distance codes 0..=29.
+pub(crate) const DISTANCE_EXTRA: [u8; 30] = [
+    0, 0, 0, 0, 1, 1, 2, 2, 3, 3, 4, 4, 5, 5, 6, 6, 7, 7, 8, 8, 9, 9, 10, 10, 11, 11, 12, 12, 13,
+    13,
+];
+
+/// The order in which the code lengths of the code length alphabet are stored.
+const CODE_LENGTH_ORDER: [usize; 19] = [
+    16, 17, 18, 0, 8, 7, 9, 6, 10, 5, 11, 4, 12, 3, 13, 2, 14, 1, 15,
+];
+
+const MAX_CODE_LENGTH: usize = 15;
+
+/// Decompresses a zlib stream. `size` is the expected size of the decompressed data. Returns
+/// [`None`] if the stream is invalid, uses a preset dictionary, or doesn't decompress to exactly
+/// `size` bytes.
+pub(super) fn zlib_decompress(data: &[u8], size: usize) -> Option<Vec<u8>> {
+    let (&cmf, &flg) = (data.first()?, data.get(1)?);
+
+    // compression method 8 is deflate, and FDICT (0x20) is not supported
+    if cmf & 0x0f != 8 || (u16::from(cmf) << 8 | u16::from(flg)) % 31 != 0 || flg & 0x20 != 0 {
+        return None;
+    }
+
+    let mut reader = BitReader::new(&data[2..]);
+    // `size` comes from the file, so don't trust it beyond the maximum deflate ratio of 1032:1
+    let mut output = Vec::with_capacity(size.min(data.len().saturating_mul(1032)));
+
+    loop {
+        let last = reader.bits(1)? == 1;
+
+        match reader.bits(2)? {
+            0 => stored_block(&mut reader, &mut output)?,
+            1 => {
+                let (literals, distances) = fixed_codes();
+                compressed_block(&mut reader, &mut output, &literals, &distances, size)?;
+            }
+            2 => {
+                let (literals, distances) = dynamic_codes(&mut reader)?;
+                compressed_block(&mut reader, &mut output, &literals, &distances, size)?;
+            }
+            _ => return None,
+        }
+
+        if output.len() > size {
+            return None;
+        } else if last {
+            break;
+        }
+    }
+
+    let checksum = reader.aligned_bytes(4)?;
+    if output.len() != size || u32::from_be_bytes(checksum.try_into().unwrap()) != adler32(&output)
+    {
+        return None;
+    }
+
+    Some(output)
+}
+
+fn stored_block(reader: &mut BitReader, output: &mut Vec<u8>) -> Option<()> {
+    let header = reader.aligned_bytes(4)?;
+    let length = u16::from_le_bytes([header[0], header[1]]);
+    let complement = u16::from_le_bytes([header[2], header[3]]);
+
+    if length != !complement {
+        return None;
+    }
+
+    output.extend_from_slice(reader.aligned_bytes(length.into())?);
+
+    Some(())
+}
+
+fn compressed_block(
+    reader: &mut BitReader,
+    output: &mut Vec<u8>,
+    literals: &Huffman,
+    distances: &Huffman,
+    size: usize,
+) -> Option<()> {
+    loop {
+        let symbol = usize::from(literals.decode(reader)?);
+
+        match symbol {
+            0..=255 => output.push(symbol.try_into().unwrap()),
+            256 => return Some(()),
+            _ => {
+                let index = symbol - 257;
+                let length = usize::from(*LENGTH_BASE.get(index)?)
+                    + usize::try_from(reader.bits(LENGTH_EXTRA[index])?).unwrap();
+
+                let index = usize::from(distances.decode(reader)?);
+                let distance = usize::from(*DISTANCE_BASE.get(index)?)
+                    + usize::try_from(reader.bits(DISTANCE_EXTRA[index])?).unwrap();
+
+                if distance > output.len() || output.len() + length > size {
+                    return None;
+                }
+
+                let start = output.len() - distance;
+                for i in 0..length {
+                    output.push(output[start + i]);
+                }
+            }
+        }
+    }
+}
+
+fn fixed_codes() -> (Huffman, Huffman) {
+    let mut lengths = [0; 288];
+    lengths[..144].fill(8);
+    lengths[144..256].fill(9);
+    lengths[256..280].fill(7);
+    lengths[280..].fill(8);
+
+    (
+        Huffman::new(&lengths).unwrap(),
+        Huffman::new(&[5; 30]).unwrap(),
+    )
+}
+
+fn dynamic_codes(reader: &mut BitReader) -> Option<(Huffman, Huffman)> {
+    let literal_count = usize::try_from(reader.bits(5)?).unwrap() + 257;
+    let distance_count = usize::try_from(reader.bits(5)?).unwrap() + 1;
+    let code_length_count = usize::try_from(reader.bits(4)?).unwrap() + 4;
+
+    let mut code_lengths = [0; 19];
+    for &index in &CODE_LENGTH_ORDER[..code_length_count] {
+        code_lengths[index] = reader.bits(3)?.try_into().unwrap();
+    }
+    let code_lengths = Huffman::new(&code_lengths)?;
+
+    let mut lengths = Vec::with_capacity(literal_count + distance_count);
+    while lengths.len() < literal_count + distance_count {
+        let (length, repeat) = match code_lengths.decode(reader)? {
+            symbol @ 0..=15 => (symbol.try_into().unwrap(), 1),
+            16 => (*lengths.last()?, 3 + reader.bits(2)?),
+            17 => (0, 3 + reader.bits(3)?),
+            18 => (0, 11 + reader.bits(7)?),
+            _ => return None,
+        };
+
+        for _ in 0..repeat {
+            lengths.push(length);
+        }
+    }
+
+    if lengths.len() != literal_count + distance_count || lengths[256] == 0 {
+        return None;
+    }
+
+    Some((
+        Huffman::new(&lengths[..literal_count])?,
+        Huffman::new(&lengths[literal_count..])?,
+    ))
+}
+
+pub(crate) fn adler32(data: &[u8]) -> u32 {
+    let (mut a, mut b) = (1u32, 0u32);
+
+    for &byte in data {
+        a = (a + u32::from(byte)) % 65521;
+        b = (b + a) % 65521;
+    }
+
+    b << 16 | a
+}
+
+/// A canonical Huffman code, stored as the number of codes of each length and the symbols sorted
+/// by their codes.
+struct Huffman {
+    counts: [u16; MAX_CODE_LENGTH + 1],
+    symbols: Vec<u16>,
+}
+
+impl Huffman {
+    /// Creates a Huffman code from the code lengths of the symbols. Returns [`None`] if the
+    /// lengths are over-subscribed.
+    fn new(lengths: &[u8]) -> Option<Self> {
+        let mut counts = [0; MAX_CODE_LENGTH + 1];
+        for &length in lengths {
+            *counts.get_mut(usize::from(length))? += 1;
+        }
+        counts[0] = 0;
+
+        let mut left = 1i32;
+        for &count in &counts[1..] {
+            left = (left << 1) - i32::from(count);
+
+            if left < 0 {
+                return None;
+            }
+        }
+
+        let mut offsets = [0; MAX_CODE_LENGTH + 1];
+        for length in 1..MAX_CODE_LENGTH {
+            offsets[length + 1] = offsets[length] + counts[length];
+        }
+
+        let mut symbols = vec![0; lengths.len()];
+        for (symbol, &length) in lengths.iter().enumerate() {
+            if length != 0 {
+                let offset = &mut offsets[usize::from(length)];
+                symbols[usize::from(*offset)] = symbol.try_into().unwrap();
+                *offset += 1;
+            }
+        }
+
+        Some(Self { counts, symbols })
+    }
+
+    fn decode(&self, reader: &mut BitReader) -> Option<u16> {
+        let (mut code, mut first, mut index) = (0i32, 0i32, 0i32);
+
+        for &count in &self.counts[1..] {
+            code |= i32::try_from(reader.bits(1)?).unwrap();
+            let count = i32::from(count);
+
+            if code - first < count {
+                return self
+                    .symbols
+                    .get(usize::try_from(index + code - first).unwrap())
+                    .copied();
+            }
+
+            index += count;
+            first = (first + count) << 1;
+            code <<= 1;
+        }
+
+        None
+    }
+}
+
+/// Reads bits starting from the least significant bit of each byte, as required by DEFLATE.
+struct BitReader<'data> {
+    data: &'data [u8],
+    position: usize,
+    buffer: u32,
+    count: u8,
+}
+
+impl<'data> BitReader<'data> {
+    fn new(data: &'data [u8]) -> Self {
+        Self {
+            data,
+            position: 0,
+            buffer: 0,
+            count: 0,
+        }
+    }
+
+    fn bits(&mut self, count: u8) -> Option<u32> {
+        while self.count < count {
+            self.buffer |= u32::from(*self.data.get(self.position)?) << self.count;
+            self.position += 1;
+            self.count += 8;
+        }
+
+        let value = self.buffer & ((1 << count) - 1);
+        self.buffer >>= count;
+        self.count -= count;
+
+        Some(value)
+    }
+
+    /// Discards the bits remaining in the current byte and reads whole bytes.
+    fn aligned_bytes(&mut self, length: usize) -> Option<&'data [u8]> {
+        // the buffer only ever holds the unread bits of the last byte, so they can be dropped
+        self.buffer = 0;
+        self.count = 0;
+
+        let bytes = self.data.get(self.position..self.position + length)?;
+        self.position += length;
+
+        Some(bytes)
+    }
+}
@@ -0,0 +1,157 @@
+use num_traits::FromPrimitive;
+
+use crate::{
+    consts::{NT_AUXV, NT_PRSTATUS},
+    AuxType, Endianness, MachineKind,
+};
+
+use super::{ElfReader, ElfValue, PrStatus};
+
+/// A reader for the notes in an `SHT_NOTE` section or a `PT_NOTE` segment.
+#[derive(Debug, Clone)]
+pub struct Notes<'reader, 'data> {
+    elf: &'reader ElfReader<'data>,
+    data: &'data [u8],
+    align: usize,
+}
+
+impl<'reader, 'data> Notes<'reader, 'data> {
+    /// `align` is the alignment of the section or segment containing the notes. Notes are aligned
+    /// to 8 bytes if it is 8, and to 4 bytes otherwise.
+    pub(super) fn new(elf: &'reader ElfReader<'data>, data: &'data [u8], align: u64) -> Self {
+        Self {
+            elf,
+            data,
+            align: if align == 8 { 8 } else { 4 },
+        }
+    }
+
+    /// Parses the first `CORE` note of type `NT_PRSTATUS`, which contains the status and registers
+    /// of a thread in a core file, using the register layout of the given machine. Returns
+    /// [`None`] if there is no such note, the note is too short, or the register layout of the
+    /// machine is not supported. Only [`MachineKind::X86_64`] and [`MachineKind::RiscV`] are
+    /// currently supported.
+    pub fn prstatus(&self, machine: MachineKind) -> Option<PrStatus> {
+        let note = self
+            .clone()
+            .into_iter()
+            .find(|note| note.name() == b"CORE" && note.kind() == NT_PRSTATUS)?;
+
+        PrStatus::parse(
+            note.desc(),
+            self.elf.endianness(),
+            self.elf.is_64bit(),
+            machine,
+        )
+    }
+
+    /// Returns an iterator over the entries of the auxiliary vector in the first `CORE` note of
+    /// type `NT_AUXV` as `(a_type, a_val)` pairs. The entries are word-sized, depending on the ELF
+    /// class. The iterator is empty if there is no such note, and it stops at the terminating
+    /// [`AuxType::Null`] entry or at the end of the note.
+    pub fn auxv(&self) -> impl Iterator<Item = (ElfValue<AuxType, u64>, u64)> + 'data {
+        let desc = self
+            .clone()
+            .into_iter()
+            .find(|note| note.name() == b"CORE" && note.kind() == NT_AUXV)
+            .map_or(&[][..], |note| note.desc());
+        let endianness = self.elf.endianness();
+        let word_size = if self.elf.is_64bit() { 8 } else { 4 };
+
+        desc.chunks_exact(2 * word_size)
+            .map(move |entry| {
+                let (kind, value) = entry.split_at(word_size);
+                let read_word = |bytes: &[u8]| match bytes.try_into() {
+                    Ok(bytes) => endianness.u64_from_bytes(bytes),
+                    Err(_) => endianness.u32_from_bytes(bytes.try_into().unwrap()).into(),
+                };
+
+                (read_word(kind), read_word(value))
+            })
+            .take_while(|&(kind, _)| kind != 0)
+            .map(|(kind, value)| {
+                (
+                    AuxType::from_u64(kind).map_or(ElfValue::Unknown(kind), ElfValue::Known),
+                    value,
+                )
+            })
+    }
+}
+
+impl<'data> IntoIterator for Notes<'_, 'data> {
+    type Item = Note<'data>;
+    type IntoIter = NotesIter<'data>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        NotesIter {
+            endianness: self.elf.endianness(),
+            data: self.data,
+            align: self.align,
+        }
+    }
+}
+
+/// An iterator over the notes in a section or segment. Iteration stops at the first note that
+/// does not fit in the remaining data.
+#[derive(Debug, Clone)]
+pub struct NotesIter<'data> {
+    endianness: Endianness,
+    data: &'data [u8],
+    align: usize,
+}
+
+impl<'data> Iterator for NotesIter<'data> {
+    type Item = Note<'data>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let read_u32 = |index: usize| {
+            self.data
+                .get(index..index + 4)
+                .map(|bytes| self.endianness.u32_from_bytes(bytes.try_into().unwrap()))
+        };
+
+        let namesz = usize::try_from(read_u32(0)?).ok()?;
+        let descsz = usize::try_from(read_u32(4)?).ok()?;
+        let kind = read_u32(8)?;
+
+        let name_start: usize = 12;
+        let desc_start = name_start.checked_add(namesz)?.next_multiple_of(self.align);
+        let desc_end = desc_start.checked_add(descsz)?;
+        let next = desc_end.next_multiple_of(self.align);
+
+        let name = self.data.get(name_start..name_start + namesz)?;
+        let desc = self.data.get(desc_start..desc_end)?;
+        self.data = self.data.get(next..).unwrap_or(&[]);
+
+        Some(Note {
+            name: name.strip_suffix(&[0]).unwrap_or(name),
+            kind,
+            desc,
+        })
+    }
+}
+
+/// A note, consisting of an owner name, a type and a descriptor.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Note<'data> {
+    name: &'data [u8],
+    kind: u32,
+    desc: &'data [u8],
+}
+
+impl<'data> Note<'data> {
+    /// The name of the note's owner without the null terminator, such as `GNU`.
+    pub fn name(&self) -> &'data [u8] {
+        self.name
+    }
+
+    /// The type of the note. The meaning depends on the owner's name.
+    pub fn kind(&self) -> u32 {
+        self.kind
+    }
+
+    /// The descriptor, the contents of the note.
+    pub fn desc(&self) -> &'data [u8] {
+        self.desc
+    }
+}
@@ -0,0 +1,170 @@
+use crate::{
+    consts::{RelocationKind, ELF32_RELA_SIZE, ELF32_REL_SIZE, ELF64_RELA_SIZE, ELF64_REL_SIZE},
+    MachineKind, SectionKind,
+};
+
+use super::{ElfReader, ElfValue, ParseError, Section};
+
+/// A reader for a relocation table section, either `SHT_REL` or `SHT_RELA`.
+#[derive(Debug, Clone)]
+pub struct Relocations<'reader, 'data> {
+    elf: &'reader ElfReader<'data>,
+    entry_size: usize,
+    offset: usize,
+    count: usize,
+    is_rela: bool,
+}
+
+impl<'reader, 'data> Relocations<'reader, 'data> {
+    pub(super) fn new(section: &Section<'reader, 'data>) -> Result<Self, ParseError> {
+        let elf = section.elf;
+        let is_rela = match section.kind() {
+            ElfValue::Known(SectionKind::Rel) => false,
+            ElfValue::Known(SectionKind::Rela) => true,
+            _ => return Err(ParseError::InvalidValue("sh_type")),
+        };
+        let entry_size = match (elf.is_64bit(), is_rela) {
+            (true, true) => ELF64_RELA_SIZE,
+            (true, false) => ELF64_REL_SIZE,
+            (false, true) => ELF32_RELA_SIZE,
+            (false, false) => ELF32_REL_SIZE,
+        };
+
+        if section.entsize() != u64::from(entry_size) {
+            return Err(ParseError::InvalidValue("sh_entsize"));
+        }
+
+        let data = section.data()?;
+
+        Ok(Self {
+            elf,
+            entry_size: entry_size.into(),
+            offset: usize::try_from(section.offset()).unwrap(),
+            count: data.len() / usize::from(entry_size),
+            is_rela,
+        })
+    }
+
+    /// Returns whether the relocation entries contain explicit addends (`SHT_RELA`).
+    pub fn is_rela(&self) -> bool {
+        self.is_rela
+    }
+
+    /// Returns a [`Relocation`] at the specified index in the table, or [`None`] if the index is
+    /// out of bounds.
+    pub fn get(&self, index: usize) -> Option<Relocation<'reader, 'data>> {
+        if index >= self.count {
+            return None;
+        }
+
+        Some(Relocation {
+            elf: self.elf,
+            offset: self.offset + self.entry_size * index,
+            is_rela: self.is_rela,
+        })
+    }
+}
+
+impl<'reader, 'data> IntoIterator for Relocations<'reader, 'data> {
+    type Item = Relocation<'reader, 'data>;
+    type IntoIter = RelocationsIter<'reader, 'data>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        RelocationsIter {
+            relocations: self,
+            index: 0,
+        }
+    }
+}
+
+/// An iterator over the entries in a relocation table.
+#[derive(Debug, Clone)]
+pub struct RelocationsIter<'reader, 'data> {
+    relocations: Relocations<'reader, 'data>,
+    index: usize,
+}
+
+impl<'reader, 'data> Iterator for RelocationsIter<'reader, 'data> {
+    type Item = Relocation<'reader, 'data>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let relocation = self.relocations.get(self.index);
+        self.index += 1;
+
+        relocation
+    }
+}
+
+/// A relocation entry, `Elf_Rel` or `Elf_Rela`.
+#[derive(Debug, Clone)]
+pub struct Relocation<'reader, 'data> {
+    elf: &'reader ElfReader<'data>,
+    offset: usize,
+    is_rela: bool,
+}
+
+impl Relocation<'_, '_> {
+    fn read_word(&self, index: usize) -> u64 {
+        if self.elf.is_64bit() {
+            self.elf.read_u64(self.offset + index * 8).unwrap()
+        } else {
+            self.elf.read_u32(self.offset + index * 4).unwrap().into()
+        }
+    }
+
+    /// The location the relocation should be applied at. A section offset for relocatable files,
+    /// and a virtual address for executable and shared object files. `r_offset` in the
+    /// specification.
+    pub fn offset(&self) -> u64 {
+        self.read_word(0)
+    }
+
+    /// The symbol table index and relocation type. `r_info` in the specification.
+    ///
+    /// 32 bits for 32-bit ELF files.
+    pub fn info(&self) -> u64 {
+        self.read_word(1)
+    }
+
+    /// The constant addend used to compute the relocated value, or [`None`] for `Elf_Rel`
+    /// entries. `r_addend` in the specification.
+    pub fn addend(&self) -> Option<i64> {
+        if !self.is_rela {
+            return None;
+        }
+
+        let value = self.read_word(2);
+
+        if self.elf.is_64bit() {
+            Some(value as i64)
+        } else {
+            Some((value as u32 as i32).into())
+        }
+    }
+
+    /// The index of the symbol the relocation refers to in the associated symbol table.
+    pub fn symbol(&self) -> u32 {
+        if self.elf.is_64bit() {
+            (self.info() >> 32) as u32
+        } else {
+            (self.info() >> 8) as u32
+        }
+    }
+
+    /// The processor-specific relocation type number.
+    pub fn kind(&self) -> u32 {
+        if self.elf.is_64bit() {
+            self.info() as u32
+        } else {
+            (self.info() & 0xff) as u32
+        }
+    }
+
+    /// Interprets the relocation type using the relocation types of the given machine.
+    pub fn reloc_type_for(&self, machine: MachineKind) -> ElfValue<RelocationKind, u32> {
+        let value = self.kind();
+
+        RelocationKind::from_machine(machine, value)
+            .map_or(ElfValue::Unknown(value), ElfValue::Known)
+    }
+}
@@ -0,0 +1,124 @@
+use crate::{Endianness, SectionKind};
+
+use super::{ElfValue, ParseError, Section};
+
+/// A reader for a table of relative relocations in the packed RELR format, `SHT_RELR`. The table
+/// is an array of address-sized words. An even word is the address of a relocated word. An odd
+/// word is a bitmap of the words following the previous address or bitmap: bit 0 marks the
+/// bitmap, and bit `n` means that the `n`th of the next `N - 1` words is relocated, where `N` is
+/// the number of bits in a word.
+///
+/// Iterating over the table yields the address of every relocated word. The load base is added to
+/// the value at each of these addresses at runtime.
+#[derive(Debug, Clone)]
+pub struct Relr<'data> {
+    data: &'data [u8],
+    endianness: Endianness,
+    is_64bit: bool,
+}
+
+impl<'data> Relr<'data> {
+    pub(super) fn new(section: &Section<'_, 'data>) -> Result<Self, ParseError> {
+        if section.kind() != ElfValue::Known(SectionKind::Relr) {
+            return Err(ParseError::InvalidValue("sh_type"));
+        }
+
+        let is_64bit = section.elf.is_64bit();
+        let word_size = if is_64bit { 8 } else { 4 };
+
+        if section.entsize() != word_size {
+            return Err(ParseError::InvalidValue("sh_entsize"));
+        }
+
+        let data = section.data()?;
+
+        if data.len() % usize::try_from(word_size).unwrap() != 0 {
+            return Err(ParseError::InvalidValue("sh_size"));
+        }
+
+        Ok(Self {
+            data,
+            endianness: section.elf.endianness(),
+            is_64bit,
+        })
+    }
+
+    /// Returns the number of words in the table, which is less than or equal to the number of
+    /// relocations.
+    pub fn len(&self) -> usize {
+        self.data.len() / if self.is_64bit { 8 } else { 4 }
+    }
+
+    /// Returns whether the table is empty.
+    pub fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+}
+
+impl<'data> IntoIterator for Relr<'data> {
+    type Item = u64;
+    type IntoIter = RelrIter<'data>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        RelrIter {
+            relr: self,
+            base: 0,
+            bitmap: 0,
+            next_base: 0,
+        }
+    }
+}
+
+/// An iterator over the addresses of the words relocated by a RELR table.
+#[derive(Debug, Clone)]
+pub struct RelrIter<'data> {
+    relr: Relr<'data>,
+    /// The address of the word bit 1 of the current bitmap refers to
+    base: u64,
+    /// The bits of the current bitmap that haven't been yielded, shifted right by one
+    bitmap: u64,
+    /// The address of the word bit 1 of the next bitmap refers to
+    next_base: u64,
+}
+
+impl RelrIter<'_> {
+    fn next_word(&mut self) -> Option<u64> {
+        let word = if self.relr.is_64bit {
+            let (word, rest) = self.relr.data.split_first_chunk::<8>()?;
+            self.relr.data = rest;
+            self.relr.endianness.u64_from_bytes(*word)
+        } else {
+            let (word, rest) = self.relr.data.split_first_chunk::<4>()?;
+            self.relr.data = rest;
+            self.relr.endianness.u32_from_bytes(*word).into()
+        };
+
+        Some(word)
+    }
+}
+
+impl Iterator for RelrIter<'_> {
+    type Item = u64;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (word_size, word_bits) = if self.relr.is_64bit { (8, 64) } else { (4, 32) };
+
+        while self.bitmap == 0 {
+            let word = self.next_word()?;
+
+            if word & 1 == 0 {
+                self.next_base = word.wrapping_add(word_size);
+                return Some(word);
+            }
+
+            self.base = self.next_base;
+            self.bitmap = word >> 1;
+            self.next_base = self.base.wrapping_add((word_bits - 1) * word_size);
+        }
+
+        let index = u64::from(self.bitmap.trailing_zeros());
+        self.bitmap &= self.bitmap - 1;
+
+        Some(self.base.wrapping_add(index * word_size))
+    }
+}
@@ -0,0 +1,258 @@
+//! Owned copies of the ELF header, section headers, and program headers. The readers borrow the
+//! ELF file and read each field on demand, so these are used to keep or serialize the values.
+//!
+//! The snapshots have the same layout for 32-bit and 64-bit files: addresses, offsets, and sizes
+//! are widened to `u64`, so code using them doesn't need to check the class of the file.
+
+use flagset::FlagSet;
+
+use crate::{
+    ElfKind, Endianness, MachineKind, OsAbi, SectionFlag, SectionKind, SegmentFlag, SegmentKind,
+};
+
+use super::{ElfValue, Header, Section, Segment};
+
+/// An owned copy of the fields of the ELF header, returned by [`Header::snapshot`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct HeaderSnapshot {
+    /// Whether the ELF file is 64-bit. `ei_class` in the specification.
+    pub is_64bit: bool,
+    /// The byte order of the ELF file. `ei_data` in the specification.
+    pub endianness: Endianness,
+    /// `ei_version` in the specification.
+    pub ei_version: u8,
+    /// `ei_osabi` in the specification.
+    pub osabi: ElfValue<OsAbi, u8>,
+    /// `ei_abiversion` in the specification.
+    pub abiversion: u8,
+    /// `e_type` in the specification.
+    pub kind: ElfValue<ElfKind, u16>,
+    /// `e_machine` in the specification.
+    pub machine: ElfValue<MachineKind, u16>,
+    /// `e_version` in the specification.
+    pub version: u32,
+    /// `e_entry` in the specification.
+    pub entry: u64,
+    /// `e_phoff` in the specification.
+    pub phoff: u64,
+    /// `e_shoff` in the specification.
+    pub shoff: u64,
+    /// `e_flags` in the specification.
+    pub flags: u32,
+    /// `e_ehsize` in the specification.
+    pub ehsize: u16,
+    /// `e_phentsize` in the specification.
+    pub phentsize: u16,
+    /// `e_phnum` in the specification.
+    pub phnum: u16,
+    /// `e_shentsize` in the specification.
+    pub shentsize: u16,
+    /// `e_shnum` in the specification.
+    pub shnum: u16,
+    /// `e_shstrndx` in the specification.
+    pub shstrndx: u16,
+}
+
+/// An owned copy of the fields of a section header, returned by [`Section::snapshot`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct SectionSnapshot {
+    /// `sh_name` in the specification.
+    pub name: u32,
+    /// `sh_type` in the specification.
+    pub kind: ElfValue<SectionKind, u32>,
+    /// `sh_flags` in the specification.
+    pub flags: ElfValue<FlagSet<SectionFlag>, u64>,
+    /// `sh_addr` in the specification.
+    pub addr: u64,
+    /// `sh_offset` in the specification.
+    pub offset: u64,
+    /// `sh_size` in the specification.
+    pub size: u64,
+    /// `sh_link` in the specification.
+    pub link: u32,
+    /// `sh_info` in the specification.
+    pub info: u32,
+    /// `sh_addralign` in the specification.
+    pub addralign: u64,
+    /// `sh_entsize` in the specification.
+    pub entsize: u64,
+}
+
+/// An owned copy of the fields of a program header, returned by [`Segment::snapshot`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct SegmentSnapshot {
+    /// `p_type` in the specification.
+    pub kind: ElfValue<SegmentKind, u32>,
+    /// `p_flags` in the specification.
+    pub flags: ElfValue<FlagSet<SegmentFlag>, u32>,
+    /// `p_offset` in the specification.
+    pub offset: u64,
+    /// `p_vaddr` in the specification.
+    pub vaddr: u64,
+    /// `p_paddr` in the specification.
+    pub paddr: u64,
+    /// `p_filesz` in the specification.
+    pub filesz: u64,
+    /// `p_memsz` in the specification.
+    pub memsz: u64,
+    /// `p_align` in the specification.
+    pub align: u64,
+}
+
+impl Header<'_, '_> {
+    /// Returns an owned copy of the header's fields. The copy doesn't borrow the ELF file, and
+    /// isn't affected by later changes to it.
+    pub fn snapshot(&self) -> HeaderSnapshot {
+        HeaderSnapshot {
+            is_64bit: self.elf.is_64bit(),
+            endianness: self.elf.endianness(),
+            ei_version: self.ei_version(),
+            osabi: self.osabi(),
+            abiversion: self.abiversion(),
+            kind: self.kind(),
+            machine: self.machine(),
+            version: self.version(),
+            entry: self.entry(),
+            phoff: self.phoff(),
+            shoff: self.shoff(),
+            flags: self.flags(),
+            ehsize: self.ehsize(),
+            phentsize: self.phentsize(),
+            phnum: self.phnum(),
+            shentsize: self.shentsize(),
+            shnum: self.shnum(),
+            shstrndx: self.shstrndx(),
+        }
+    }
+}
+
+impl Section<'_, '_> {
+    /// Returns an owned copy of the section header's fields.
+    pub fn snapshot(&self) -> SectionSnapshot {
+        SectionSnapshot {
+            name: self.name(),
+            kind: self.kind(),
+            flags: self.flags(),
+            addr: self.addr(),
+            offset: self.offset(),
+            size: self.size(),
+            link: self.link(),
+            info: self.info(),
+            addralign: self.addralign(),
+            entsize: self.entsize(),
+        }
+    }
+}
+
+impl Segment<'_, '_> {
+    /// Returns an owned copy of the program header's fields.
+    pub fn snapshot(&self) -> SegmentSnapshot {
+        SegmentSnapshot {
+            kind: self.kind(),
+            flags: self.flags(),
+            offset: self.offset(),
+            vaddr: self.vaddr(),
+            paddr: self.paddr(),
+            filesz: self.filesz(),
+            memsz: self.memsz(),
+            align: self.align(),
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+mod serialize {
+    use alloc::vec::Vec;
+
+    use flagset::{FlagSet, Flags};
+    use num_traits::ToPrimitive;
+    use serde::{ser::SerializeStruct, Serialize, Serializer};
+
+    use crate::{
+        DynTag, ElfKind, MachineKind, OsAbi, RelocationKind, SectionKind, SegmentKind,
+        SymbolBinding, SymbolKind,
+    };
+
+    use super::super::{ElfValue, Header, Section, Segment};
+
+    /// Serializes a value as a struct containing its numeric `value` and its `name`, which is
+    /// `null` if the value is unknown.
+    fn serialize_value<S: Serializer, N: Serialize, V: Serialize>(
+        serializer: S,
+        value: V,
+        name: Option<N>,
+    ) -> Result<S::Ok, S::Error> {
+        let mut state = serializer.serialize_struct("ElfValue", 2)?;
+        state.serialize_field("value", &value)?;
+        state.serialize_field("name", &name)?;
+        state.end()
+    }
+
+    macro_rules! serialize_enum_value {
+        ($($kind:ty: $unknown:ty),+ $(,)?) => {
+            $(
+                impl Serialize for ElfValue<$kind, $unknown> {
+                    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                        match self {
+                            ElfValue::Known(kind) => serialize_value(
+                                serializer,
+                                <$unknown>::try_from(kind.to_u64().unwrap()).unwrap(),
+                                Some(kind),
+                            ),
+                            ElfValue::Unknown(value) => {
+                                serialize_value(serializer, value, None::<$kind>)
+                            }
+                        }
+                    }
+                }
+            )+
+        };
+    }
+
+    serialize_enum_value! {
+        OsAbi: u8,
+        ElfKind: u16,
+        MachineKind: u16,
+        SectionKind: u32,
+        SegmentKind: u32,
+        SymbolBinding: u8,
+        SymbolKind: u8,
+        DynTag: u64,
+        RelocationKind: u32,
+    }
+
+    /// Known flags are serialized with the list of their names.
+    impl<F: Flags + Serialize, U: Serialize + From<F::Type>> Serialize for ElfValue<FlagSet<F>, U> {
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            match self {
+                ElfValue::Known(flags) => serialize_value(
+                    serializer,
+                    U::from(flags.bits()),
+                    Some(flags.into_iter().collect::<Vec<_>>()),
+                ),
+                ElfValue::Unknown(value) => serialize_value(serializer, value, None::<Vec<F>>),
+            }
+        }
+    }
+
+    impl Serialize for Header<'_, '_> {
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            self.snapshot().serialize(serializer)
+        }
+    }
+
+    impl Serialize for Section<'_, '_> {
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            self.snapshot().serialize(serializer)
+        }
+    }
+
+    impl Serialize for Segment<'_, '_> {
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            self.snapshot().serialize(serializer)
+        }
+    }
+}
@@ -0,0 +1,311 @@
+use crate::{
+    consts::{
+        ELF32_SYMBOL_SIZE, ELF64_SYMBOL_SIZE, SHN_XINDEX, VERSYM_HIDDEN, VER_NDX_GLOBAL,
+        VER_NDX_LOCAL,
+    },
+    SectionKind, SymbolBinding, SymbolKind,
+};
+
+use super::{split_symbol_info, ElfReader, ElfValue, ParseError, Section, Strings};
+
+/// A reader for a symbol table section, either `SHT_SYMTAB` or `SHT_DYNSYM`.
+#[derive(Debug, Clone)]
+pub struct Symbols<'reader, 'data> {
+    elf: &'reader ElfReader<'data>,
+    entry_size: usize,
+    offset: usize,
+    count: usize,
+    link: u32,
+    /// The index of the symbol table section
+    section_index: usize,
+}
+
+impl<'reader, 'data> Symbols<'reader, 'data> {
+    pub(super) fn new(section: &Section<'reader, 'data>) -> Result<Self, ParseError> {
+        let elf = section.elf;
+
+        match section.kind() {
+            ElfValue::Known(SectionKind::SymbolTable | SectionKind::DynSym) => {}
+            _ => return Err(ParseError::InvalidValue("sh_type")),
+        }
+
+        let entry_size = match elf.is_64bit() {
+            true => ELF64_SYMBOL_SIZE,
+            false => ELF32_SYMBOL_SIZE,
+        };
+
+        if section.entsize() != u64::from(entry_size) {
+            return Err(ParseError::InvalidValue("sh_entsize"));
+        }
+
+        let data = section.data()?;
+
+        Ok(Self {
+            elf,
+            entry_size: entry_size.into(),
+            offset: usize::try_from(section.offset()).unwrap(),
+            count: data.len() / usize::from(entry_size),
+            link: section.link(),
+            section_index: section.index(),
+        })
+    }
+
+    /// Returns the [`Strings`] object of the string table containing the names of the symbols, as
+    /// specified by the symbol table's `sh_link`.
+    pub fn strings(&self) -> Result<Strings<'data>, ParseError> {
+        Strings::new(self.elf, usize::try_from(self.link).unwrap(), "sh_link")
+    }
+
+    /// Returns a [`Symbol`] at the specified index in the symbol table, or [`None`] if the index is
+    /// out of bounds.
+    pub fn get(&self, index: usize) -> Option<Symbol<'reader, 'data>> {
+        if index >= self.count {
+            return None;
+        }
+
+        Some(Symbol {
+            elf: self.elf,
+            offset: self.offset + self.entry_size * index,
+            index,
+            table: self.section_index,
+        })
+    }
+
+    /// Returns the version index of the symbol at the specified index from the
+    /// [`SectionKind::GnuVerSym`] section linked to the symbol table, or [`None`] if there is no
+    /// such section or the index is out of bounds. Index 0 means that the symbol is local, and 1
+    /// that it is global and has the base version of the file. Other indices refer to the version
+    /// definitions and requirements. If bit 15 (`0x8000`) is set, the version is hidden and the
+    /// symbol can't be linked against by default.
+    pub fn version_index(&self, index: usize) -> Option<u16> {
+        let versions = self
+            .elf
+            .sections()
+            .ok()?
+            .of_kind(SectionKind::GnuVerSym)
+            .find(|section| usize::try_from(section.link()).unwrap() == self.section_index)?;
+
+        versions
+            .data()
+            .ok()?
+            .get(index.checked_mul(2)?..index.checked_mul(2)? + 2)
+            .map(|bytes| {
+                self.elf
+                    .endianness()
+                    .u16_from_bytes(bytes.try_into().unwrap())
+            })
+    }
+
+    /// Returns the name of the version of the symbol at the specified index, such as
+    /// `GLIBC_2.2.5`, from the [`SectionKind::GnuVerDef`] or [`SectionKind::GnuVerNeed`] section
+    /// containing the version index of the symbol. Whether the version is hidden is ignored.
+    /// Returns [`None`] if the symbol is local or has the base version, see
+    /// [`Symbols::version_index`], or if the version could not be found or read.
+    pub fn version(&self, index: usize) -> Option<&'data str> {
+        let version = self.version_index(index)? & !VERSYM_HIDDEN;
+
+        if matches!(version, VER_NDX_LOCAL | VER_NDX_GLOBAL) {
+            return None;
+        }
+
+        for section in self.elf.sections().ok()? {
+            let (strings, name) = match section.kind() {
+                ElfValue::Known(SectionKind::GnuVerDef) => {
+                    let definitions = section.version_definitions().ok()?;
+                    let name = definitions
+                        .clone()
+                        .into_iter()
+                        .find(|definition| definition.index() == version)
+                        .map(|definition| definition.name());
+
+                    (definitions.strings(), name)
+                }
+                ElfValue::Known(SectionKind::GnuVerNeed) => {
+                    let requirements = section.version_requirements().ok()?;
+                    let name = requirements
+                        .clone()
+                        .into_iter()
+                        .find(|requirement| requirement.index() == version)
+                        .map(|requirement| requirement.name());
+
+                    (requirements.strings(), name)
+                }
+                _ => continue,
+            };
+
+            if let Some(name) = name {
+                return strings.ok()?.get_str(name.into())?.ok();
+            }
+        }
+
+        None
+    }
+
+    /// Returns the defined [`SymbolKind::Func`] or [`SymbolKind::Object`] symbol whose range
+    /// `[st_value, st_value + st_size)` contains the address, or [`None`] if there is no such
+    /// symbol. If several symbols contain the address, the one with the highest value is returned,
+    /// and the first one in the table if they have the same value.
+    pub fn symbol_at(&self, addr: u64) -> Option<Symbol<'reader, 'data>> {
+        (0..self.count)
+            .filter_map(|index| self.get(index))
+            .filter(|symbol| {
+                matches!(
+                    symbol.kind(),
+                    ElfValue::Known(SymbolKind::Func | SymbolKind::Object)
+                ) && symbol.shndx() != 0
+                    && addr >= symbol.value()
+                    && addr - symbol.value() < symbol.size()
+            })
+            .reduce(|best, symbol| {
+                if symbol.value() > best.value() {
+                    symbol
+                } else {
+                    best
+                }
+            })
+    }
+
+    /// Returns an iterator over the symbols defined in the section at the given index in the
+    /// section header table. The index is compared to [`Symbol::section_index`], so symbols whose
+    /// index is stored in the extended section index table (`SHN_XINDEX`) are included, and
+    /// symbols whose index can't be read are skipped.
+    pub fn in_section(&self, section_index: u32) -> impl Iterator<Item = Symbol<'reader, 'data>> {
+        self.clone()
+            .into_iter()
+            .filter(move |symbol| symbol.section_index() == Ok(section_index))
+    }
+}
+
+impl<'reader, 'data> IntoIterator for Symbols<'reader, 'data> {
+    type Item = Symbol<'reader, 'data>;
+    type IntoIter = SymbolsIter<'reader, 'data>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        SymbolsIter {
+            symbols: self,
+            index: 0,
+        }
+    }
+}
+
+/// An iterator over the symbols in a symbol table.
+#[derive(Debug, Clone)]
+pub struct SymbolsIter<'reader, 'data> {
+    symbols: Symbols<'reader, 'data>,
+    index: usize,
+}
+
+impl<'reader, 'data> Iterator for SymbolsIter<'reader, 'data> {
+    type Item = Symbol<'reader, 'data>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let symbol = self.symbols.get(self.index);
+        self.index += 1;
+
+        symbol
+    }
+}
+
+/// A symbol in a symbol table.
+#[derive(Debug, Clone)]
+pub struct Symbol<'reader, 'data> {
+    elf: &'reader ElfReader<'data>,
+    offset: usize,
+    /// The index of the symbol in the symbol table
+    index: usize,
+    /// The index of the symbol table section
+    table: usize,
+}
+
+impl Symbol<'_, '_> {
+    /// The string table index of the symbol's name. `st_name` in the specification.
+    pub fn name(&self) -> u32 {
+        self.elf.read_u32(self.offset).unwrap()
+    }
+
+    /// The value of the symbol, usually an address. `st_value` in the specification.
+    pub fn value(&self) -> u64 {
+        if self.elf.is_64bit() {
+            self.elf.read_u64(self.offset + 8).unwrap()
+        } else {
+            self.elf.read_u32(self.offset + 4).unwrap().into()
+        }
+    }
+
+    /// The size of the object the symbol refers to, or 0 if unknown. `st_size` in the
+    /// specification.
+    pub fn size(&self) -> u64 {
+        if self.elf.is_64bit() {
+            self.elf.read_u64(self.offset + 16).unwrap()
+        } else {
+            self.elf.read_u32(self.offset + 8).unwrap().into()
+        }
+    }
+
+    /// The symbol's type and binding attributes. `st_info` in the specification.
+    pub fn info(&self) -> u8 {
+        if self.elf.is_64bit() {
+            self.elf.read_u8(self.offset + 4).unwrap()
+        } else {
+            self.elf.read_u8(self.offset + 12).unwrap()
+        }
+    }
+
+    /// The symbol's visibility. `st_other` in the specification.
+    pub fn other(&self) -> u8 {
+        if self.elf.is_64bit() {
+            self.elf.read_u8(self.offset + 5).unwrap()
+        } else {
+            self.elf.read_u8(self.offset + 13).unwrap()
+        }
+    }
+
+    /// The index of the section the symbol is defined in, or a special section index.
+    /// `st_shndx` in the specification.
+    pub fn shndx(&self) -> u16 {
+        if self.elf.is_64bit() {
+            self.elf.read_u16(self.offset + 6).unwrap()
+        } else {
+            self.elf.read_u16(self.offset + 14).unwrap()
+        }
+    }
+
+    /// The index of the section the symbol is defined in, or a special section index. This is
+    /// `st_shndx`, unless it is `SHN_XINDEX` (`0xffff`), in which case the index doesn't fit in
+    /// `st_shndx` and is stored in the [`SectionKind::SymTabShndx`] section linked to the symbol
+    /// table instead. Returns an error if there is no such section or it doesn't contain the index.
+    pub fn section_index(&self) -> Result<u32, ParseError> {
+        let shndx = self.shndx();
+
+        if shndx != SHN_XINDEX {
+            return Ok(shndx.into());
+        }
+
+        let indices = self
+            .elf
+            .sections()?
+            .of_kind(SectionKind::SymTabShndx)
+            .find(|section| usize::try_from(section.link()).unwrap() == self.table)
+            .ok_or(ParseError::InvalidValue("st_shndx"))?;
+
+        indices
+            .data()?
+            .get(self.index * 4..self.index * 4 + 4)
+            .map(|bytes| {
+                self.elf
+                    .endianness()
+                    .u32_from_bytes(bytes.try_into().unwrap())
+            })
+            .ok_or(ParseError::InvalidValue("st_shndx"))
+    }
+
+    /// The type of the symbol, stored in the low four bits of `st_info`.
+    pub fn kind(&self) -> ElfValue<SymbolKind, u8> {
+        split_symbol_info(self.info()).1
+    }
+
+    /// The binding of the symbol, stored in the high four bits of `st_info`.
+    pub fn binding(&self) -> ElfValue<SymbolBinding, u8> {
+        split_symbol_info(self.info()).0
+    }
+}
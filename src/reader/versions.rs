@@ -0,0 +1,274 @@
+use crate::{Endianness, SectionKind};
+
+use super::{ElfReader, ElfValue, ParseError, Section, Strings};
+
+/// A reader for the version definitions in a `SHT_GNU_verdef` section, `.gnu.version_d`. The
+/// names of the versions are in the string table linked to the section.
+#[derive(Debug, Clone)]
+pub struct VersionDefinitions<'reader, 'data> {
+    elf: &'reader ElfReader<'data>,
+    data: &'data [u8],
+    link: u32,
+}
+
+impl<'reader, 'data> VersionDefinitions<'reader, 'data> {
+    pub(super) fn new(section: &Section<'reader, 'data>) -> Result<Self, ParseError> {
+        if section.kind() != ElfValue::Known(SectionKind::GnuVerDef) {
+            return Err(ParseError::InvalidValue("sh_type"));
+        }
+
+        Ok(Self {
+            elf: section.elf,
+            data: section.data()?,
+            link: section.link(),
+        })
+    }
+
+    /// Returns the [`Strings`] object of the string table containing the names of the versions,
+    /// as specified by the section's `sh_link`.
+    pub fn strings(&self) -> Result<Strings<'data>, ParseError> {
+        linked_strings(self.elf, self.link)
+    }
+}
+
+impl<'data> IntoIterator for VersionDefinitions<'_, 'data> {
+    type Item = VersionDefinition;
+    type IntoIter = VersionDefinitionsIter<'data>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        VersionDefinitionsIter {
+            endianness: self.elf.endianness(),
+            data: self.data,
+            offset: Some(0),
+        }
+    }
+}
+
+/// An iterator over the version definitions in a section. Iteration stops at the last definition,
+/// whose `vd_next` is 0, or at the first definition that does not fit in the section.
+#[derive(Debug, Clone)]
+pub struct VersionDefinitionsIter<'data> {
+    endianness: Endianness,
+    data: &'data [u8],
+    offset: Option<usize>,
+}
+
+impl Iterator for VersionDefinitionsIter<'_> {
+    type Item = VersionDefinition;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let offset = self.offset.take()?;
+        let read_u16 =
+            |index: usize| read_u16(self.endianness, self.data, offset.checked_add(index)?);
+        let read_u32 =
+            |index: usize| read_u32(self.endianness, self.data, offset.checked_add(index)?);
+
+        let flags = read_u16(2)?;
+        let index = read_u16(4)?;
+        let hash = read_u32(8)?;
+        let aux = usize::try_from(read_u32(12)?).ok()?;
+        let next = usize::try_from(read_u32(16)?).ok()?;
+        // the first Elf_Verdaux entry names the version, any others name its parents
+        let name = read_u32(aux)?;
+
+        if next != 0 {
+            self.offset = offset.checked_add(next);
+        }
+
+        Some(VersionDefinition {
+            flags,
+            index,
+            hash,
+            name,
+        })
+    }
+}
+
+/// A version defined by the file, `Elf_Verdef` in the specification.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VersionDefinition {
+    flags: u16,
+    index: u16,
+    hash: u32,
+    name: u32,
+}
+
+impl VersionDefinition {
+    /// The version flags, such as `VER_FLG_BASE` (1) for the version of the file itself.
+    /// `vd_flags` in the specification.
+    pub fn flags(&self) -> u16 {
+        self.flags
+    }
+
+    /// The version index that symbols of this version have in the `.gnu.version` section.
+    /// `vd_ndx` in the specification.
+    pub fn index(&self) -> u16 {
+        self.index
+    }
+
+    /// The ELF hash of the version name. `vd_hash` in the specification.
+    pub fn hash(&self) -> u32 {
+        self.hash
+    }
+
+    /// The string table index of the version name, from the first `Elf_Verdaux` entry.
+    pub fn name(&self) -> u32 {
+        self.name
+    }
+}
+
+/// A reader for the version requirements in a `SHT_GNU_verneed` section, `.gnu.version_r`. The
+/// names of the files and the versions are in the string table linked to the section.
+#[derive(Debug, Clone)]
+pub struct VersionRequirements<'reader, 'data> {
+    elf: &'reader ElfReader<'data>,
+    data: &'data [u8],
+    link: u32,
+}
+
+impl<'reader, 'data> VersionRequirements<'reader, 'data> {
+    pub(super) fn new(section: &Section<'reader, 'data>) -> Result<Self, ParseError> {
+        if section.kind() != ElfValue::Known(SectionKind::GnuVerNeed) {
+            return Err(ParseError::InvalidValue("sh_type"));
+        }
+
+        Ok(Self {
+            elf: section.elf,
+            data: section.data()?,
+            link: section.link(),
+        })
+    }
+
+    /// Returns the [`Strings`] object of the string table containing the names of the files and
+    /// the versions, as specified by the section's `sh_link`.
+    pub fn strings(&self) -> Result<Strings<'data>, ParseError> {
+        linked_strings(self.elf, self.link)
+    }
+}
+
+impl<'data> IntoIterator for VersionRequirements<'_, 'data> {
+    type Item = VersionRequirement;
+    type IntoIter = VersionRequirementsIter<'data>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        VersionRequirementsIter {
+            endianness: self.elf.endianness(),
+            data: self.data,
+            file: Some(0),
+            aux: None,
+        }
+    }
+}
+
+/// An iterator over the versions required from every file listed in a section. The versions of
+/// each file are yielded in order, followed by the versions of the next file. Iteration stops at
+/// the last entry, whose `vn_next` is 0, or at the first entry that does not fit in the section.
+#[derive(Debug, Clone)]
+pub struct VersionRequirementsIter<'data> {
+    endianness: Endianness,
+    data: &'data [u8],
+    /// The offset of the next `Elf_Verneed` entry
+    file: Option<usize>,
+    /// The offset of the current `Elf_Verneed` entry and of its next `Elf_Vernaux` entry
+    aux: Option<(usize, usize)>,
+}
+
+impl Iterator for VersionRequirementsIter<'_> {
+    type Item = VersionRequirement;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let read_u16 = |index: usize| read_u16(self.endianness, self.data, index);
+        let read_u32 = |index: usize| read_u32(self.endianness, self.data, index);
+
+        let (file, aux) = loop {
+            if let Some(aux) = self.aux.take() {
+                break aux;
+            }
+
+            let file = self.file.take()?;
+            let next = usize::try_from(read_u32(file.checked_add(12)?)?).ok()?;
+            if next != 0 {
+                self.file = file.checked_add(next);
+            }
+
+            // files without any versions are skipped
+            if read_u16(file.checked_add(2)?)? != 0 {
+                let aux = usize::try_from(read_u32(file.checked_add(8)?)?).ok()?;
+                break (file, file.checked_add(aux)?);
+            }
+        };
+
+        let hash = read_u32(aux)?;
+        let flags = read_u16(aux.checked_add(4)?)?;
+        let index = read_u16(aux.checked_add(6)?)?;
+        let name = read_u32(aux.checked_add(8)?)?;
+        let next = usize::try_from(read_u32(aux.checked_add(12)?)?).ok()?;
+
+        if next != 0 {
+            self.aux = Some((file, aux.checked_add(next)?));
+        }
+
+        Some(VersionRequirement {
+            file: read_u32(file.checked_add(4)?)?,
+            hash,
+            flags,
+            index,
+            name,
+        })
+    }
+}
+
+/// A version required from a shared object, `Elf_Vernaux` in the specification, along with the
+/// file name from its `Elf_Verneed` entry.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VersionRequirement {
+    file: u32,
+    hash: u32,
+    flags: u16,
+    index: u16,
+    name: u32,
+}
+
+impl VersionRequirement {
+    /// The string table index of the name of the shared object the version is required from,
+    /// such as `libc.so.6`. `vn_file` in the specification.
+    pub fn file(&self) -> u32 {
+        self.file
+    }
+
+    /// The ELF hash of the version name. `vna_hash` in the specification.
+    pub fn hash(&self) -> u32 {
+        self.hash
+    }
+
+    /// The version flags, such as `VER_FLG_WEAK` (2). `vna_flags` in the specification.
+    pub fn flags(&self) -> u16 {
+        self.flags
+    }
+
+    /// The version index that symbols of this version have in the `.gnu.version` section.
+    /// `vna_other` in the specification.
+    pub fn index(&self) -> u16 {
+        self.index
+    }
+
+    /// The string table index of the version name, such as `GLIBC_2.2.5`. `vna_name` in the
+    /// specification.
+    pub fn name(&self) -> u32 {
+        self.name
+    }
+}
+
+fn linked_strings<'data>(elf: &ElfReader<'data>, link: u32) -> Result<Strings<'data>, ParseError> {
+    Strings::new(elf, usize::try_from(link).unwrap(), "sh_link")
+}
+
+fn read_u16(endianness: Endianness, data: &[u8], index: usize) -> Option<u16> {
+    data.get(index..index.checked_add(2)?)
+        .map(|bytes| endianness.u16_from_bytes(bytes.try_into().unwrap()))
+}
+
+fn read_u32(endianness: Endianness, data: &[u8], index: usize) -> Option<u32> {
+    data.get(index..index.checked_add(4)?)
+        .map(|bytes| endianness.u32_from_bytes(bytes.try_into().unwrap()))
+}
@@ -0,0 +1,98 @@
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    borrow::Cow,
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+use eelf::{
+    builder::{RelaEntry, RelocationTable, Section},
+    ElfBuilder, ElfKind, Endianness, MachineKind, SectionFlag, SectionKind, SymbolBinding,
+    SymbolKind,
+};
+
+/// Keeps track of the number of bytes allocated, and the highest number of bytes allocated at
+/// once.
+struct CountingAllocator;
+
+static ALLOCATED: AtomicUsize = AtomicUsize::new(0);
+static PEAK: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let allocated = ALLOCATED.fetch_add(layout.size(), Ordering::SeqCst) + layout.size();
+        PEAK.fetch_max(allocated, Ordering::SeqCst);
+
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        ALLOCATED.fetch_sub(layout.size(), Ordering::SeqCst);
+
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+#[test]
+fn build_does_not_buffer_tables() {
+    const COUNT: u64 = 5000;
+
+    let mut builder = ElfBuilder::new(
+        ElfKind::Relocatable,
+        MachineKind::X86_64,
+        true,
+        Endianness::Little,
+    );
+
+    let name = builder.add_string(".text");
+    let text = builder
+        .add_section(Section {
+            data: Cow::Borrowed(&[0; 16]),
+            name,
+            kind: SectionKind::Progbits,
+            flags: SectionFlag::Alloc | SectionFlag::ExecInstr,
+            extra_flags: 0,
+            vaddr: 0,
+            info: 0,
+            entsize: 0,
+            alignment: 16,
+            size: None,
+            link: None,
+        })
+        .unwrap();
+
+    let mut table = builder.create_rela_table(".rela.text", text);
+
+    for i in 0..COUNT {
+        let binding = match i % 2 {
+            0 => SymbolBinding::Global,
+            _ => SymbolBinding::Local,
+        };
+        let symbol = builder
+            .add_symbol(format!("symbol{i}"), i, 0, binding, SymbolKind::Func, text)
+            .unwrap();
+
+        table.add(RelaEntry {
+            offset: i,
+            info: (u64::from(symbol) << 32) | 1,
+            addend: 0,
+        });
+    }
+
+    builder.add_relocation_table(RelocationTable::Rela(table));
+
+    let before = ALLOCATED.load(Ordering::SeqCst);
+    PEAK.store(before, Ordering::SeqCst);
+
+    builder.build(std::io::sink()).unwrap();
+
+    // the symbol table and the relocation table are 24 bytes per entry each, so buffering either
+    // of them would exceed this
+    let extra = PEAK.load(Ordering::SeqCst) - before;
+    assert!(
+        extra < usize::try_from(COUNT * 24).unwrap(),
+        "{extra} bytes allocated"
+    );
+}
@@ -1,12 +1,39 @@
 use std::borrow::Cow;
 
 use eelf::{
-    builder::{RelEntry, RelaEntry, RelocationTable, Section, Segment},
+    builder::{
+        RelEntry, RelaEntry, RelocationTable, Section, SectionHeaderPlacement, Segment, StringId,
+    },
     flagset::FlagSet,
-    ElfBuilder, ElfKind, Endianness, MachineKind, SectionFlag, SectionKind, SegmentFlag,
-    SegmentKind, SymbolKind,
+    reader::ElfValue,
+    BuildError, CompressionKind, ElfBuilder, ElfKind, ElfReader, Endianness, GroupFlag,
+    MachineKind, OsAbi, ParseError, SectionFlag, SectionKind, SegmentFlag, SegmentKind,
+    SymbolBinding, SymbolKind,
 };
 
+/// Returns a section with the given name, type, flags, and data, no alignment, and the other
+/// fields set to 0 or [`None`]. The other fields can be set with the struct update syntax.
+fn new_section<'data>(
+    name: StringId,
+    kind: SectionKind,
+    flags: FlagSet<SectionFlag>,
+    data: &'data [u8],
+) -> Section<'data> {
+    Section {
+        data: Cow::Borrowed(data),
+        name,
+        kind,
+        flags,
+        extra_flags: 0,
+        vaddr: 0,
+        info: 0,
+        entsize: 0,
+        alignment: 1,
+        size: None,
+        link: None,
+    }
+}
+
 #[test]
 fn nonsense_build() {
     let mut builder = ElfBuilder::new(
@@ -16,58 +43,84 @@ fn nonsense_build() {
         Endianness::Big,
     );
 
-    builder.set_entrypoint(0xc001c0d3);
+    builder.set_entrypoint(0xc001c0d3).unwrap();
 
     let section_name = builder.add_string(".verylongsectionnamejusttotestthestringtable");
-    builder.add_section(Section {
-        // randomly generated, chosen by fair dice roll
-        data: Cow::Borrowed(&[0x71, 0xb5, 0x88, 0xba, 0x44, 0x2a, 0x05, 0x2c]),
-        name: section_name,
-        kind: SectionKind::Progbits,
-        flags: SectionFlag::Alloc | SectionFlag::OsNonconforming,
-        vaddr: 0x1122334455667788,
-        info: 0,
-        entsize: 0xceadeeda,
-        alignment: 0x11f000,
-    });
+    builder
+        .add_section(Section {
+            vaddr: 0x1122334455667788,
+            entsize: 0xceadeeda,
+            alignment: 0x20,
+            ..new_section(
+                section_name,
+                SectionKind::Progbits,
+                SectionFlag::Alloc | SectionFlag::OsNonconforming,
+                // randomly generated, chosen by fair dice roll
+                &[0x71, 0xb5, 0x88, 0xba, 0x44, 0x2a, 0x05, 0x2c],
+            )
+        })
+        .unwrap();
 
     let section_name = builder.add_string(".section");
-    let section = builder.add_section(Section {
-        data: Cow::Owned(vec![1, 2, 3, 4, 5, 6, 7, 8]),
-        name: section_name,
-        kind: SectionKind::Progbits,
-        flags: FlagSet::new(0).unwrap(),
-        vaddr: 0x7fffffff98760000,
-        info: 0,
-        entsize: 4,
-        alignment: 0x1000,
-    });
+    let section = builder
+        .add_section(Section {
+            data: Cow::Owned(vec![1, 2, 3, 4, 5, 6, 7, 8]),
+            vaddr: 0x7fffffff98760000,
+            entsize: 4,
+            alignment: 0x1000,
+            ..new_section(section_name, SectionKind::Progbits, FlagSet::default(), &[])
+        })
+        .unwrap();
 
-    builder.add_segment(Segment {
-        section,
-        kind: SegmentKind::Tls,
-        vaddr: 0x7fffffff98760000,
-        paddr: 0x6eeeeeee87650000,
-        filesz: 8,
-        memsz: 24,
-        flags: SegmentFlag::Read | SegmentFlag::Write,
-        align: 0x4000,
-    });
+    builder
+        .add_segment(Segment {
+            section,
+            kind: SegmentKind::Tls,
+            vaddr: 0x7fffffff98760000,
+            paddr: 0x6eeeeeee87650000,
+            filesz: 8,
+            memsz: 24,
+            flags: SegmentFlag::Read | SegmentFlag::Write,
+            align: 0x4000,
+            last_section: None,
+        })
+        .unwrap();
 
     let symbol_table = builder.symbol_table();
-    builder.add_segment(Segment {
-        section: symbol_table,
-        kind: SegmentKind::Load,
-        vaddr: 0x1122334433443322,
-        paddr: 0x4232fab213a9923a,
-        filesz: 16,
-        memsz: 17,
-        flags: SegmentFlag::Execute | SegmentFlag::Write,
-        align: 0x12,
-    });
+    builder
+        .add_segment(Segment {
+            section: symbol_table,
+            kind: SegmentKind::Load,
+            vaddr: 0x1122334433443322,
+            paddr: 0x4232fab213a9923a,
+            filesz: 16,
+            memsz: 17,
+            flags: SegmentFlag::Execute | SegmentFlag::Write,
+            align: 0x12,
+            last_section: None,
+        })
+        .unwrap();
 
-    builder.add_symbol("local_symbol", 9, 32, false, SymbolKind::Object, section);
-    builder.add_symbol("_____staaaaaaart", 4, 16, true, SymbolKind::Func, section);
+    builder
+        .add_symbol(
+            "local_symbol",
+            9,
+            32,
+            SymbolBinding::Local,
+            SymbolKind::Object,
+            section,
+        )
+        .unwrap();
+    builder
+        .add_symbol(
+            "_____staaaaaaart",
+            4,
+            16,
+            SymbolBinding::Global,
+            SymbolKind::Func,
+            section,
+        )
+        .unwrap();
 
     let mut rel_table = builder.create_rel_table(".rel.section", section);
     rel_table.add(RelEntry {
@@ -85,7 +138,2306 @@ fn nonsense_build() {
     builder.add_relocation_table(RelocationTable::Rela(rela_table));
 
     let mut bytes = Vec::new();
-    builder.build(&mut bytes).unwrap();
+    builder.clone().build(&mut bytes).unwrap();
 
     assert_eq!(bytes, include_bytes!("nonsense.bin"));
+    assert_eq!(builder.build_to_vec().unwrap(), bytes);
+}
+
+#[test]
+fn symbol_order() {
+    let mut builder = ElfBuilder::new(
+        ElfKind::Relocatable,
+        MachineKind::RiscV,
+        false,
+        Endianness::Little,
+    );
+
+    let name = builder.add_string(".text");
+    let text = builder
+        .add_section(Section {
+            alignment: 4,
+            ..new_section(
+                name,
+                SectionKind::Progbits,
+                SectionFlag::Alloc | SectionFlag::ExecInstr,
+                &[0; 8],
+            )
+        })
+        .unwrap();
+
+    let global = builder
+        .add_symbol(
+            "global",
+            0,
+            4,
+            SymbolBinding::Global,
+            SymbolKind::Func,
+            text,
+        )
+        .unwrap();
+    builder
+        .add_symbol("weak", 4, 4, SymbolBinding::Weak, SymbolKind::Func, text)
+        .unwrap();
+    let local = builder
+        .add_symbol("local", 4, 4, SymbolBinding::Local, SymbolKind::Func, text)
+        .unwrap();
+
+    let mut rel_table = builder.create_rel_table(".rel.text", text);
+    rel_table.add(RelEntry {
+        offset: 0,
+        info: (u64::from(local) << 8) | 18,
+    });
+    rel_table.add(RelEntry {
+        offset: 4,
+        info: (u64::from(global) << 8) | 18,
+    });
+    builder.add_relocation_table(RelocationTable::Rel(rel_table));
+
+    let bytes = builder.build_to_vec().unwrap();
+
+    let reader = ElfReader::new(&bytes).unwrap();
+    let sections = reader.sections().unwrap();
+    let symtab = sections
+        .clone()
+        .into_iter()
+        .find(|section| section.kind() == ElfValue::Known(SectionKind::SymbolTable))
+        .unwrap();
+    let symbols = symtab.symbols().unwrap();
+    let strings = symbols.strings().unwrap();
+
+    let bindings = symbols
+        .clone()
+        .into_iter()
+        .map(|symbol| symbol.binding())
+        .collect::<Vec<_>>();
+    assert_eq!(
+        bindings,
+        [
+            ElfValue::Known(SymbolBinding::Local),
+            ElfValue::Known(SymbolBinding::Local),
+            ElfValue::Known(SymbolBinding::Global),
+            ElfValue::Known(SymbolBinding::Weak),
+        ]
+    );
+    assert_eq!(symtab.info(), 2);
+
+    let names = sections
+        .into_iter()
+        .find(|section| section.kind() == ElfValue::Known(SectionKind::Rel))
+        .unwrap()
+        .relocations()
+        .unwrap()
+        .into_iter()
+        .map(|relocation| {
+            let symbol = symbols
+                .get(relocation.symbol().try_into().unwrap())
+                .unwrap();
+            strings.get_str(symbol.name().into()).unwrap().unwrap()
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(names, ["local", "global"]);
+}
+
+#[test]
+fn relocation_entries() {
+    for is_64bit in [false, true] {
+        let mut builder = ElfBuilder::new(
+            ElfKind::Relocatable,
+            MachineKind::X86_64,
+            is_64bit,
+            Endianness::Little,
+        );
+        let name = builder.add_string(".text");
+        let text = builder
+            .add_section(new_section(
+                name,
+                SectionKind::Progbits,
+                SectionFlag::Alloc | SectionFlag::ExecInstr,
+                &[0; 8],
+            ))
+            .unwrap();
+        builder
+            .add_symbol("global", 0, 0, true, SymbolKind::Func, text)
+            .unwrap();
+        // sorted before the global symbol when building
+        let local = builder
+            .add_symbol("local", 4, 0, false, SymbolKind::Func, text)
+            .unwrap();
+
+        let entry = RelEntry::new(4, local, 2, is_64bit).unwrap();
+        let shift = if is_64bit { 32 } else { 8 };
+        assert_eq!(entry.info, 2 << shift | 2);
+        let mut rel_table = builder.create_rel_table(".rel.text", text);
+        rel_table.add(entry);
+        builder.add_relocation_table(RelocationTable::Rel(rel_table));
+
+        let mut rela_table = builder.create_rela_table(".rela.text", text);
+        rela_table.add(RelaEntry::new(0, local, 4, 0xfffc, is_64bit).unwrap());
+        builder.add_relocation_table(RelocationTable::Rela(rela_table));
+
+        assert_eq!(RelEntry::new(0, local, 0x100, is_64bit).is_ok(), is_64bit);
+
+        let bytes = builder.build_to_vec().unwrap();
+        let reader = ElfReader::new(&bytes).unwrap();
+        let relocations = reader
+            .sections()
+            .unwrap()
+            .into_iter()
+            .filter_map(|section| section.relocations().ok())
+            .flatten()
+            .map(|relocation| (relocation.offset(), relocation.symbol(), relocation.kind()))
+            .collect::<Vec<_>>();
+        assert_eq!(relocations, [(4, 1, 2), (0, 1, 4)]);
+    }
+}
+
+#[test]
+fn symbol_in_named_section() {
+    let mut builder = ElfBuilder::new(
+        ElfKind::Relocatable,
+        MachineKind::X86_64,
+        true,
+        Endianness::Little,
+    );
+
+    for (name, kind, data, size) in [
+        (".text", SectionKind::Progbits, &[0xc3][..], None),
+        (".bss", SectionKind::Nobits, &[], Some(8)),
+    ] {
+        let name = builder.add_string(name);
+        builder
+            .add_section(Section {
+                size,
+                ..new_section(name, kind, SectionFlag::Alloc.into(), data)
+            })
+            .unwrap();
+    }
+
+    builder
+        .add_symbol_in("counter", 0, 8, true, SymbolKind::Object, ".bss")
+        .unwrap();
+    assert_eq!(
+        builder.add_symbol_in("missing", 0, 0, true, SymbolKind::Func, ".data"),
+        Err(BuildError::InvalidValue("st_shndx"))
+    );
+
+    let bytes = builder.build_to_vec().unwrap();
+
+    let reader = ElfReader::new(&bytes).unwrap();
+    let symbols = reader
+        .sections()
+        .unwrap()
+        .of_kind(SectionKind::SymbolTable)
+        .next()
+        .unwrap()
+        .symbols()
+        .unwrap();
+    let strings = symbols.strings().unwrap();
+    let symbol = symbols.into_iter().nth(1).unwrap();
+    assert_eq!(strings.get_str(symbol.name().into()), Some(Ok("counter")));
+    assert_eq!(symbol.section_index(), Ok(2));
+}
+
+#[test]
+fn reserved_symbol_sections() {
+    let mut builder = ElfBuilder::new(
+        ElfKind::Relocatable,
+        MachineKind::X86_64,
+        false,
+        Endianness::Little,
+    );
+
+    let abs = builder.absolute_section();
+    let common = builder.common_section();
+    builder
+        .add_symbol("version", 3, 0, true, SymbolKind::NoType, abs)
+        .unwrap();
+    // the value of a common symbol is its alignment
+    builder
+        .add_symbol("buffer", 16, 64, true, SymbolKind::Object, common)
+        .unwrap();
+
+    let bytes = builder.build_to_vec().unwrap();
+
+    let reader = ElfReader::new(&bytes).unwrap();
+    let symbols = reader
+        .sections()
+        .unwrap()
+        .of_kind(SectionKind::SymbolTable)
+        .next()
+        .unwrap()
+        .symbols()
+        .unwrap();
+    let strings = symbols.strings().unwrap();
+    let symbols = symbols
+        .into_iter()
+        .skip(1)
+        .map(|symbol| {
+            (
+                strings.get_str(symbol.name().into()).unwrap().unwrap(),
+                symbol.value(),
+                symbol.shndx(),
+            )
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(symbols, [("version", 3, 0xfff1), ("buffer", 16, 0xfff2)]);
+
+    // relocations can only apply to sections added to the builder
+    let mut builder = ElfBuilder::new(
+        ElfKind::Relocatable,
+        MachineKind::X86_64,
+        false,
+        Endianness::Little,
+    );
+    let abs = builder.absolute_section();
+    let table = builder.create_rel_table(".rel.abs", abs);
+    builder.add_relocation_table(RelocationTable::Rel(table));
+    let error = eelf::Error::from(builder.build_to_vec().unwrap_err());
+    assert!(matches!(
+        error,
+        eelf::Error::Build(BuildError::InvalidValue("sh_info"))
+    ));
+}
+
+#[test]
+fn dynamic_symbol_table() {
+    let mut builder = ElfBuilder::new(
+        ElfKind::Dynamic,
+        MachineKind::X86_64,
+        true,
+        Endianness::Little,
+    );
+
+    let name = builder.add_string(".text");
+    let text = builder
+        .add_section(Section {
+            vaddr: 0x1000,
+            ..new_section(
+                name,
+                SectionKind::Progbits,
+                SectionFlag::Alloc | SectionFlag::ExecInstr,
+                &[0xc3],
+            )
+        })
+        .unwrap();
+
+    builder
+        .add_dynamic_symbol(
+            "exported",
+            0x1000,
+            1,
+            SymbolBinding::Global,
+            SymbolKind::Func,
+            text,
+        )
+        .unwrap();
+    builder
+        .add_symbol(
+            "internal",
+            0x1000,
+            1,
+            SymbolBinding::Local,
+            SymbolKind::Func,
+            text,
+        )
+        .unwrap();
+
+    builder.allow_unusual();
+    let bytes = builder.build_to_vec().unwrap();
+
+    let reader = ElfReader::new(&bytes).unwrap();
+    let sections = reader.sections().unwrap();
+    let strings = reader.strings().unwrap();
+    let dynsym = sections
+        .clone()
+        .into_iter()
+        .find(|section| section.kind() == ElfValue::Known(SectionKind::DynSym))
+        .unwrap();
+
+    assert_eq!(strings.get_str(dynsym.name().into()), Some(Ok(".dynsym")));
+    assert_eq!(dynsym.flags(), ElfValue::Known(SectionFlag::Alloc.into()));
+    assert_eq!(dynsym.info(), 1);
+
+    let dynstr = sections.get(dynsym.link().try_into().unwrap()).unwrap();
+    assert_eq!(strings.get_str(dynstr.name().into()), Some(Ok(".dynstr")));
+    assert_eq!(dynstr.kind(), ElfValue::Known(SectionKind::StringTable));
+    assert_eq!(dynstr.flags(), ElfValue::Known(SectionFlag::Alloc.into()));
+
+    let symbols = dynsym.symbols().unwrap();
+    let symbol = symbols.get(1).unwrap();
+    assert_eq!(
+        symbols.strings().unwrap().get_str(symbol.name().into()),
+        Some(Ok("exported"))
+    );
+    assert_eq!(symbol.value(), 0x1000);
+    assert!(symbols.get(2).is_none());
+
+    let symtab = sections
+        .into_iter()
+        .find(|section| section.kind() == ElfValue::Known(SectionKind::SymbolTable))
+        .unwrap();
+    let symbols = symtab.symbols().unwrap();
+    assert_eq!(
+        symbols
+            .strings()
+            .unwrap()
+            .get_str(symbols.get(1).unwrap().name().into()),
+        Some(Ok("internal"))
+    );
+}
+
+#[test]
+fn dynamic_symbol_order() {
+    let mut builder = ElfBuilder::new(
+        ElfKind::Relocatable,
+        MachineKind::X86_64,
+        true,
+        Endianness::Little,
+    );
+    let null = builder.null_section();
+
+    let global = builder
+        .add_dynamic_symbol("g", 0, 0, SymbolBinding::Global, SymbolKind::Func, null)
+        .unwrap();
+    assert_eq!(u64::from(global), 1);
+    let local = builder
+        .add_dynamic_symbol("l", 0, 0, SymbolBinding::Local, SymbolKind::Func, null)
+        .unwrap();
+    assert_eq!(u64::from(local), 1);
+    let weak = builder
+        .add_dynamic_symbol("w", 0, 0, SymbolBinding::Weak, SymbolKind::Func, null)
+        .unwrap();
+    assert_eq!(u64::from(weak), 3);
+
+    let bytes = builder.build_to_vec().unwrap();
+    let reader = ElfReader::new(&bytes).unwrap();
+    let dynsym = reader
+        .sections()
+        .unwrap()
+        .first_of_kind(SectionKind::DynSym)
+        .unwrap();
+    assert_eq!(dynsym.info(), 2);
+
+    let symbols = dynsym.symbols().unwrap();
+    let strings = symbols.strings().unwrap();
+    let names = symbols
+        .into_iter()
+        .skip(1)
+        .map(|symbol| strings.get_str(symbol.name().into()).unwrap().unwrap())
+        .collect::<Vec<_>>();
+    assert_eq!(names, ["l", "g", "w"]);
+}
+
+#[test]
+fn header_flags() {
+    for is_64bit in [true, false] {
+        let mut builder = ElfBuilder::new(
+            ElfKind::Relocatable,
+            MachineKind::RiscV,
+            is_64bit,
+            Endianness::Little,
+        );
+
+        // RVC, double-float ABI
+        builder.set_flags(0x1 | 0x4);
+
+        let bytes = builder.build_to_vec().unwrap();
+
+        let reader = ElfReader::new(&bytes).unwrap();
+        assert_eq!(reader.header().unwrap().flags(), 0x5);
+    }
+}
+
+#[test]
+fn header_osabi() {
+    for is_64bit in [true, false] {
+        let mut builder = ElfBuilder::new(
+            ElfKind::Relocatable,
+            MachineKind::X86_64,
+            is_64bit,
+            Endianness::Big,
+        );
+
+        builder.set_osabi(OsAbi::FreeBsd);
+        builder.set_abiversion(3);
+
+        let bytes = builder.build_to_vec().unwrap();
+
+        let reader = ElfReader::new(&bytes).unwrap();
+        let header = reader.header().unwrap();
+        assert_eq!(header.osabi(), ElfValue::Known(OsAbi::FreeBsd));
+        assert_eq!(header.abiversion(), 3);
+        assert_eq!(&header.ident()[9..], &[0; 7]);
+    }
+}
+
+#[test]
+fn ident_bytes() {
+    let mut builder = ElfBuilder::new(
+        ElfKind::Relocatable,
+        MachineKind::X86_64,
+        false,
+        Endianness::Little,
+    );
+
+    builder.set_ident_byte(9, b'E').unwrap();
+    builder.set_ident_byte(15, b'!').unwrap();
+    for index in [0, 8, 16] {
+        assert_eq!(
+            builder.set_ident_byte(index, 1),
+            Err(BuildError::InvalidValue("e_ident"))
+        );
+    }
+
+    let bytes = builder.build_to_vec().unwrap();
+    let reader = ElfReader::new(&bytes).unwrap();
+    let header = reader.header().unwrap();
+    assert_eq!(header.ident_padding(), b"E\0\0\0\0\0!");
+    assert_eq!(header.abiversion(), 0);
+
+    // the padding is kept when importing
+    let bytes = ElfBuilder::from_reader(&reader)
+        .unwrap()
+        .build_to_vec()
+        .unwrap();
+    let reader = ElfReader::new(&bytes).unwrap();
+    assert_eq!(reader.header().unwrap().ident_padding(), b"E\0\0\0\0\0!");
+}
+
+#[test]
+fn header_version() {
+    for is_64bit in [true, false] {
+        let mut builder = ElfBuilder::new(
+            ElfKind::Relocatable,
+            MachineKind::X86_64,
+            is_64bit,
+            Endianness::Little,
+        );
+
+        let bytes = builder.clone().build_to_vec().unwrap();
+        let reader = ElfReader::new(&bytes).unwrap();
+        let header = reader.header().unwrap();
+        assert_eq!(header.ei_version(), 1);
+        assert_eq!(header.version(), 1);
+
+        builder.set_version(2);
+        let bytes = builder.clone().build_to_vec().unwrap();
+        let reader = ElfReader::new(&bytes).unwrap();
+        assert_eq!(reader.header().unwrap().version(), 2);
+
+        // the reader rejects other versions in the identification bytes
+        builder.set_ei_version(2);
+        let bytes = builder.build_to_vec().unwrap();
+        assert_eq!(bytes[6], 2);
+        assert_eq!(
+            ElfReader::new(&bytes).unwrap_err(),
+            ParseError::InvalidValue("ei_version")
+        );
+    }
+}
+
+#[test]
+fn unusual_kinds() {
+    let build = |kind, entrypoint, segment: bool, allow_unusual: bool| {
+        let mut builder = ElfBuilder::new(kind, MachineKind::X86_64, true, Endianness::Little);
+        builder.set_entrypoint(entrypoint).unwrap();
+
+        if segment {
+            let name = builder.add_string(".text");
+            let section = builder
+                .add_section(Section {
+                    vaddr: 0x401000,
+                    ..new_section(
+                        name,
+                        SectionKind::Progbits,
+                        SectionFlag::Alloc | SectionFlag::ExecInstr,
+                        &[0xc3],
+                    )
+                })
+                .unwrap();
+            builder
+                .add_segment(Segment {
+                    section,
+                    kind: SegmentKind::Load,
+                    vaddr: 0x401000,
+                    paddr: 0x401000,
+                    filesz: 1,
+                    memsz: 1,
+                    flags: SegmentFlag::Read | SegmentFlag::Execute,
+                    align: 0x1000,
+                    last_section: None,
+                })
+                .unwrap();
+        }
+        if allow_unusual {
+            builder.allow_unusual();
+        }
+
+        builder.build_to_vec().map_err(eelf::Error::from)
+    };
+
+    fn invalid(result: Result<Vec<u8>, eelf::Error>, field: &str) -> bool {
+        matches!(
+            result,
+            Err(eelf::Error::Build(BuildError::InvalidValue(f))) if f == field
+        )
+    }
+
+    assert!(build(ElfKind::Executable, 0x401000, true, false).is_ok());
+    assert!(build(ElfKind::Relocatable, 0, false, false).is_ok());
+    assert!(build(ElfKind::Core, 0, false, false).is_ok());
+
+    assert!(invalid(
+        build(ElfKind::Relocatable, 0, true, false),
+        "e_phnum"
+    ));
+    assert!(invalid(
+        build(ElfKind::Relocatable, 0x401000, false, false),
+        "e_entry"
+    ));
+    assert!(invalid(
+        build(ElfKind::Executable, 0x401000, false, false),
+        "e_phnum"
+    ));
+    assert!(invalid(build(ElfKind::Dynamic, 0, false, false), "e_phnum"));
+
+    assert!(build(ElfKind::Relocatable, 0x401000, true, true).is_ok());
+    assert!(build(ElfKind::Dynamic, 0, false, true).is_ok());
+}
+
+#[test]
+fn section_alignment() {
+    for is_64bit in [true, false] {
+        let mut builder = ElfBuilder::new(
+            ElfKind::Executable,
+            MachineKind::RiscV,
+            is_64bit,
+            Endianness::Little,
+        );
+
+        let name = builder.add_string(".text");
+        let text = builder
+            .add_section(Section {
+                vaddr: 0x10000,
+                alignment: 0x1000,
+                ..new_section(
+                    name,
+                    SectionKind::Progbits,
+                    SectionFlag::Alloc | SectionFlag::ExecInstr,
+                    &[0x13, 0, 0, 0],
+                )
+            })
+            .unwrap();
+
+        let name = builder.add_string(".data");
+        builder
+            .add_section(Section {
+                vaddr: 0x11000,
+                alignment: 16,
+                ..new_section(
+                    name,
+                    SectionKind::Progbits,
+                    SectionFlag::Alloc | SectionFlag::Write,
+                    &[1, 2, 3],
+                )
+            })
+            .unwrap();
+
+        builder
+            .add_segment(Segment {
+                section: text,
+                kind: SegmentKind::Load,
+                vaddr: 0x10000,
+                paddr: 0x10000,
+                filesz: 4,
+                memsz: 4,
+                flags: SegmentFlag::Read | SegmentFlag::Execute,
+                align: 0x1000,
+                last_section: None,
+            })
+            .unwrap();
+
+        let bytes = builder.build_to_vec().unwrap();
+
+        let reader = ElfReader::new(&bytes).unwrap();
+        let sections = reader.sections().unwrap();
+
+        let text = sections.get(1).unwrap();
+        assert_eq!(text.offset(), 0x1000);
+        assert_eq!(text.data().unwrap(), &[0x13, 0, 0, 0]);
+
+        let data = sections.get(2).unwrap();
+        assert_eq!(data.offset(), 0x1010);
+        assert_eq!(data.data().unwrap(), &[1, 2, 3]);
+
+        let segment = reader.segments().unwrap().get(0).unwrap();
+        assert_eq!(segment.offset(), 0x1000);
+        assert_eq!(
+            segment.offset() % segment.align(),
+            segment.vaddr() % segment.align()
+        );
+
+        let shoff = reader.header().unwrap().shoff();
+        assert_eq!(shoff % if is_64bit { 8 } else { 4 }, 0);
+    }
+}
+
+#[test]
+fn oversized_values() {
+    let mut builder = ElfBuilder::new(
+        ElfKind::Executable,
+        MachineKind::RiscV,
+        false,
+        Endianness::Little,
+    );
+
+    assert_eq!(
+        builder.set_entrypoint(0x1_0000_0000),
+        Err(BuildError::ValueTooLarge("e_entry"))
+    );
+
+    let name = builder.add_string(".text");
+    let section = Section {
+        vaddr: 0x1_0000_0000,
+        alignment: 4,
+        ..new_section(
+            name,
+            SectionKind::Progbits,
+            SectionFlag::Alloc | SectionFlag::ExecInstr,
+            &[0x13, 0, 0, 0],
+        )
+    };
+    assert_eq!(
+        builder.add_section(section.clone()),
+        Err(BuildError::ValueTooLarge("sh_addr"))
+    );
+
+    let text = builder
+        .add_section(Section {
+            vaddr: 0x10000,
+            ..section.clone()
+        })
+        .unwrap();
+
+    assert_eq!(
+        builder.add_symbol("_start", 0x10000, u64::MAX, true, SymbolKind::Func, text),
+        Err(BuildError::ValueTooLarge("st_size"))
+    );
+
+    let segment = Segment {
+        section: text,
+        kind: SegmentKind::Load,
+        vaddr: 0x10000,
+        paddr: 0x10000,
+        filesz: 4,
+        memsz: 2,
+        flags: SegmentFlag::Read | SegmentFlag::Execute,
+        align: 0x1000,
+        last_section: None,
+    };
+    assert_eq!(
+        builder.add_segment(segment.clone()),
+        Err(BuildError::InvalidValue("p_memsz"))
+    );
+    assert_eq!(
+        builder.add_segment(Segment {
+            memsz: 4,
+            align: 0x1_0000_0000,
+            ..segment
+        }),
+        Err(BuildError::ValueTooLarge("p_align"))
+    );
+
+    // the same values are fine in a 64-bit file
+    let mut builder = ElfBuilder::new(
+        ElfKind::Executable,
+        MachineKind::RiscV,
+        true,
+        Endianness::Little,
+    );
+    builder.set_entrypoint(0x1_0000_0000).unwrap();
+    builder.add_section(section).unwrap();
+}
+
+#[test]
+fn from_reader_round_trip() {
+    let bytes = include_bytes!("nonsense.bin");
+    let reader = ElfReader::new(bytes).unwrap();
+
+    let built = ElfBuilder::from_reader(&reader)
+        .unwrap()
+        .build_to_vec()
+        .unwrap();
+
+    assert_eq!(built, bytes);
+}
+
+#[test]
+fn from_reader_real_file() {
+    fn sections(reader: &ElfReader) -> Vec<(String, u64, Vec<u8>)> {
+        let strings = reader.strings().unwrap();
+
+        reader
+            .sections()
+            .unwrap()
+            .into_iter()
+            .filter(|section| {
+                section.kind() != ElfValue::Known(SectionKind::Null)
+                    && section.kind() != ElfValue::Known(SectionKind::StringTable)
+            })
+            .map(|section| {
+                let name = strings.get_str(section.name().into()).unwrap().unwrap();
+                let data = match section.kind() {
+                    ElfValue::Known(SectionKind::Nobits) => Vec::new(),
+                    _ => section.data().unwrap().to_vec(),
+                };
+
+                (name.to_string(), section.addr(), data)
+            })
+            .collect()
+    }
+
+    let bytes = include_bytes!("hello-world.bin");
+    let reader = ElfReader::new(bytes).unwrap();
+
+    let built = ElfBuilder::from_reader(&reader)
+        .unwrap()
+        .build_to_vec()
+        .unwrap();
+    let built_reader = ElfReader::new(&built).unwrap();
+
+    let header = reader.header().unwrap();
+    let built_header = built_reader.header().unwrap();
+    assert_eq!(built_header.entry(), header.entry());
+    assert_eq!(built_header.machine(), header.machine());
+    assert_eq!(built_header.flags(), header.flags());
+    assert_eq!(sections(&built_reader), sections(&reader));
+}
+
+#[test]
+fn from_reader_segment_overflow() {
+    let mut builder = ElfBuilder::new(
+        ElfKind::Executable,
+        MachineKind::X86_64,
+        true,
+        Endianness::Little,
+    );
+
+    let name = builder.add_string(".bss");
+    let bss = builder
+        .add_section(Section {
+            vaddr: 0x402000,
+            size: Some(0x10),
+            ..new_section(
+                name,
+                SectionKind::Nobits,
+                SectionFlag::Alloc | SectionFlag::Write,
+                &[],
+            )
+        })
+        .unwrap();
+    builder
+        .add_segment(Segment {
+            section: bss,
+            kind: SegmentKind::Load,
+            vaddr: 0x402000,
+            paddr: 0x402000,
+            filesz: 0,
+            memsz: 0x10,
+            flags: SegmentFlag::Read | SegmentFlag::Write,
+            align: 0x1000,
+            last_section: None,
+        })
+        .unwrap();
+    let mut bytes = builder.build_to_vec().unwrap();
+
+    // the data of NOBITS sections isn't read, so sh_offset and p_offset can be anything
+    let reader = ElfReader::new(&bytes).unwrap();
+    let header = reader.header().unwrap();
+    let shoff = usize::try_from(header.shoff()).unwrap();
+    let phoff = usize::try_from(header.phoff()).unwrap();
+    let (sh_offset, p_offset, p_filesz) = (shoff + 64 + 24, phoff + 8, phoff + 32);
+    bytes[sh_offset..sh_offset + 8].copy_from_slice(&(u64::MAX - 4).to_le_bytes());
+    bytes[p_offset..p_offset + 8].copy_from_slice(&(u64::MAX - 4).to_le_bytes());
+    bytes[p_filesz..p_filesz + 8].copy_from_slice(&8u64.to_le_bytes());
+
+    let reader = ElfReader::new(&bytes).unwrap();
+    assert!(matches!(
+        ElfBuilder::from_reader(&reader),
+        Err(ParseError::InvalidValue("p_filesz"))
+    ));
+}
+
+#[test]
+fn nobits_section() {
+    let mut builder = ElfBuilder::new(
+        ElfKind::Relocatable,
+        MachineKind::RiscV,
+        true,
+        Endianness::Little,
+    );
+
+    let name = builder.add_string(".bss");
+    builder
+        .add_section(Section {
+            vaddr: 0x20000,
+            alignment: 8,
+            size: Some(0x10000),
+            ..new_section(
+                name,
+                SectionKind::Nobits,
+                SectionFlag::Alloc | SectionFlag::Write,
+                &[],
+            )
+        })
+        .unwrap();
+
+    let name = builder.add_string(".data");
+    builder
+        .add_section(Section {
+            vaddr: 0x30000,
+            alignment: 0,
+            ..new_section(
+                name,
+                SectionKind::Progbits,
+                SectionFlag::Alloc | SectionFlag::Write,
+                &[1, 2, 3, 4],
+            )
+        })
+        .unwrap();
+
+    let name = builder.add_string(".text");
+    assert_eq!(
+        builder.add_section(Section {
+            vaddr: 0x10000,
+            alignment: 4,
+            size: Some(8),
+            ..new_section(
+                name,
+                SectionKind::Progbits,
+                SectionFlag::Alloc | SectionFlag::ExecInstr,
+                &[0x13, 0, 0, 0]
+            )
+        }),
+        Err(BuildError::InvalidValue("sh_size"))
+    );
+
+    let bytes = builder.build_to_vec().unwrap();
+    assert!(bytes.len() < 0x10000);
+
+    let reader = ElfReader::new(&bytes).unwrap();
+    let sections = reader.sections().unwrap();
+    let bss = sections.get(1).unwrap();
+    let data = sections.get(2).unwrap();
+
+    assert_eq!(bss.kind(), ElfValue::Known(SectionKind::Nobits));
+    assert_eq!(bss.size(), 0x10000);
+    assert_eq!(data.offset(), bss.offset());
+    assert_eq!(data.data().unwrap(), &[1, 2, 3, 4]);
+}
+
+#[test]
+fn nobits_section_data() {
+    for is_64bit in [false, true] {
+        let mut builder = ElfBuilder::new(
+            ElfKind::Relocatable,
+            MachineKind::RiscV,
+            is_64bit,
+            Endianness::Little,
+        );
+
+        // the data only sets the size, it isn't written to the file
+        for (name, kind, data) in [
+            (".bss", SectionKind::Nobits, &[0xaa; 0x20][..]),
+            (".data", SectionKind::Progbits, &[1, 2, 3, 4][..]),
+        ] {
+            let name = builder.add_string(name);
+            builder
+                .add_section(Section {
+                    alignment: 0,
+                    ..new_section(name, kind, SectionFlag::Alloc | SectionFlag::Write, data)
+                })
+                .unwrap();
+        }
+
+        let layout = builder.layout().unwrap();
+        let bytes = builder.build_to_vec().unwrap();
+        assert!(!bytes.contains(&0xaa));
+
+        let reader = ElfReader::new(&bytes).unwrap();
+        let sections = reader.sections().unwrap();
+        let bss = sections.get(1).unwrap();
+        let data = sections.get(2).unwrap();
+
+        assert_eq!(bss.size(), 0x20);
+        assert_eq!(bss.offset(), layout.sections[1]);
+        assert_eq!(data.offset(), bss.offset());
+        assert_eq!(data.data().unwrap(), &[1, 2, 3, 4]);
+    }
+}
+
+#[test]
+fn section_link() {
+    let mut builder = ElfBuilder::new(
+        ElfKind::Dynamic,
+        MachineKind::X86_64,
+        true,
+        Endianness::Little,
+    );
+
+    let name = builder.add_string(".data");
+    let data = builder
+        .add_section(Section {
+            vaddr: 0x1000,
+            alignment: 0,
+            ..new_section(
+                name,
+                SectionKind::Progbits,
+                SectionFlag::Alloc | SectionFlag::Write,
+                &[1, 2, 3, 4],
+            )
+        })
+        .unwrap();
+
+    let name = builder.add_string(".hash");
+    let link = Some(builder.dynamic_symbol_table());
+    builder
+        .add_section(Section {
+            vaddr: 0x2000,
+            entsize: 4,
+            alignment: 4,
+            link,
+            ..new_section(name, SectionKind::Hash, SectionFlag::Alloc.into(), &[0; 8])
+        })
+        .unwrap();
+
+    let name = builder.add_string(".note.data");
+    builder
+        .add_section(Section {
+            alignment: 0,
+            link: Some(data),
+            ..new_section(name, SectionKind::Progbits, FlagSet::default(), &[])
+        })
+        .unwrap();
+
+    builder.allow_unusual();
+    let bytes = builder.build_to_vec().unwrap();
+
+    let reader = ElfReader::new(&bytes).unwrap();
+    let sections = reader.sections().unwrap();
+    let dynsym = sections
+        .clone()
+        .into_iter()
+        .position(|section| section.kind() == ElfValue::Known(SectionKind::DynSym))
+        .unwrap();
+
+    assert_eq!(sections.get(1).unwrap().link(), 0);
+    assert_eq!(sections.get(2).unwrap().link(), dynsym as u32);
+    assert_eq!(sections.get(3).unwrap().link(), 1);
+}
+
+#[test]
+fn multi_section_segment() {
+    for is_64bit in [true, false] {
+        let mut builder = ElfBuilder::new(
+            ElfKind::Executable,
+            MachineKind::RiscV,
+            is_64bit,
+            Endianness::Little,
+        );
+
+        let name = builder.add_string(".text");
+        let text = builder
+            .add_section(Section {
+                vaddr: 0x10000,
+                alignment: 4,
+                ..new_section(
+                    name,
+                    SectionKind::Progbits,
+                    SectionFlag::Alloc | SectionFlag::ExecInstr,
+                    &[0x13, 0, 0, 0, 0x13, 0, 0, 0],
+                )
+            })
+            .unwrap();
+
+        let name = builder.add_string(".rodata");
+        let rodata = builder
+            .add_section(Section {
+                vaddr: 0x10010,
+                alignment: 16,
+                ..new_section(
+                    name,
+                    SectionKind::Progbits,
+                    SectionFlag::Alloc.into(),
+                    b"hello",
+                )
+            })
+            .unwrap();
+
+        let load = Segment {
+            section: text,
+            kind: SegmentKind::Load,
+            vaddr: 0x10000,
+            paddr: 0x10000,
+            filesz: 0,
+            memsz: 0x15,
+            flags: SegmentFlag::Read | SegmentFlag::Execute,
+            align: 0x1000,
+            last_section: Some(rodata),
+        };
+        builder.add_segment(load.clone()).unwrap();
+
+        let bytes = builder.clone().build_to_vec().unwrap();
+
+        let reader = ElfReader::new(&bytes).unwrap();
+        let sections = reader.sections().unwrap();
+        let text_offset = sections.get(1).unwrap().offset();
+        let rodata_offset = sections.get(2).unwrap().offset();
+        let segment = reader.segments().unwrap().get(0).unwrap();
+
+        assert_eq!(segment.offset(), text_offset);
+        assert_eq!(segment.filesz(), rodata_offset + 5 - text_offset);
+        assert!(segment.data().unwrap().starts_with(&[0x13, 0, 0, 0]));
+        assert!(segment.data().unwrap().ends_with(b"hello"));
+
+        // the segment must be large enough in memory to contain the sections
+        builder
+            .add_segment(Segment {
+                memsz: 0x10,
+                ..load
+            })
+            .unwrap();
+        assert!(builder.build(&mut Vec::new()).is_err());
+    }
+}
+
+#[test]
+fn section_group() {
+    let mut builder = ElfBuilder::new(
+        ElfKind::Relocatable,
+        MachineKind::X86_64,
+        true,
+        Endianness::Little,
+    );
+
+    let name = builder.add_string(".text.foo");
+    let text = builder
+        .add_section(new_section(
+            name,
+            SectionKind::Progbits,
+            SectionFlag::Alloc | SectionFlag::ExecInstr | SectionFlag::Group,
+            &[0xc3],
+        ))
+        .unwrap();
+
+    // the global signature symbol is moved after the local symbol when building
+    let signature = builder
+        .add_symbol("foo", 0, 1, SymbolBinding::Global, SymbolKind::Func, text)
+        .unwrap();
+    builder
+        .add_symbol("bar", 0, 0, SymbolBinding::Local, SymbolKind::NoType, text)
+        .unwrap();
+
+    let mut data = Vec::new();
+    data.extend_from_slice(&1u32.to_le_bytes()); // GRP_COMDAT
+    data.extend_from_slice(&1u32.to_le_bytes()); // .text.foo
+
+    let name = builder.add_string(".group");
+    let link = Some(builder.symbol_table());
+    builder
+        .add_section(Section {
+            data: Cow::Owned(data),
+            info: u64::from(signature).try_into().unwrap(),
+            entsize: 4,
+            alignment: 4,
+            link,
+            ..new_section(name, SectionKind::Group, FlagSet::default(), &[])
+        })
+        .unwrap();
+
+    let bytes = builder.build_to_vec().unwrap();
+
+    let reader = ElfReader::new(&bytes).unwrap();
+    let sections = reader.sections().unwrap();
+    let group = sections.get(2).unwrap();
+
+    let symbol_table = sections
+        .get(usize::try_from(group.link()).unwrap())
+        .unwrap();
+    assert_eq!(
+        symbol_table.kind(),
+        ElfValue::Known(SectionKind::SymbolTable)
+    );
+
+    let symbols = symbol_table.symbols().unwrap();
+    let strings = symbols.strings().unwrap();
+    let symbol = symbols.get(usize::try_from(group.info()).unwrap()).unwrap();
+    assert_eq!(strings.get_str(symbol.name().into()), Some(Ok("foo")));
+
+    assert_eq!(
+        group.group().unwrap().flags(),
+        ElfValue::Known(GroupFlag::Comdat.into())
+    );
+    assert_eq!(group.group_members().unwrap().collect::<Vec<_>>(), [1]);
+}
+
+#[test]
+fn section_accessors() {
+    let mut builder = ElfBuilder::new(
+        ElfKind::Executable,
+        MachineKind::RiscV,
+        false,
+        Endianness::Little,
+    );
+
+    let name = builder.add_string(".bss");
+    let bss = Section {
+        vaddr: 0x1000,
+        alignment: 8,
+        size: Some(0x100),
+        ..new_section(
+            name,
+            SectionKind::Nobits,
+            SectionFlag::Alloc | SectionFlag::Write,
+            &[],
+        )
+    };
+    let id = builder.add_section(bss.clone()).unwrap();
+
+    let segment = Segment {
+        section: id,
+        kind: SegmentKind::Load,
+        vaddr: 0x1000,
+        paddr: 0x1000,
+        filesz: 0,
+        memsz: 0x100,
+        flags: SegmentFlag::Read | SegmentFlag::Write,
+        align: 0x1000,
+        last_section: None,
+    };
+    builder.add_segment(segment.clone()).unwrap();
+
+    assert_eq!(builder.section(id), Some(&bss));
+    assert_eq!(builder.section(id).unwrap().size(), 0x100);
+    assert_eq!(builder.section(builder.null_section()).unwrap().size(), 0);
+    assert_eq!(builder.section(builder.string_table()), None);
+    assert_eq!(builder.segments(), [segment]);
+}
+
+#[test]
+fn segment_on_symbol_table_32bit() {
+    let mut builder = ElfBuilder::new(
+        ElfKind::Executable,
+        MachineKind::Ppc,
+        false,
+        Endianness::Big,
+    );
+
+    let name = builder.add_string(".text");
+    let text = builder
+        .add_section(Section {
+            vaddr: 0x10000,
+            alignment: 4,
+            ..new_section(
+                name,
+                SectionKind::Progbits,
+                SectionFlag::Alloc | SectionFlag::ExecInstr,
+                &[0x4e, 0x80, 0x00, 0x20],
+            )
+        })
+        .unwrap();
+
+    builder
+        .add_symbol(
+            "_start",
+            0x10000,
+            4,
+            SymbolBinding::Global,
+            SymbolKind::Func,
+            text,
+        )
+        .unwrap();
+
+    for (section, vaddr, size) in [
+        (builder.symbol_table(), 0x20000, 32), // the null symbol and _start
+        (builder.string_table(), 0x30000, 0),
+    ] {
+        builder
+            .add_segment(Segment {
+                section,
+                kind: SegmentKind::Load,
+                vaddr,
+                paddr: vaddr,
+                filesz: size,
+                memsz: size,
+                flags: SegmentFlag::Read.into(),
+                align: 4,
+                last_section: None,
+            })
+            .unwrap();
+    }
+
+    let bytes = builder.build_to_vec().unwrap();
+
+    let reader = ElfReader::new(&bytes).unwrap();
+    let sections = reader.sections().unwrap();
+    let segments = reader.segments().unwrap();
+
+    let symbol_table = sections.first_of_kind(SectionKind::SymbolTable).unwrap();
+    assert_eq!(segments.get(0).unwrap().offset(), symbol_table.offset());
+    assert_eq!(segments.get(0).unwrap().filesz(), symbol_table.size());
+
+    let string_table = sections
+        .get(usize::try_from(symbol_table.link()).unwrap())
+        .unwrap();
+    assert_eq!(segments.get(1).unwrap().offset(), string_table.offset());
+}
+
+#[test]
+fn load_segment_alignment() {
+    let mut builder = ElfBuilder::new(
+        ElfKind::Executable,
+        MachineKind::X86_64,
+        true,
+        Endianness::Little,
+    );
+
+    let name = builder.add_string(".text");
+    let text = builder
+        .add_section(Section {
+            vaddr: 0x401230,
+            alignment: 16,
+            ..new_section(
+                name,
+                SectionKind::Progbits,
+                SectionFlag::Alloc | SectionFlag::ExecInstr,
+                &[0xc3],
+            )
+        })
+        .unwrap();
+
+    let segment = Segment {
+        section: text,
+        kind: SegmentKind::Load,
+        vaddr: 0x401230,
+        paddr: 0x401230,
+        filesz: 1,
+        memsz: 1,
+        flags: SegmentFlag::Read | SegmentFlag::Execute,
+        align: 0x1000,
+        last_section: None,
+    };
+    builder.add_segment(segment.clone()).unwrap();
+
+    let bytes = builder.clone().build_to_vec().unwrap();
+    let reader = ElfReader::new(&bytes).unwrap();
+    let loaded = reader.segments().unwrap().get(0).unwrap();
+
+    assert_eq!(loaded.offset() % 0x1000, loaded.vaddr() % 0x1000);
+    assert_eq!(loaded.offset(), 0x230);
+    assert_eq!(loaded.data().unwrap(), &[0xc3]);
+
+    // a second segment at the same section can't be satisfied as well
+    builder
+        .add_segment(Segment {
+            vaddr: 0x401238,
+            align: 0x10,
+            ..segment
+        })
+        .unwrap();
+    assert!(builder.build_to_vec().is_err());
+}
+
+#[test]
+fn note_sections() {
+    for endianness in [Endianness::Little, Endianness::Big] {
+        let mut builder =
+            ElfBuilder::new(ElfKind::Relocatable, MachineKind::X86_64, true, endianness);
+        builder
+            .add_note_section(".note.test", "test", 7, &[1, 2, 3, 4, 5])
+            .unwrap();
+        builder.add_build_id(&[0xab; 20]).unwrap();
+
+        let bytes = builder.build_to_vec().unwrap();
+        let reader = ElfReader::new(&bytes).unwrap();
+        assert_eq!(reader.build_id().unwrap(), Some(&[0xab; 20][..]));
+
+        let section = reader.sections().unwrap().get(1).unwrap();
+        assert_eq!(section.kind(), ElfValue::Known(SectionKind::Note));
+        // header, "test\0" padded to 8 bytes, and the descriptor padded to 8 bytes
+        assert_eq!(section.size(), 12 + 8 + 8);
+
+        let notes = section.notes().unwrap().into_iter().collect::<Vec<_>>();
+        assert_eq!(notes.len(), 1);
+        assert_eq!(notes[0].name(), b"test");
+        assert_eq!(notes[0].kind(), 7);
+        assert_eq!(notes[0].desc(), &[1, 2, 3, 4, 5]);
+    }
+
+    let builder = ElfBuilder::new(
+        ElfKind::Relocatable,
+        MachineKind::X86_64,
+        true,
+        Endianness::Little,
+    );
+    let bytes = builder.build_to_vec().unwrap();
+    assert_eq!(ElfReader::new(&bytes).unwrap().build_id().unwrap(), None);
+}
+
+#[test]
+fn interpreter() {
+    let mut builder = ElfBuilder::new(
+        ElfKind::Dynamic,
+        MachineKind::X86_64,
+        true,
+        Endianness::Little,
+    );
+
+    let path = "/lib64/ld-linux-x86-64.so.2";
+    let interp = builder.set_interpreter(path, 0x400238).unwrap();
+    assert_eq!(
+        builder.set_interpreter(path, 0x400238),
+        Err(BuildError::InvalidValue("p_type"))
+    );
+
+    builder
+        .add_segment(Segment {
+            section: interp,
+            kind: SegmentKind::Load,
+            vaddr: 0x400000,
+            paddr: 0x400000,
+            filesz: 0x1000,
+            memsz: 0x1000,
+            flags: SegmentFlag::Read.into(),
+            align: 0x1000,
+            last_section: None,
+        })
+        .unwrap();
+
+    let bytes = builder.build_to_vec().unwrap();
+    let reader = ElfReader::new(&bytes).unwrap();
+    assert_eq!(
+        reader.interpreter().unwrap().unwrap().to_str().unwrap(),
+        path
+    );
+
+    let section = reader.sections().unwrap().get(1).unwrap();
+    assert!(section.is_alloc());
+    assert_eq!(section.addr(), 0x400238);
+
+    // the interpreter comes first even though the loadable segment has a lower address
+    let segment = reader.segments().unwrap().get(0).unwrap();
+    assert_eq!(segment.kind(), ElfValue::Known(SegmentKind::Interp));
+    assert_eq!(segment.offset(), section.offset());
+    assert_eq!(segment.filesz(), section.size());
+    assert_eq!(segment.filesz(), u64::try_from(path.len()).unwrap() + 1);
+}
+
+#[test]
+fn tls_segment() {
+    let mut builder = ElfBuilder::new(
+        ElfKind::Executable,
+        MachineKind::X86_64,
+        true,
+        Endianness::Little,
+    );
+
+    let flags = SectionFlag::Alloc | SectionFlag::Write | SectionFlag::Tls;
+    let name = builder.add_string(".tdata");
+    let tdata = builder
+        .add_section(Section {
+            vaddr: 0x402000,
+            alignment: 8,
+            ..new_section(name, SectionKind::Progbits, flags, &[1; 8])
+        })
+        .unwrap();
+    let name = builder.add_string(".tbss");
+    let tbss = builder
+        .add_section(Section {
+            vaddr: 0x402008,
+            alignment: 8,
+            size: Some(0x18),
+            ..new_section(name, SectionKind::Nobits, flags, &[])
+        })
+        .unwrap();
+
+    builder
+        .add_segment(Segment {
+            section: tdata,
+            kind: SegmentKind::Load,
+            vaddr: 0x402000,
+            paddr: 0x402000,
+            filesz: 0,
+            memsz: 0x20,
+            flags: SegmentFlag::Read | SegmentFlag::Write,
+            align: 0x1000,
+            last_section: Some(tbss),
+        })
+        .unwrap();
+
+    assert_eq!(
+        builder.add_tls_segment(builder.null_section(), 0, 8),
+        Err(BuildError::InvalidValue("p_offset"))
+    );
+    assert_eq!(
+        builder.add_tls_segment(tdata, u64::MAX, 8),
+        Err(BuildError::ValueTooLarge("p_memsz"))
+    );
+
+    // without initialized data, the template is only .tbss
+    let mut only_tbss = builder.clone();
+    only_tbss.add_tls_segment(tbss, 0, 8).unwrap();
+    let segment = only_tbss.segments().last().unwrap();
+    assert_eq!((segment.filesz, segment.memsz), (0, 0x18));
+
+    builder.add_tls_segment(tdata, 0x18, 8).unwrap();
+
+    let bytes = builder.build_to_vec().unwrap();
+    let reader = ElfReader::new(&bytes).unwrap();
+    let segments = reader.segments().unwrap();
+    assert_eq!(segments.check(), Ok(()));
+
+    let tdata = reader.sections().unwrap().get(1).unwrap();
+    let segment = segments.of_kind(SegmentKind::Tls).next().unwrap();
+    assert_eq!(segment.offset(), tdata.offset());
+    assert_eq!(segment.vaddr(), 0x402000);
+    assert_eq!(segment.filesz(), 8);
+    assert_eq!(segment.memsz(), 0x20);
+    assert_eq!(segment.align(), 8);
+    assert_eq!(segment.flags(), ElfValue::Known(SegmentFlag::Read.into()));
+    assert_eq!(segment.data().unwrap(), &[1; 8]);
+}
+
+#[test]
+fn phdr_segment() {
+    for is_64bit in [false, true] {
+        let mut builder = ElfBuilder::new(
+            ElfKind::Executable,
+            MachineKind::X86_64,
+            is_64bit,
+            Endianness::Little,
+        );
+
+        let name = builder.add_string(".text");
+        let text = builder
+            .add_section(Section {
+                vaddr: 0x401000,
+                alignment: 16,
+                ..new_section(
+                    name,
+                    SectionKind::Progbits,
+                    SectionFlag::Alloc | SectionFlag::ExecInstr,
+                    &[0xc3],
+                )
+            })
+            .unwrap();
+        builder
+            .add_segment(Segment {
+                section: text,
+                kind: SegmentKind::Load,
+                vaddr: 0x401000,
+                paddr: 0x401000,
+                filesz: 1,
+                memsz: 1,
+                flags: SegmentFlag::Read | SegmentFlag::Execute,
+                align: 0x1000,
+                last_section: None,
+            })
+            .unwrap();
+        builder
+            .emit_phdr_segment(SegmentFlag::Read, 0x400040)
+            .unwrap();
+
+        let bytes = builder.build_to_vec().unwrap();
+        let reader = ElfReader::new(&bytes).unwrap();
+        let header = reader.header().unwrap();
+        assert_eq!(header.phnum(), 2);
+
+        let segments = reader.segments().unwrap();
+        let phdr = segments.get(0).unwrap();
+        assert_eq!(phdr.kind(), ElfValue::Known(SegmentKind::Phdr));
+        assert_eq!(phdr.flags(), ElfValue::Known(SegmentFlag::Read.into()));
+        assert_eq!(phdr.offset(), header.phoff());
+        assert_eq!(phdr.vaddr(), 0x400040);
+        assert_eq!(phdr.filesz(), 2 * u64::from(header.phentsize()));
+        assert_eq!(phdr.memsz(), phdr.filesz());
+
+        // the section data starts after both program headers
+        let load = segments.get(1).unwrap();
+        assert_eq!(load.kind(), ElfValue::Known(SegmentKind::Load));
+        assert_eq!(load.data().unwrap(), &[0xc3]);
+    }
+}
+
+#[test]
+fn tail_merge_strings() {
+    let section_names = ["table", ".text", "stringtable", ".rela.text", "text"];
+    let symbol_names = ["ble", "stringtable", "unrelated"];
+
+    let build = |merge: bool| {
+        let mut builder = ElfBuilder::new(
+            ElfKind::Relocatable,
+            MachineKind::X86_64,
+            true,
+            Endianness::Little,
+        );
+        builder.set_tail_merge_strings(merge);
+
+        let mut section = builder.null_section();
+        for name in section_names {
+            let name = builder.add_string(name);
+            section = builder
+                .add_section(new_section(
+                    name,
+                    SectionKind::Progbits,
+                    SectionFlag::Alloc.into(),
+                    &[0x90],
+                ))
+                .unwrap();
+        }
+        for name in symbol_names {
+            builder
+                .add_symbol(name, 0, 1, SymbolBinding::Global, SymbolKind::Func, section)
+                .unwrap();
+        }
+
+        builder.build_to_vec().unwrap()
+    };
+
+    let strtab_size = |bytes: &[u8]| {
+        let reader = ElfReader::new(bytes).unwrap();
+        let sections = reader.sections().unwrap();
+        let names = sections
+            .with_names()
+            .map(|(_, name)| name.unwrap().to_owned())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            names,
+            [
+                "",
+                "table",
+                ".text",
+                "stringtable",
+                ".rela.text",
+                "text",
+                ".symtab",
+                ".strtab"
+            ]
+        );
+
+        let symbols = sections.get(6).unwrap().symbols().unwrap();
+        let strings = symbols.strings().unwrap();
+        let names = symbols
+            .into_iter()
+            .skip(1)
+            .map(|symbol| {
+                strings
+                    .get_str(symbol.name().into())
+                    .unwrap()
+                    .unwrap()
+                    .to_owned()
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(names, symbol_names);
+
+        sections.get(7).unwrap().size()
+    };
+
+    // "table", ".text", "text", and "ble" are suffixes of other strings
+    assert_eq!(strtab_size(&build(false)), strtab_size(&build(true)) + 21);
+}
+
+#[test]
+fn tail_merge_foreign_name() {
+    let mut builder = ElfBuilder::new(
+        ElfKind::Relocatable,
+        MachineKind::X86_64,
+        true,
+        Endianness::Little,
+    );
+    builder.set_tail_merge_strings(true);
+
+    // offset 6 of .dynstr is in the middle of ".strtab" in .strtab
+    builder.add_dynamic_string("aaaa");
+    let name = builder.add_dynamic_string("bbbb");
+    builder
+        .add_section(new_section(
+            name,
+            SectionKind::Progbits,
+            SectionFlag::Alloc.into(),
+            &[0x90],
+        ))
+        .unwrap();
+
+    let error = eelf::Error::from(builder.build_to_vec().unwrap_err());
+    assert!(matches!(
+        error,
+        eelf::Error::Build(BuildError::InvalidValue("sh_name"))
+    ));
+}
+
+#[test]
+fn strip_foreign_name() {
+    let mut builder = ElfBuilder::new(
+        ElfKind::Relocatable,
+        MachineKind::X86_64,
+        true,
+        Endianness::Little,
+    );
+    builder.set_strip(true);
+
+    // offset 6 of .dynstr isn't the start of a string in .strtab
+    builder.add_dynamic_string("aaaa");
+    let name = builder.add_dynamic_string("bbbb");
+    builder
+        .add_section(new_section(
+            name,
+            SectionKind::Progbits,
+            SectionFlag::Alloc.into(),
+            &[0x90],
+        ))
+        .unwrap();
+
+    let error = eelf::Error::from(builder.build_to_vec().unwrap_err());
+    assert!(matches!(
+        error,
+        eelf::Error::Build(BuildError::InvalidValue("sh_name"))
+    ));
+}
+
+#[test]
+fn strip() {
+    let bytes = include_bytes!("hello-world.bin");
+    let reader = ElfReader::new(bytes).unwrap();
+
+    let mut builder = ElfBuilder::from_reader(&reader).unwrap();
+    let unstripped = builder.clone().build_to_vec().unwrap();
+    let unstripped_reader = ElfReader::new(&unstripped).unwrap();
+    builder.set_strip(true);
+    let built = builder.build_to_vec().unwrap();
+    let built_reader = ElfReader::new(&built).unwrap();
+
+    let names = |reader: &ElfReader| {
+        reader
+            .sections()
+            .unwrap()
+            .with_names()
+            .filter(|(section, _)| section.is_alloc())
+            .map(|(section, name)| (name.unwrap().to_owned(), section.addr()))
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(names(&built_reader), names(&reader));
+
+    // only the allocated sections and the string table naming them remain
+    let sections = built_reader.sections().unwrap();
+    assert_eq!(sections.len(), names(&reader).len() + 2);
+    assert_eq!(built_reader.shstrndx().unwrap(), sections.len() - 1);
+    assert!(sections
+        .into_iter()
+        .all(|section| section.kind() != ElfValue::Known(SectionKind::SymbolTable)));
+
+    let loads = |reader: &ElfReader| {
+        reader
+            .segments()
+            .unwrap()
+            .into_iter()
+            .filter(|segment| segment.kind() == ElfValue::Known(SegmentKind::Load))
+            .map(|segment| (segment.vaddr(), segment.data().unwrap().to_vec()))
+            .collect::<Vec<_>>()
+    };
+    // the segments cover the same data as without stripping, even though the sections moved
+    assert_eq!(loads(&built_reader), loads(&unstripped_reader));
+}
+
+#[test]
+fn strip_remaps_sections() {
+    let mut builder = ElfBuilder::new(
+        ElfKind::Executable,
+        MachineKind::X86_64,
+        true,
+        Endianness::Little,
+    );
+    builder.set_strip(true);
+
+    let [_, linked, _] = [
+        (".debug_info", &[1, 2, 3][..]),
+        (".linked", &[4][..]),
+        (".comment", &b"GCC\0"[..]),
+    ]
+    .map(|(name, data)| {
+        let name = builder.add_string(name);
+        builder
+            .add_section(Section {
+                vaddr: 0x401000,
+                ..new_section(name, SectionKind::Progbits, FlagSet::default(), data)
+            })
+            .unwrap()
+    });
+    let name = builder.add_string(".text");
+    let text = builder
+        .add_section(Section {
+            vaddr: 0x401000,
+            link: Some(linked),
+            ..new_section(
+                name,
+                SectionKind::Progbits,
+                SectionFlag::Alloc | SectionFlag::ExecInstr,
+                &[0xc3],
+            )
+        })
+        .unwrap();
+
+    builder
+        .add_segment(Segment {
+            section: text,
+            kind: SegmentKind::Load,
+            vaddr: 0x401000,
+            paddr: 0x401000,
+            filesz: 1,
+            memsz: 1,
+            flags: SegmentFlag::Read | SegmentFlag::Execute,
+            align: 0x1000,
+            last_section: None,
+        })
+        .unwrap();
+    builder
+        .add_symbol("local", 0, 0, false, SymbolKind::NoType, text)
+        .unwrap();
+    builder
+        .add_dynamic_symbol("_start", 0x401000, 1, true, SymbolKind::Func, text)
+        .unwrap();
+
+    let bytes = builder.build_to_vec().unwrap();
+    let reader = ElfReader::new(&bytes).unwrap();
+    let sections = reader.sections().unwrap();
+    let names = sections
+        .with_names()
+        .map(|(_, name)| name.unwrap())
+        .collect::<Vec<_>>();
+    assert_eq!(
+        names,
+        ["", ".linked", ".text", ".dynsym", ".dynstr", ".strtab"]
+    );
+    assert_eq!(sections.get(2).unwrap().link(), 1);
+
+    let segment = reader.segments().unwrap().get(0).unwrap();
+    assert_eq!(segment.offset(), sections.get(2).unwrap().offset());
+    assert_eq!(segment.data().unwrap(), &[0xc3]);
+
+    let symbol = sections.get(3).unwrap().symbols().unwrap().get(1).unwrap();
+    assert_eq!(symbol.shndx(), 2);
+}
+
+#[test]
+fn section_headers_after_program_headers() {
+    let bytes = include_bytes!("hello-world.bin");
+    let reader = ElfReader::new(bytes).unwrap();
+
+    let mut builder = ElfBuilder::from_reader(&reader).unwrap();
+    let default = builder.clone().build_to_vec().unwrap();
+    let default_reader = ElfReader::new(&default).unwrap();
+    builder.set_section_header_placement(SectionHeaderPlacement::AfterProgramHeaders);
+    let built = builder.build_to_vec().unwrap();
+    let built_reader = ElfReader::new(&built).unwrap();
+
+    let header = built_reader.header().unwrap();
+    let phdrs_end = header.phoff() + 56 * u64::from(header.phnum());
+    assert_eq!(header.shoff(), phdrs_end.next_multiple_of(8));
+    assert!(default_reader.header().unwrap().shoff() > header.shoff());
+
+    let sections = built_reader.sections().unwrap();
+    let data_start = header.shoff() + 64 * sections.len() as u64;
+    assert!(sections
+        .into_iter()
+        .skip(1)
+        .all(|section| section.offset() >= data_start));
+
+    let contents = |reader: &ElfReader| {
+        reader
+            .sections()
+            .unwrap()
+            .with_names()
+            .map(|(section, name)| {
+                let data = match section.kind() {
+                    ElfValue::Known(SectionKind::Nobits) => Vec::new(),
+                    _ => section.data().unwrap().to_vec(),
+                };
+                (name.unwrap().to_owned(), section.addr(), data)
+            })
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(contents(&built_reader), contents(&default_reader));
+
+    let loads = |reader: &ElfReader| {
+        reader
+            .segments()
+            .unwrap()
+            .into_iter()
+            .filter(|segment| segment.kind() == ElfValue::Known(SegmentKind::Load))
+            .map(|segment| (segment.vaddr(), segment.data().unwrap().to_vec()))
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(loads(&built_reader), loads(&default_reader));
+}
+
+#[test]
+fn layout() {
+    let bytes = include_bytes!("hello-world.bin");
+    let reader = ElfReader::new(bytes).unwrap();
+    let mut builder = ElfBuilder::from_reader(&reader).unwrap();
+
+    for placement in [
+        SectionHeaderPlacement::End,
+        SectionHeaderPlacement::AfterProgramHeaders,
+    ] {
+        builder.set_section_header_placement(placement);
+        let layout = builder.layout().unwrap();
+        let built = builder.clone().build_to_vec().unwrap();
+        let reader = ElfReader::new(&built).unwrap();
+        let header = reader.header().unwrap();
+
+        assert_eq!(layout.program_headers, header.phoff());
+        assert_eq!(layout.section_headers, header.shoff());
+        assert_eq!(layout.size, built.len() as u64);
+        // the null section's offset is written as 0
+        let offsets = reader
+            .sections()
+            .unwrap()
+            .into_iter()
+            .skip(1)
+            .map(|section| section.offset())
+            .collect::<Vec<_>>();
+        assert_eq!(layout.sections[1..], offsets);
+    }
+
+    let mut builder = ElfBuilder::new(
+        ElfKind::Relocatable,
+        MachineKind::X86_64,
+        true,
+        Endianness::Little,
+    );
+    let layout = builder.layout().unwrap();
+    assert_eq!(layout.program_headers, 0);
+    // the null section and .strtab, which is placed right after the header
+    assert_eq!(layout.sections, [64, 64]);
+    // the string table "\0.strtab\0" ends at 73, and the section headers are aligned to 8
+    assert_eq!(layout.section_headers, 80);
+    assert_eq!(layout.size, layout.section_headers + 2 * 64);
+
+    builder.set_entrypoint(0x401000).unwrap();
+    assert_eq!(builder.layout(), Err(BuildError::InvalidValue("e_entry")));
+}
+
+#[test]
+fn compressed_section() {
+    let text = (0..200)
+        .map(|i| format!("line {i}: the quick brown fox jumps over the lazy dog\n"))
+        .collect::<String>();
+    let mut state = 1u32;
+    let noise = (0..100_000)
+        .map(|_| {
+            state = state.wrapping_mul(1_103_515_245).wrapping_add(12345);
+            // a small alphabet so that there are both literals and matches at long distances
+            b'a' + (state >> 16) as u8 % 4
+        })
+        .collect::<Vec<_>>();
+
+    for (is_64bit, endianness) in [(true, Endianness::Little), (false, Endianness::Big)] {
+        let mut builder = ElfBuilder::new(
+            ElfKind::Relocatable,
+            MachineKind::X86_64,
+            is_64bit,
+            endianness,
+        );
+
+        for (name, data) in [
+            (".debug_str", text.as_bytes()),
+            (".debug_info", &noise[..]),
+            (".debug_line", &[][..]),
+        ] {
+            let name = builder.add_string(name);
+            builder
+                .add_compressed_section(Section {
+                    entsize: 1,
+                    ..new_section(name, SectionKind::Progbits, FlagSet::default(), data)
+                })
+                .unwrap();
+        }
+        let name = builder.add_string(".text");
+        assert_eq!(
+            builder.add_compressed_section(new_section(
+                name,
+                SectionKind::Progbits,
+                SectionFlag::Alloc.into(),
+                &[0xc3],
+            )),
+            Err(BuildError::InvalidValue("sh_flags"))
+        );
+
+        let bytes = builder.build_to_vec().unwrap();
+        let reader = ElfReader::new(&bytes).unwrap();
+        let sections = reader.sections().unwrap();
+
+        for (index, data) in [(1, text.as_bytes()), (2, &noise[..]), (3, &[][..])] {
+            let section = sections.get(index).unwrap();
+            assert!(section.has_flag(SectionFlag::Compressed));
+            assert_eq!(section.addralign(), if is_64bit { 8 } else { 4 });
+
+            let header = section.compression().unwrap().unwrap();
+            assert_eq!(header.kind, ElfValue::Known(CompressionKind::Zlib));
+            assert_eq!(header.size, data.len() as u64);
+            assert_eq!(header.addralign, 1);
+
+            assert_eq!(section.decompressed_data().unwrap(), data);
+        }
+
+        assert!(sections.get(1).unwrap().size() < text.len() as u64 / 4);
+    }
+}
+
+#[test]
+fn extended_section_indices() {
+    let mut builder = ElfBuilder::new(
+        ElfKind::Relocatable,
+        MachineKind::X86_64,
+        true,
+        Endianness::Little,
+    );
+
+    let name = builder.add_string(".data");
+    let sections = (0..0xff00)
+        .map(|_| {
+            builder
+                .add_section(new_section(
+                    name,
+                    SectionKind::Progbits,
+                    SectionFlag::Alloc | SectionFlag::Write,
+                    &[],
+                ))
+                .unwrap()
+        })
+        .collect::<Vec<_>>();
+
+    builder
+        .add_symbol("first", 0, 0, true, SymbolKind::Object, sections[0])
+        .unwrap();
+    builder
+        .add_symbol("last", 0, 0, true, SymbolKind::Object, sections[0xfeff])
+        .unwrap();
+
+    let bytes = builder.build_to_vec().unwrap();
+    let reader = ElfReader::new(&bytes).unwrap();
+    let header = reader.header().unwrap();
+    let sections = reader.sections().unwrap();
+
+    // the null section, the data sections, .symtab, .symtab_shndx, and .strtab
+    assert_eq!(sections.len(), 0xff04);
+    assert_eq!(header.shnum(), 0);
+    assert_eq!(header.shstrndx(), 0xffff);
+    assert_eq!(reader.shstrndx().unwrap(), 0xff03);
+    assert_eq!(
+        reader
+            .strings()
+            .unwrap()
+            .get_str(sections.get(0xff00).unwrap().name().into()),
+        Some(Ok(".data"))
+    );
+
+    let indices = sections.get(0xff02).unwrap();
+    assert_eq!(indices.kind(), ElfValue::Known(SectionKind::SymTabShndx));
+    assert_eq!(indices.link(), 0xff01);
+
+    let symbols = sections.get(0xff01).unwrap().symbols().unwrap();
+    let first = symbols.get(1).unwrap();
+    assert_eq!(first.shndx(), 1);
+    assert_eq!(first.section_index(), Ok(1));
+    let last = symbols.get(2).unwrap();
+    assert_eq!(last.shndx(), 0xffff);
+    assert_eq!(last.section_index(), Ok(0xff00));
+    assert_eq!(
+        symbols
+            .in_section(0xff00)
+            .map(|symbol| symbol.value())
+            .collect::<Vec<_>>(),
+        [0]
+    );
+    assert_eq!(symbols.in_section(1).count(), 1);
+
+    // the indices survive importing and building again
+    let rebuilt = ElfBuilder::from_reader(&reader)
+        .unwrap()
+        .build_to_vec()
+        .unwrap();
+    let reader = ElfReader::new(&rebuilt).unwrap();
+    let sections = reader.sections().unwrap();
+    assert_eq!(sections.len(), 0xff04);
+    let symbols = sections.get(0xff01).unwrap().symbols().unwrap();
+    assert_eq!(symbols.get(2).unwrap().section_index(), Ok(0xff00));
+}
+
+#[test]
+fn section_at_index() {
+    let mut builder = ElfBuilder::new(
+        ElfKind::Relocatable,
+        MachineKind::X86_64,
+        true,
+        Endianness::Little,
+    );
+    let (kind, flags) = (
+        SectionKind::Progbits,
+        SectionFlag::Alloc | SectionFlag::ExecInstr,
+    );
+
+    let text = new_section(builder.add_string(".text"), kind, flags, &[0xc3]);
+    let text = builder.add_section_at(3, text).unwrap();
+    let init = new_section(builder.add_string(".init"), kind, flags, &[0xc3]);
+    builder.add_section_at(1, init).unwrap();
+    let fini = new_section(builder.add_string(".fini"), kind, flags, &[0xc3]);
+    builder.add_section(fini).unwrap();
+
+    for index in [0, 3] {
+        let section = new_section(builder.add_string(".other"), kind, flags, &[0xc3]);
+        assert_eq!(
+            builder.add_section_at(index, section),
+            Err(BuildError::InvalidValue("e_shnum"))
+        );
+    }
+    // the gap would have to be filled with up to billions of null sections
+    let mut large = builder.clone();
+    for index in [0xff00, u32::MAX] {
+        let section = new_section(large.add_string(".other"), kind, flags, &[0xc3]);
+        assert_eq!(
+            large.add_section_at(index, section),
+            Err(BuildError::ValueTooLarge("e_shnum"))
+        );
+    }
+    // past SHN_LORESERVE, sections can only be added at the end
+    let last = new_section(large.add_string(".last"), kind, flags, &[0xc3]);
+    large.add_section_at(0xfeff, last).unwrap();
+    let extended = new_section(large.add_string(".extended"), kind, flags, &[0xc3]);
+    large.add_section_at(0xff00, extended).unwrap();
+
+    builder
+        .add_symbol("_start", 0, 1, true, SymbolKind::Func, text)
+        .unwrap();
+
+    let bytes = builder.build_to_vec().unwrap();
+    let reader = ElfReader::new(&bytes).unwrap();
+    let sections = reader.sections().unwrap();
+    let strings = reader.strings().unwrap();
+
+    let names = sections
+        .clone()
+        .into_iter()
+        .map(|section| strings.get_str(section.name().into()).unwrap().unwrap())
+        .collect::<Vec<_>>();
+    assert_eq!(
+        names,
+        ["", ".init", "", ".text", ".fini", ".symtab", ".strtab"]
+    );
+    assert_eq!(
+        sections.get(2).unwrap().kind(),
+        ElfValue::Known(SectionKind::Null)
+    );
+
+    let symbols = sections.get(5).unwrap().symbols().unwrap();
+    assert_eq!(symbols.get(1).unwrap().shndx(), 3);
+}
+
+#[test]
+fn append_to_section() {
+    let mut builder = ElfBuilder::new(
+        ElfKind::Relocatable,
+        MachineKind::X86_64,
+        true,
+        Endianness::Little,
+    );
+    let flags = SectionFlag::Alloc | SectionFlag::ExecInstr;
+    let name = builder.add_string(".text");
+    let text = builder
+        .add_section(new_section(name, SectionKind::Progbits, flags, &[0x55]))
+        .unwrap();
+    let name = builder.add_string(".bss");
+    let bss = builder
+        .add_section(Section {
+            size: Some(16),
+            ..new_section(name, SectionKind::Nobits, flags, &[0x55])
+        })
+        .unwrap();
+
+    // push rbp; mov rbp, rsp; pop rbp; ret
+    builder
+        .append_to_section(text, &[0x48, 0x89, 0xe5])
+        .unwrap();
+    builder.append_to_section(text, &[0x5d, 0xc3]).unwrap();
+    assert_eq!(
+        *builder.section(text).unwrap().data,
+        [0x55, 0x48, 0x89, 0xe5, 0x5d, 0xc3]
+    );
+
+    for id in [bss, builder.null_section(), builder.symbol_table()] {
+        assert_eq!(
+            builder.append_to_section(id, &[0]),
+            Err(BuildError::InvalidValue("sh_type"))
+        );
+    }
+
+    let bytes = builder.build_to_vec().unwrap();
+    let reader = ElfReader::new(&bytes).unwrap();
+    let section = reader.sections().unwrap().get(1).unwrap();
+    assert_eq!(
+        section.data().unwrap(),
+        [0x55, 0x48, 0x89, 0xe5, 0x5d, 0xc3]
+    );
+}
+
+#[test]
+fn section_from_reader() {
+    use std::io::Read;
+
+    let mut builder = ElfBuilder::new(
+        ElfKind::Relocatable,
+        MachineKind::X86_64,
+        true,
+        Endianness::Little,
+    );
+    let name = builder.add_string(".payload");
+    let section = new_section(name, SectionKind::Progbits, FlagSet::default(), &[]);
+
+    let payload = (0..=255).cycle().take(10000).collect::<Vec<u8>>();
+    let reader = payload[..4000].chain(&payload[4000..]);
+    let id = builder
+        .add_section_from_reader(section.clone(), reader)
+        .unwrap();
+    assert_eq!(*builder.section(id).unwrap().data, *payload);
+
+    // invalid sections are rejected after reading
+    let error = builder
+        .add_section_from_reader(
+            Section {
+                size: Some(16),
+                ..section
+            },
+            &b"data"[..],
+        )
+        .unwrap_err();
+    assert!(matches!(
+        eelf::Error::from(error),
+        eelf::Error::Build(BuildError::InvalidValue("sh_size"))
+    ));
+
+    let bytes = builder.build_to_vec().unwrap();
+    let reader = ElfReader::new(&bytes).unwrap();
+    let sections = reader.sections().unwrap();
+    assert_eq!(sections.len(), 3);
+    assert_eq!(sections.get(1).unwrap().data().unwrap(), payload);
+}
+
+#[test]
+fn extra_section_flags() {
+    // SHF_X86_64_LARGE and SHF_EXCLUDE
+    let extra_flags = 0x1000_0000 | 0x8000_0000;
+
+    let mut builder = ElfBuilder::new(
+        ElfKind::Relocatable,
+        MachineKind::X86_64,
+        true,
+        Endianness::Little,
+    );
+    let name = builder.add_string(".lbss");
+    builder
+        .add_section(Section {
+            extra_flags,
+            alignment: 8,
+            size: Some(16),
+            ..new_section(
+                name,
+                SectionKind::Nobits,
+                SectionFlag::Alloc | SectionFlag::Write,
+                &[],
+            )
+        })
+        .unwrap();
+
+    let bytes = builder.build_to_vec().unwrap();
+    let reader = ElfReader::new(&bytes).unwrap();
+    let section = reader.sections().unwrap().get(1).unwrap();
+    assert_eq!(section.flags(), ElfValue::Unknown(0x9000_0003));
+    assert!(section.has_flag(SectionFlag::Alloc));
+    assert_eq!(
+        section.known_flags(),
+        SectionFlag::Alloc | SectionFlag::Write
+    );
+
+    // the flags are kept when importing the file
+    let builder = ElfBuilder::from_reader(&reader).unwrap();
+    let rebuilt = builder.build_to_vec().unwrap();
+    let reader = ElfReader::new(&rebuilt).unwrap();
+    let section = reader.sections().unwrap().get(1).unwrap();
+    assert_eq!(section.flags(), ElfValue::Unknown(0x9000_0003));
+
+    let mut builder = ElfBuilder::new(
+        ElfKind::Relocatable,
+        MachineKind::Ia386,
+        false,
+        Endianness::Little,
+    );
+    let name = builder.add_string(".text");
+    assert_eq!(
+        builder.add_section(Section {
+            extra_flags: 1 << 32,
+            ..new_section(
+                name,
+                SectionKind::Progbits,
+                SectionFlag::Alloc | SectionFlag::ExecInstr,
+                &[0xc3],
+            )
+        }),
+        Err(BuildError::ValueTooLarge("sh_flags"))
+    );
+}
+
+#[test]
+fn unified_error() {
+    fn rebuild(bytes: &[u8]) -> Result<Vec<u8>, eelf::Error> {
+        let reader = ElfReader::new(bytes)?;
+        let mut built = Vec::new();
+        ElfBuilder::from_reader(&reader)?.build(&mut built)?;
+        Ok(built)
+    }
+
+    fn build_stripped() -> Result<Vec<u8>, eelf::Error> {
+        let mut builder = ElfBuilder::new(
+            ElfKind::Relocatable,
+            MachineKind::X86_64,
+            true,
+            Endianness::Little,
+        );
+        builder.set_strip(true);
+
+        // kept when stripping, but linked to the symbol table which is removed
+        let name = builder.add_string(".rela.dyn");
+        builder.add_section(Section {
+            entsize: 24,
+            alignment: 8,
+            ..new_section(name, SectionKind::Rela, SectionFlag::Alloc.into(), &[])
+        })?;
+
+        let mut built = Vec::new();
+        builder.build(&mut built)?;
+        Ok(built)
+    }
+
+    let bytes = include_bytes!("nonsense.bin");
+    assert!(rebuild(bytes).is_ok());
+    assert!(matches!(
+        rebuild(&bytes[..4]),
+        Err(eelf::Error::Parse(ParseError::UnexpectedEof { .. }))
+    ));
+    // the build error is unwrapped from the I/O error returned by `build`
+    assert!(matches!(
+        build_stripped(),
+        Err(eelf::Error::Build(BuildError::InvalidValue("sh_link")))
+    ));
 }
@@ -1,7 +1,16 @@
+use std::borrow::Cow;
+
 use eelf::{
-    flagset::FlagSet, reader::ElfValue, ElfKind, ElfReader, Endianness, MachineKind, OsAbi,
-    SectionFlag, SectionKind, SegmentFlag, SegmentKind,
+    builder::{RelEntry, RelaEntry, RelocationTable, Section, Segment},
+    flagset::FlagSet,
+    machine_name,
+    reader::{CompressionHeader, ElfValue, Registers, TypedSection},
+    segment_flags_str, ArmSectionKind, AuxType, CompressionKind, CoreNoteKind, DynTag, ElfBuilder,
+    ElfKind, ElfReader, Endianness, MachineKind, MipsSectionKind, OsAbi, ParseError,
+    ProcessorSectionKind, RelocationKind, RiscvReloc, SectionFlag, SectionKind, SegmentFlag,
+    SegmentKind, SymbolBinding, SymbolKind, X86_64SectionKind,
 };
+use num_traits::{FromPrimitive, ToPrimitive};
 
 #[test]
 fn hello_world() {
@@ -288,3 +297,2701 @@ fn hello_world() {
         assert_eq!(segment.align(), expected_segments[i].7);
     }
 }
+
+#[test]
+fn relocations() {
+    for is_64bit in [true, false] {
+        let mut builder = ElfBuilder::new(
+            ElfKind::Relocatable,
+            MachineKind::RiscV,
+            is_64bit,
+            Endianness::Little,
+        );
+
+        let name = builder.add_string(".text");
+        let text = builder
+            .add_section(Section {
+                data: Cow::Borrowed(&[0; 16]),
+                name,
+                kind: SectionKind::Progbits,
+                flags: SectionFlag::Alloc | SectionFlag::ExecInstr,
+                extra_flags: 0,
+                vaddr: 0,
+                info: 0,
+                entsize: 0,
+                alignment: 4,
+                size: None,
+                link: None,
+            })
+            .unwrap();
+        let symbol = u64::from(
+            builder
+                .add_symbol("f", 0, 16, SymbolBinding::Global, SymbolKind::Func, text)
+                .unwrap(),
+        );
+        let shift = if is_64bit { 32 } else { 8 };
+
+        let mut rela_table = builder.create_rela_table(".rela.text", text);
+        rela_table.add(RelaEntry {
+            offset: 4,
+            info: (symbol << shift) | 18,
+            addend: if is_64bit {
+                -8i64 as u64
+            } else {
+                u64::from(-8i32 as u32)
+            },
+        });
+        builder.add_relocation_table(RelocationTable::Rela(rela_table));
+
+        let mut rel_table = builder.create_rel_table(".rel.text", text);
+        rel_table.add(RelEntry {
+            offset: 8,
+            info: (symbol << shift) | 0x7f,
+        });
+        builder.add_relocation_table(RelocationTable::Rel(rel_table));
+
+        let bytes = builder.build_to_vec().unwrap();
+
+        let reader = ElfReader::new(&bytes).unwrap();
+        let sections = reader.sections().unwrap();
+
+        let rela = sections
+            .clone()
+            .into_iter()
+            .find(|section| section.kind() == ElfValue::Known(SectionKind::Rela))
+            .unwrap()
+            .relocations()
+            .unwrap();
+        assert!(rela.is_rela());
+        let relocation = rela.get(0).unwrap();
+        assert!(rela.get(1).is_none());
+        assert_eq!(relocation.offset(), 4);
+        assert_eq!(relocation.symbol(), 1);
+        assert_eq!(relocation.kind(), 18);
+        assert_eq!(relocation.addend(), Some(-8));
+        assert_eq!(
+            relocation.reloc_type_for(MachineKind::RiscV),
+            ElfValue::Known(RelocationKind::RiscV(RiscvReloc::Call))
+        );
+        assert_eq!(
+            relocation.reloc_type_for(MachineKind::Sparc),
+            ElfValue::Unknown(18)
+        );
+
+        let rel = sections
+            .clone()
+            .into_iter()
+            .find(|section| section.kind() == ElfValue::Known(SectionKind::Rel))
+            .unwrap()
+            .relocations()
+            .unwrap();
+        let relocation = rel.into_iter().next().unwrap();
+        assert_eq!(relocation.offset(), 8);
+        assert_eq!(relocation.addend(), None);
+        assert_eq!(
+            relocation.reloc_type_for(MachineKind::RiscV),
+            ElfValue::Unknown(0x7f)
+        );
+
+        assert!(sections.get(0).unwrap().relocations().is_err());
+    }
+}
+
+#[test]
+fn typed_sections() {
+    let mut builder = ElfBuilder::new(
+        ElfKind::Dynamic,
+        MachineKind::X86_64,
+        true,
+        Endianness::Little,
+    );
+
+    let name = builder.add_string(".note.test");
+    let mut note = Vec::new();
+    note.extend_from_slice(&4u32.to_le_bytes());
+    note.extend_from_slice(&3u32.to_le_bytes());
+    note.extend_from_slice(&1u32.to_le_bytes());
+    note.extend_from_slice(b"GNU\0");
+    note.extend_from_slice(&[1, 2, 3, 0]);
+    builder
+        .add_section(Section {
+            data: Cow::Owned(note),
+            name,
+            kind: SectionKind::Note,
+            flags: FlagSet::from(SectionFlag::Alloc),
+            extra_flags: 0,
+            vaddr: 0,
+            info: 0,
+            entsize: 0,
+            alignment: 4,
+            size: None,
+            link: None,
+        })
+        .unwrap();
+
+    let name = builder.add_string(".dynamic");
+    let mut dynamic = Vec::new();
+    for (tag, value) in [(1u64, 7u64), (14, 3), (0, 0), (1, 9)] {
+        dynamic.extend_from_slice(&tag.to_le_bytes());
+        dynamic.extend_from_slice(&value.to_le_bytes());
+    }
+    builder
+        .add_section(Section {
+            data: Cow::Owned(dynamic),
+            name,
+            kind: SectionKind::Dynamic,
+            flags: SectionFlag::Alloc | SectionFlag::Write,
+            extra_flags: 0,
+            vaddr: 0,
+            info: 0,
+            entsize: 16,
+            alignment: 8,
+            size: None,
+            link: None,
+        })
+        .unwrap();
+
+    let name = builder.add_string(".data");
+    let data = builder
+        .add_section(Section {
+            data: Cow::Borrowed(&[0xaa; 8]),
+            name,
+            kind: SectionKind::Progbits,
+            flags: SectionFlag::Alloc | SectionFlag::Write,
+            extra_flags: 0,
+            vaddr: 0x1000,
+            info: 0,
+            entsize: 0,
+            alignment: 8,
+            size: None,
+            link: None,
+        })
+        .unwrap();
+    builder
+        .add_symbol(
+            "value",
+            0x1000,
+            8,
+            SymbolBinding::Weak,
+            SymbolKind::Object,
+            data,
+        )
+        .unwrap();
+
+    builder.allow_unusual();
+    let bytes = builder.build_to_vec().unwrap();
+
+    let reader = ElfReader::new(&bytes).unwrap();
+    let strings = reader.strings().unwrap();
+    let mut seen = 0;
+
+    for section in reader.sections().unwrap() {
+        let name = strings.get_str(section.name().into()).unwrap().unwrap();
+
+        match (name, section.typed().unwrap()) {
+            ("", TypedSection::Raw(data)) => assert!(data.is_empty()),
+            (".note.test", TypedSection::Notes(notes)) => {
+                let notes = notes.into_iter().collect::<Vec<_>>();
+                assert_eq!(notes.len(), 1);
+                assert_eq!(notes[0].name(), b"GNU");
+                assert_eq!(notes[0].kind(), 1);
+                assert_eq!(notes[0].desc(), &[1, 2, 3]);
+            }
+            (".dynamic", TypedSection::Dynamic(dynamic)) => {
+                let entries = dynamic
+                    .into_iter()
+                    .map(|entry| (entry.tag(), entry.value()))
+                    .collect::<Vec<_>>();
+                assert_eq!(
+                    entries,
+                    [
+                        (ElfValue::Known(DynTag::Needed), 7),
+                        (ElfValue::Known(DynTag::SoName), 3)
+                    ]
+                );
+            }
+            (".data", TypedSection::Raw(data)) => assert_eq!(data, &[0xaa; 8]),
+            (".symtab", TypedSection::Symbols(symbols)) => {
+                let symbol_strings = symbols.strings().unwrap();
+                let symbol = symbols.get(1).unwrap();
+                assert!(symbols.get(2).is_none());
+                assert_eq!(
+                    symbol_strings.get_str(symbol.name().into()),
+                    Some(Ok("value"))
+                );
+                assert_eq!(symbol.value(), 0x1000);
+                assert_eq!(symbol.size(), 8);
+                assert_eq!(symbol.kind(), ElfValue::Known(SymbolKind::Object));
+                assert_eq!(symbol.binding(), ElfValue::Known(SymbolBinding::Weak));
+                assert_eq!(symbol.shndx(), 3);
+            }
+            (".strtab", TypedSection::Strings(strings)) => {
+                assert_eq!(strings.get_str(1), Some(Ok(".note.test")));
+            }
+            (name, _) => panic!("unexpected view for section {name}"),
+        }
+
+        seen += 1;
+    }
+
+    assert_eq!(seen, 6);
+}
+
+#[test]
+fn relr() {
+    let build = |is_64bit: bool, entries: &[u64]| {
+        let mut builder = ElfBuilder::new(
+            ElfKind::Dynamic,
+            MachineKind::X86_64,
+            is_64bit,
+            Endianness::Little,
+        );
+
+        let data = entries
+            .iter()
+            .flat_map(|&entry| match is_64bit {
+                true => entry.to_le_bytes().to_vec(),
+                false => (entry as u32).to_le_bytes().to_vec(),
+            })
+            .collect::<Vec<_>>();
+
+        let name = builder.add_string(".relr.dyn");
+        builder
+            .add_section(Section {
+                data: Cow::Owned(data),
+                name,
+                kind: SectionKind::Relr,
+                flags: SectionFlag::Alloc.into(),
+                extra_flags: 0,
+                vaddr: 0,
+                info: 0,
+                entsize: if is_64bit { 8 } else { 4 },
+                alignment: 8,
+                size: None,
+                link: None,
+            })
+            .unwrap();
+
+        builder.allow_unusual();
+        builder.build_to_vec().unwrap()
+    };
+    let addresses = |bytes: &[u8]| {
+        let reader = ElfReader::new(bytes).unwrap();
+        let section = reader.sections().unwrap().get(1).unwrap();
+        assert!(matches!(section.typed(), Ok(TypedSection::Relr(_))));
+
+        section.relr().unwrap().into_iter().collect::<Vec<_>>()
+    };
+
+    // an address, a bitmap of the words after it, a bitmap of the 63 words after those, and
+    // another address
+    let bytes = build(true, &[0x10000, 0b10111, 0b111, 0x20000]);
+    assert_eq!(
+        addresses(&bytes),
+        [0x10000, 0x10008, 0x10010, 0x10020, 0x10200, 0x10208, 0x20000]
+    );
+
+    let bytes = build(false, &[0x1000, 0b111, 0b101]);
+    assert_eq!(addresses(&bytes), [0x1000, 0x1004, 0x1008, 0x1084]);
+
+    let bytes = build(true, &[]);
+    assert!(addresses(&bytes).is_empty());
+
+    assert_eq!(SectionKind::Relr.to_string(), "RELR");
+    assert_eq!(DynTag::from_u64(36), Some(DynTag::Relr));
+}
+
+#[test]
+fn needed_libraries() {
+    let build = |strtab: u64, strsz: u64| {
+        let mut builder = ElfBuilder::new(
+            ElfKind::Dynamic,
+            MachineKind::X86_64,
+            true,
+            Endianness::Little,
+        );
+
+        let name = builder.add_string(".dynstr");
+        let dynstr = builder
+            .add_section(Section {
+                data: Cow::Borrowed(b"\0libc.so.6\0libm.so.6\0trailing"),
+                name,
+                kind: SectionKind::StringTable,
+                flags: SectionFlag::Alloc.into(),
+                extra_flags: 0,
+                vaddr: 0x1000,
+                info: 0,
+                entsize: 0,
+                alignment: 1,
+                size: None,
+                link: None,
+            })
+            .unwrap();
+
+        let name = builder.add_string(".dynamic");
+        let mut dynamic = Vec::new();
+        for (tag, value) in [(1u64, 1u64), (5, strtab), (10, strsz), (1, 11), (0, 0)] {
+            dynamic.extend_from_slice(&tag.to_le_bytes());
+            dynamic.extend_from_slice(&value.to_le_bytes());
+        }
+        let dynamic = builder
+            .add_section(Section {
+                data: Cow::Owned(dynamic),
+                name,
+                kind: SectionKind::Dynamic,
+                flags: SectionFlag::Alloc | SectionFlag::Write,
+                extra_flags: 0,
+                vaddr: 0x2000,
+                info: 0,
+                entsize: 16,
+                alignment: 8,
+                size: None,
+                link: Some(dynstr),
+            })
+            .unwrap();
+
+        builder
+            .add_segment(Segment {
+                section: dynstr,
+                kind: SegmentKind::Load,
+                vaddr: 0x1000,
+                paddr: 0x1000,
+                filesz: 29,
+                memsz: 29,
+                flags: SegmentFlag::Read.into(),
+                align: 1,
+                last_section: None,
+            })
+            .unwrap();
+        builder
+            .add_segment(Segment {
+                section: dynamic,
+                kind: SegmentKind::Load,
+                vaddr: 0x2000,
+                paddr: 0x2000,
+                filesz: 80,
+                memsz: 80,
+                flags: SegmentFlag::Read | SegmentFlag::Write,
+                align: 1,
+                last_section: None,
+            })
+            .unwrap();
+
+        builder.build_to_vec().unwrap()
+    };
+
+    let bytes = build(0x1000, 21);
+    let reader = ElfReader::new(&bytes).unwrap();
+    let dynamic = reader
+        .sections()
+        .unwrap()
+        .get(2)
+        .unwrap()
+        .dynamic()
+        .unwrap();
+    assert_eq!(dynamic.find(DynTag::StrTab), Some(0x1000));
+    assert_eq!(dynamic.find(DynTag::StrSz), Some(21));
+    assert_eq!(dynamic.find(DynTag::Null), None);
+    assert_eq!(dynamic.find(DynTag::SoName), None);
+    assert_eq!(
+        dynamic.needed_libraries().unwrap(),
+        [c"libc.so.6", c"libm.so.6"]
+    );
+
+    // DT_STRTAB isn't in any loadable segment
+    let bytes = build(0x3000, 21);
+    let reader = ElfReader::new(&bytes).unwrap();
+    let dynamic = reader
+        .sections()
+        .unwrap()
+        .get(2)
+        .unwrap()
+        .dynamic()
+        .unwrap();
+    assert_eq!(
+        dynamic.needed_libraries(),
+        Err(ParseError::InvalidValue("DT_STRTAB"))
+    );
+
+    // the second name isn't null-terminated within DT_STRSZ bytes
+    let bytes = build(0x1000, 20);
+    let reader = ElfReader::new(&bytes).unwrap();
+    let dynamic = reader
+        .sections()
+        .unwrap()
+        .get(2)
+        .unwrap()
+        .dynamic()
+        .unwrap();
+    assert_eq!(
+        dynamic.needed_libraries(),
+        Err(ParseError::InvalidValue("DT_NEEDED"))
+    );
+}
+
+#[test]
+fn compressed_sections() {
+    // zlib streams using dynamic Huffman codes, fixed Huffman codes and a stored block
+    const DYNAMIC: &[u8] = &[
+        0x78, 0xda, 0x9d, 0xd2, 0x5d, 0x16, 0x42, 0x50, 0x18, 0x85, 0xe1, 0x7b, 0xa3, 0xf8, 0x86,
+        0x60, 0x4b, 0x3f, 0x9a, 0x8d, 0x38, 0x4a, 0x0e, 0x27, 0x0a, 0x65, 0xf4, 0x96, 0x66, 0xe0,
+        0xbd, 0xde, 0xeb, 0xbd, 0xda, 0x8f, 0xaf, 0x3b, 0x67, 0xf1, 0xd5, 0x3e, 0x0f, 0x67, 0xfd,
+        0x58, 0x17, 0x8d, 0xdd, 0x86, 0x30, 0x77, 0x56, 0x85, 0xaf, 0x3d, 0xc7, 0xf6, 0xf5, 0xb6,
+        0x30, 0xb9, 0xe1, 0x3f, 0xfb, 0x7c, 0xf9, 0x59, 0x19, 0xee, 0x91, 0xdf, 0x1a, 0x81, 0x26,
+        0x01, 0xcd, 0x01, 0x34, 0x29, 0x68, 0x8e, 0xa0, 0x39, 0x81, 0xe6, 0x0c, 0x9a, 0x0b, 0x68,
+        0x32, 0xf2, 0x29, 0x82, 0x40, 0x24, 0x88, 0x50, 0x10, 0xb1, 0x20, 0x82, 0x41, 0x44, 0x83,
+        0x08, 0x07, 0x11, 0x0f, 0x22, 0x20, 0xb4, 0x53, 0xc4, 0x0a, 0xaf, 0x3e, 0x70, 0xf8,
+    ];
+    const FIXED: &[u8] = &[
+        0x78, 0xda, 0xcb, 0x48, 0xcd, 0xc9, 0xc9, 0x57, 0xc8, 0x40, 0x90, 0x00, 0x3a, 0x2e, 0x06,
+        0x7d,
+    ];
+    const STORED: &[u8] = &[
+        0x78, 0x01, 0x01, 0x06, 0x00, 0xf9, 0xff, 0x73, 0x74, 0x6f, 0x72, 0x65, 0x64, 0x09, 0x3c,
+        0x02, 0x92,
+    ];
+
+    let text = (0..20)
+        .map(|i| format!("line {i}: the quick brown fox jumps over the lazy dog\n"))
+        .collect::<String>();
+
+    for (is_64bit, endianness) in [(true, Endianness::Little), (false, Endianness::Big)] {
+        let mut builder = ElfBuilder::new(
+            ElfKind::Relocatable,
+            MachineKind::X86_64,
+            is_64bit,
+            endianness,
+        );
+
+        let compressed = |kind: u32, size: u64, data: &[u8]| {
+            let mut section = Vec::new();
+
+            if is_64bit {
+                section.extend_from_slice(&endianness.u32_to_bytes(kind));
+                section.extend_from_slice(&[0; 4]);
+                section.extend_from_slice(&endianness.u64_to_bytes(size));
+                section.extend_from_slice(&endianness.u64_to_bytes(1));
+            } else {
+                section.extend_from_slice(&endianness.u32_to_bytes(kind));
+                section.extend_from_slice(&endianness.u32_to_bytes(size.try_into().unwrap()));
+                section.extend_from_slice(&endianness.u32_to_bytes(1));
+            }
+
+            section.extend_from_slice(data);
+            section
+        };
+
+        let sections = [
+            (".debug_info", compressed(1, 1050, DYNAMIC)),
+            (".debug_str", compressed(1, 17, FIXED)),
+            (".debug_line", compressed(1, 6, STORED)),
+            (".debug_abbrev", compressed(2, 17, FIXED)),
+            (".debug_ranges", compressed(1, 16, FIXED)),
+            (".comment", b"not compressed".to_vec()),
+            // a bogus size must not be trusted for the allocation
+            (
+                ".debug_loc",
+                compressed(1, if is_64bit { 1 << 40 } else { u32::MAX.into() }, FIXED),
+            ),
+        ];
+
+        for (name, data) in sections {
+            let flags = match name {
+                ".comment" => FlagSet::default(),
+                _ => SectionFlag::Compressed.into(),
+            };
+
+            let name = builder.add_string(name);
+            builder
+                .add_section(Section {
+                    data: Cow::Owned(data),
+                    name,
+                    kind: SectionKind::Progbits,
+                    flags,
+                    extra_flags: 0,
+                    vaddr: 0,
+                    info: 0,
+                    entsize: 0,
+                    alignment: if is_64bit { 8 } else { 4 },
+                    size: None,
+                    link: None,
+                })
+                .unwrap();
+        }
+
+        let bytes = builder.build_to_vec().unwrap();
+
+        let reader = ElfReader::new(&bytes).unwrap();
+        let sections = reader.sections().unwrap();
+        let decompressed = |index| sections.get(index).unwrap().decompressed_data();
+
+        assert_eq!(decompressed(1).unwrap(), text.as_bytes());
+        assert_eq!(decompressed(2).unwrap(), &b"hello hello hello"[..]);
+        assert_eq!(decompressed(3).unwrap(), &b"stored"[..]);
+        assert_eq!(decompressed(4), Err(ParseError::UnsupportedCompression(2)));
+        assert_eq!(decompressed(5), Err(ParseError::InvalidCompressedData));
+        assert!(matches!(
+            decompressed(6).unwrap(),
+            Cow::Borrowed(b"not compressed")
+        ));
+        assert_eq!(decompressed(7), Err(ParseError::InvalidCompressedData));
+
+        let compression = |index| sections.get(index).unwrap().compression().unwrap();
+        assert_eq!(
+            compression(1),
+            Some(CompressionHeader {
+                kind: ElfValue::Known(CompressionKind::Zlib),
+                size: 1050,
+                addralign: 1,
+            })
+        );
+        assert_eq!(
+            compression(4).unwrap().kind,
+            ElfValue::Known(CompressionKind::Zstd)
+        );
+        assert_eq!(compression(6), None);
+    }
+}
+
+#[test]
+fn hash_tables() {
+    fn elf_hash(name: &str) -> u32 {
+        name.bytes().fold(0u32, |hash, byte| {
+            let hash = (hash << 4) + u32::from(byte);
+            let high = hash & 0xf000_0000;
+
+            (hash ^ (high >> 24)) & !high
+        })
+    }
+
+    fn gnu_hash(name: &str) -> u32 {
+        name.bytes()
+            .fold(5381u32, |hash, byte| hash * 33 + u32::from(byte))
+    }
+
+    let names = ["foo", "bar", "baz"];
+
+    let mut builder = ElfBuilder::new(
+        ElfKind::Dynamic,
+        MachineKind::X86_64,
+        true,
+        Endianness::Little,
+    );
+    let dynsym = builder.dynamic_symbol_table();
+
+    for name in names {
+        builder
+            .add_dynamic_symbol(
+                name,
+                0,
+                0,
+                SymbolBinding::Global,
+                SymbolKind::Func,
+                builder.null_section(),
+            )
+            .unwrap();
+    }
+
+    // SysV hash table with two buckets
+    let mut buckets = [0u32; 2];
+    let mut chains = [0u32; 4];
+    for (index, name) in (1..).zip(names) {
+        let bucket = &mut buckets[(elf_hash(name) % 2) as usize];
+        chains[index as usize] = *bucket;
+        *bucket = index;
+    }
+
+    let mut hash = Vec::new();
+    for word in [2, 4].iter().chain(&buckets).chain(&chains) {
+        hash.extend_from_slice(&word.to_le_bytes());
+    }
+
+    // GNU hash table with a single bucket and a single bloom filter word
+    let mut bloom = 0u64;
+    let mut gnu_chains = Vec::new();
+    for name in names {
+        let hash = gnu_hash(name);
+        bloom |= (1 << (hash % 64)) | (1 << ((hash >> 6) % 64));
+        gnu_chains.push(hash & !1);
+    }
+    *gnu_chains.last_mut().unwrap() |= 1;
+
+    let mut gnu_hash_data = Vec::new();
+    for word in [1u32, 1, 1, 6] {
+        gnu_hash_data.extend_from_slice(&word.to_le_bytes());
+    }
+    gnu_hash_data.extend_from_slice(&bloom.to_le_bytes());
+    gnu_hash_data.extend_from_slice(&1u32.to_le_bytes());
+    for word in gnu_chains {
+        gnu_hash_data.extend_from_slice(&word.to_le_bytes());
+    }
+
+    // the chain of the last possible symbol index doesn't end, so the lookup can't continue
+    let mut overflow_data = Vec::new();
+    for word in [1u32, u32::MAX, 1, 6] {
+        overflow_data.extend_from_slice(&word.to_le_bytes());
+    }
+    overflow_data.extend_from_slice(&u64::MAX.to_le_bytes());
+    overflow_data.extend_from_slice(&u32::MAX.to_le_bytes());
+    overflow_data.extend_from_slice(&0u32.to_le_bytes());
+
+    for (name, kind, data) in [
+        (".hash", SectionKind::Hash, hash),
+        (".gnu.hash", SectionKind::GnuHash, gnu_hash_data),
+        (".gnu.hash", SectionKind::GnuHash, overflow_data),
+    ] {
+        let name = builder.add_string(name);
+        builder
+            .add_section(Section {
+                data: Cow::Owned(data),
+                name,
+                kind,
+                flags: SectionFlag::Alloc.into(),
+                extra_flags: 0,
+                vaddr: 0,
+                info: 0,
+                entsize: 4,
+                alignment: 8,
+                size: None,
+                link: Some(dynsym),
+            })
+            .unwrap();
+    }
+
+    builder.allow_unusual();
+    let bytes = builder.build_to_vec().unwrap();
+
+    let reader = ElfReader::new(&bytes).unwrap();
+    let sections = reader.sections().unwrap();
+
+    let hash = sections.get(1).unwrap().hash_table().unwrap();
+    assert_eq!(hash.bucket_count(), 2);
+    assert_eq!(hash.chain_count(), 4);
+
+    let gnu_hash = match sections.get(2).unwrap().typed().unwrap() {
+        TypedSection::GnuHash(gnu_hash) => gnu_hash,
+        _ => panic!("expected a GNU hash table"),
+    };
+    assert_eq!(gnu_hash.symbol_offset(), 1);
+    assert_eq!(gnu_hash.bloom(0), Some(bloom));
+
+    for (index, name) in (1..).zip(names) {
+        assert_eq!(hash.lookup(name), Some(index));
+        assert_eq!(gnu_hash.lookup(name), Some(index));
+    }
+
+    assert_eq!(hash.lookup("qux"), None);
+    assert_eq!(gnu_hash.lookup("qux"), None);
+    assert!(sections.get(1).unwrap().gnu_hash_table().is_err());
+
+    let overflow = sections.get(3).unwrap().gnu_hash_table().unwrap();
+    assert_eq!(overflow.chain(u32::MAX), Some(0));
+    assert_eq!(overflow.lookup("foo"), None);
+}
+
+#[test]
+fn interpreter() {
+    fn build(path: &'static [u8]) -> Vec<u8> {
+        let mut builder = ElfBuilder::new(
+            ElfKind::Executable,
+            MachineKind::X86_64,
+            true,
+            Endianness::Little,
+        );
+
+        let name = builder.add_string(".interp");
+        let section = builder
+            .add_section(Section {
+                data: Cow::Borrowed(path),
+                name,
+                kind: SectionKind::Progbits,
+                flags: SectionFlag::Alloc.into(),
+                extra_flags: 0,
+                vaddr: 0x400000,
+                info: 0,
+                entsize: 0,
+                alignment: 1,
+                size: None,
+                link: None,
+            })
+            .unwrap();
+
+        builder
+            .add_segment(Segment {
+                section,
+                kind: SegmentKind::Interp,
+                vaddr: 0x400000,
+                paddr: 0x400000,
+                filesz: path.len().try_into().unwrap(),
+                memsz: path.len().try_into().unwrap(),
+                flags: SegmentFlag::Read.into(),
+                align: 1,
+                last_section: None,
+            })
+            .unwrap();
+
+        builder.allow_unusual();
+        builder.build_to_vec().unwrap()
+    }
+
+    let bytes = build(b"/lib/ld-linux-x86-64.so.2\0");
+    let reader = ElfReader::new(&bytes).unwrap();
+    assert_eq!(reader.interpreter(), Ok(Some(c"/lib/ld-linux-x86-64.so.2")));
+
+    let bytes = build(b"/lib/ld-linux-x86-64.so.2");
+    let reader = ElfReader::new(&bytes).unwrap();
+    assert_eq!(
+        reader.interpreter(),
+        Err(ParseError::InvalidValue("p_filesz"))
+    );
+
+    let reader = ElfReader::new(include_bytes!("hello-world.bin")).unwrap();
+    assert_eq!(reader.interpreter(), Ok(None));
+}
+
+#[test]
+fn filter_by_kind() {
+    let reader = ElfReader::new(include_bytes!("hello-world.bin")).unwrap();
+    let sections = reader.sections().unwrap();
+    let strings = reader.strings().unwrap();
+
+    let progbits = sections
+        .of_kind(SectionKind::Progbits)
+        .map(|section| strings.get_str(section.name().into()).unwrap().unwrap())
+        .collect::<Vec<_>>();
+    assert_eq!(
+        progbits,
+        [
+            ".rodata",
+            ".eh_frame_hdr",
+            ".eh_frame",
+            ".text",
+            ".data",
+            ".comment"
+        ]
+    );
+
+    let nobits = sections.first_of_kind(SectionKind::Nobits).unwrap();
+    assert_eq!(strings.get_str(nobits.name().into()), Some(Ok(".tbss")));
+    assert!(sections.first_of_kind(SectionKind::Dynamic).is_none());
+
+    let segments = reader.segments().unwrap();
+    let load_offsets = segments
+        .of_kind(SegmentKind::Load)
+        .map(|segment| segment.offset())
+        .collect::<Vec<_>>();
+    assert_eq!(load_offsets, [0, 0xca4, 0x1b20]);
+    assert_eq!(segments.of_kind(SegmentKind::Interp).count(), 0);
+}
+
+#[test]
+fn embedded_elf() {
+    let payload = include_bytes!("hello-world.bin");
+
+    let mut builder = ElfBuilder::new(
+        ElfKind::Executable,
+        MachineKind::RiscV,
+        false,
+        Endianness::Big,
+    );
+    let name = builder.add_string(".payload");
+    let section = builder
+        .add_section(Section {
+            data: Cow::Borrowed(payload),
+            name,
+            kind: SectionKind::Progbits,
+            flags: SectionFlag::Alloc.into(),
+            extra_flags: 0,
+            vaddr: 0x80000000,
+            info: 0,
+            entsize: 0,
+            alignment: 8,
+            size: None,
+            link: None,
+        })
+        .unwrap();
+    builder
+        .add_segment(Segment {
+            section,
+            kind: SegmentKind::Load,
+            vaddr: 0x80000000,
+            paddr: 0x80000000,
+            filesz: payload.len().try_into().unwrap(),
+            memsz: payload.len().try_into().unwrap(),
+            flags: SegmentFlag::Read.into(),
+            align: 8,
+            last_section: None,
+        })
+        .unwrap();
+
+    let bytes = builder.build_to_vec().unwrap();
+    let reader = ElfReader::new(&bytes).unwrap();
+    let embedded = reader
+        .segments()
+        .unwrap()
+        .get(0)
+        .unwrap()
+        .as_embedded_elf()
+        .unwrap();
+    assert_eq!(embedded.bytes(), payload);
+    assert!(embedded.is_64bit());
+    assert_eq!(embedded.endianness(), Endianness::Little);
+    assert_eq!(
+        embedded.header().unwrap().machine(),
+        ElfValue::Known(MachineKind::X86_64)
+    );
+
+    // the first loadable segment of hello-world.bin contains its own ELF header, but the others
+    // don't start with the ELF magic
+    let reader = ElfReader::new(payload).unwrap();
+    let embedded = reader
+        .segments()
+        .unwrap()
+        .of_kind(SegmentKind::Load)
+        .map(|segment| segment.as_embedded_elf().is_some())
+        .collect::<Vec<_>>();
+    assert_eq!(embedded, [true, false, false]);
+}
+
+#[test]
+fn string_table() {
+    let mut builder = ElfBuilder::new(
+        ElfKind::Relocatable,
+        MachineKind::X86_64,
+        true,
+        Endianness::Little,
+    );
+    builder
+        .add_dynamic_symbol(
+            "puts",
+            0,
+            0,
+            SymbolBinding::Global,
+            SymbolKind::Func,
+            builder.null_section(),
+        )
+        .unwrap();
+    let bytes = builder.build_to_vec().unwrap();
+    let reader = ElfReader::new(&bytes).unwrap();
+    let sections = reader.sections().unwrap();
+
+    // the string table of the dynamic symbol table, `.dynstr`
+    let dynsym = sections
+        .clone()
+        .into_iter()
+        .find(|section| section.kind() == ElfValue::Known(SectionKind::DynSym))
+        .unwrap();
+    let symbols = dynsym.symbols().unwrap();
+    let strings = reader
+        .string_table(usize::try_from(dynsym.link()).unwrap())
+        .unwrap();
+    let name = symbols.get(1).unwrap().name();
+    assert_eq!(strings.get_str(name.into()), Some(Ok("puts")));
+
+    let shstrndx = reader.shstrndx().unwrap();
+    let strings = reader.string_table(shstrndx).unwrap();
+    assert_eq!(strings.get_str(1), reader.strings().unwrap().get_str(1));
+
+    assert_eq!(
+        reader.string_table(sections.len()).unwrap_err(),
+        ParseError::InvalidValue("e_shnum")
+    );
+}
+
+#[test]
+fn strings_iter() {
+    let mut builder = ElfBuilder::new(
+        ElfKind::Relocatable,
+        MachineKind::X86_64,
+        true,
+        Endianness::Little,
+    );
+    for (name, data) in [(".empty", &b""[..]), (".unterminated", &b"\0foo\0bar"[..])] {
+        let name = builder.add_string(name);
+        builder
+            .add_section(Section {
+                data: Cow::Borrowed(data),
+                name,
+                kind: SectionKind::StringTable,
+                flags: FlagSet::default(),
+                extra_flags: 0,
+                vaddr: 0,
+                info: 0,
+                entsize: 0,
+                alignment: 1,
+                size: None,
+                link: None,
+            })
+            .unwrap();
+    }
+    let bytes = builder.build_to_vec().unwrap();
+    let reader = ElfReader::new(&bytes).unwrap();
+
+    assert_eq!(reader.string_table(1).unwrap().iter().count(), 0);
+    let strings = reader.string_table(2).unwrap();
+    let strings = strings
+        .iter()
+        .map(|(index, string)| (index, string.to_bytes()))
+        .collect::<Vec<_>>();
+    assert_eq!(strings, [(0, &b""[..]), (1, b"foo")]);
+
+    // the unterminated string can still be read as raw bytes
+    let strings = reader.string_table(2).unwrap();
+    assert_eq!(strings.get_bytes_until_nul(1), Some(&b"foo"[..]));
+    assert_eq!(strings.get_bytes_until_nul(5), Some(&b"bar"[..]));
+    assert_eq!(strings.get_cstr(5), None);
+    assert_eq!(strings.get_bytes_until_nul(8), Some(&b""[..]));
+    assert_eq!(strings.get_bytes_until_nul(9), None);
+}
+
+#[test]
+fn kinds_from_raw() {
+    assert_eq!(SegmentKind::from_raw(1), ElfValue::Known(SegmentKind::Load));
+    assert_eq!(SegmentKind::from_raw(7), ElfValue::Known(SegmentKind::Tls));
+    assert_eq!(
+        SegmentKind::from_raw(0x6474_e550),
+        ElfValue::Unknown(0x6474_e550)
+    );
+    assert_eq!(SectionKind::from_raw(0), ElfValue::Known(SectionKind::Null));
+    assert_eq!(
+        SectionKind::from_raw(0x7000_0001),
+        ElfValue::Unknown(0x7000_0001)
+    );
+
+    // the reader interprets the headers of a file the same way
+    let file = include_bytes!("hello-world.bin");
+    let reader = ElfReader::new(file).unwrap();
+    let phdrs = reader.phdr_bytes().unwrap();
+    for (segment, phdr) in reader.segments().unwrap().into_iter().zip(phdrs.chunks(56)) {
+        let raw = u32::from_le_bytes(phdr[..4].try_into().unwrap());
+        assert_eq!(segment.kind(), SegmentKind::from_raw(raw));
+    }
+}
+
+#[test]
+fn processor_section_kinds() {
+    let mut builder = ElfBuilder::new(
+        ElfKind::Relocatable,
+        MachineKind::Arm,
+        false,
+        Endianness::Little,
+    );
+    let name = builder.add_string(".ARM.exidx");
+    builder
+        .add_section(Section {
+            data: Cow::Borrowed(&[0; 8]),
+            name,
+            kind: SectionKind::Progbits,
+            flags: SectionFlag::Alloc.into(),
+            extra_flags: 0,
+            vaddr: 0,
+            info: 0,
+            entsize: 0,
+            alignment: 4,
+            size: None,
+            link: None,
+        })
+        .unwrap();
+    let mut bytes = builder.build_to_vec().unwrap();
+
+    // the builder can't write processor-specific types, so sh_type of section 1 is patched
+    let shoff = ElfReader::new(&bytes).unwrap().header().unwrap().shoff();
+    let sh_type = usize::try_from(shoff).unwrap() + 40 + 4;
+    bytes[sh_type..sh_type + 4].copy_from_slice(&0x7000_0001u32.to_le_bytes());
+
+    let reader = ElfReader::new(&bytes).unwrap();
+    let section = reader.sections().unwrap().get(1).unwrap();
+    assert_eq!(section.kind(), ElfValue::Unknown(0x7000_0001));
+
+    let kind = section.kind_for(MachineKind::Arm);
+    assert_eq!(
+        kind,
+        ElfValue::Known(ProcessorSectionKind::Arm(ArmSectionKind::Exidx))
+    );
+    let ElfValue::Known(kind) = kind else {
+        unreachable!()
+    };
+    assert_eq!(kind.to_string(), "ARM_EXIDX");
+    assert_eq!(kind.to_u32(), Some(0x7000_0001));
+
+    // the same number means something else on other machines
+    assert_eq!(
+        section.kind_for(MachineKind::Mips),
+        ElfValue::Known(ProcessorSectionKind::Mips(MipsSectionKind::Msym))
+    );
+    assert_eq!(
+        section.kind_for(MachineKind::X86_64),
+        ElfValue::Known(ProcessorSectionKind::X86_64(X86_64SectionKind::Unwind))
+    );
+    assert_eq!(
+        section.kind_for(MachineKind::Ia386),
+        ElfValue::Unknown(0x7000_0001)
+    );
+    assert_eq!(
+        reader
+            .sections()
+            .unwrap()
+            .get(0)
+            .unwrap()
+            .kind_for(MachineKind::Arm),
+        ElfValue::Unknown(0)
+    );
+}
+
+#[test]
+fn stripped() {
+    let reader = ElfReader::new(include_bytes!("hello-world.bin")).unwrap();
+    assert!(reader.is_stripped().unwrap());
+
+    for strip in [false, true] {
+        let mut builder = ElfBuilder::new(
+            ElfKind::Relocatable,
+            MachineKind::X86_64,
+            true,
+            Endianness::Little,
+        );
+        builder
+            .add_symbol(
+                "main",
+                0,
+                0,
+                SymbolBinding::Global,
+                SymbolKind::Func,
+                builder.null_section(),
+            )
+            .unwrap();
+        builder.set_strip(strip);
+        let mut bytes = builder.build_to_vec().unwrap();
+
+        let reader = ElfReader::new(&bytes).unwrap();
+        assert_eq!(reader.is_stripped().unwrap(), strip);
+
+        // e_shoff is 0 in files without a section header table
+        bytes[0x28..0x30].fill(0);
+        let reader = ElfReader::new(&bytes).unwrap();
+        assert!(reader.is_stripped().unwrap());
+    }
+}
+
+#[test]
+fn executable_ranges() {
+    let file = include_bytes!("hello-world.bin");
+    let reader = ElfReader::new(file).unwrap();
+    let ranges = reader.executable_ranges().unwrap();
+    assert_eq!(ranges, [(0x201ca4, &file[0xca4..0xca4 + 0xe7b])]);
+
+    // without the section header table, the executable segments are used
+    let mut bytes = file.to_vec();
+    bytes[0x28..0x30].fill(0);
+    let reader = ElfReader::new(&bytes).unwrap();
+    let ranges = reader.executable_ranges().unwrap();
+    assert_eq!(ranges, [(0x201ca4, &file[0xca4..0xca4 + 0xe7b])]);
+
+    // an empty executable section is skipped
+    let mut builder = ElfBuilder::new(
+        ElfKind::Relocatable,
+        MachineKind::X86_64,
+        true,
+        Endianness::Little,
+    );
+    for (name, data) in [(".text", &[0x90, 0xc3][..]), (".text.empty", &[])] {
+        let name = builder.add_string(name);
+        builder
+            .add_section(Section {
+                data: Cow::Borrowed(data),
+                name,
+                kind: SectionKind::Progbits,
+                flags: SectionFlag::Alloc | SectionFlag::ExecInstr,
+                extra_flags: 0,
+                vaddr: 0,
+                info: 0,
+                entsize: 0,
+                alignment: 1,
+                size: None,
+                link: None,
+            })
+            .unwrap();
+    }
+    let bytes = builder.build_to_vec().unwrap();
+    let reader = ElfReader::new(&bytes).unwrap();
+    assert_eq!(
+        reader.executable_ranges().unwrap(),
+        [(0, &[0x90, 0xc3][..])]
+    );
+}
+
+#[test]
+fn image_end() {
+    let bytes = include_bytes!("hello-world.bin");
+    let reader = ElfReader::new(bytes).unwrap();
+    assert_eq!(reader.image_end().unwrap(), bytes.len() as u64);
+
+    let mut builder = ElfBuilder::new(
+        ElfKind::Relocatable,
+        MachineKind::X86_64,
+        true,
+        Endianness::Little,
+    );
+    let name = builder.add_string(".text");
+    builder
+        .add_section(Section {
+            data: Cow::Borrowed(&[0xc3]),
+            name,
+            kind: SectionKind::Progbits,
+            flags: SectionFlag::Alloc | SectionFlag::ExecInstr,
+            extra_flags: 0,
+            vaddr: 0,
+            info: 0,
+            entsize: 0,
+            alignment: 1,
+            size: None,
+            link: None,
+        })
+        .unwrap();
+    let mut bytes = builder.build_to_vec().unwrap();
+    let len = bytes.len() as u64;
+
+    // data appended after the file isn't counted
+    bytes.extend_from_slice(&[0xff; 100]);
+    let reader = ElfReader::new(&bytes).unwrap();
+    assert_eq!(reader.image_end().unwrap(), len);
+
+    // a section whose data isn't within the file
+    let shoff = usize::try_from(reader.header().unwrap().shoff()).unwrap();
+    let sh_offset = shoff + 64 + 24;
+    bytes[sh_offset..sh_offset + 8].copy_from_slice(&0x10000u64.to_le_bytes());
+    let reader = ElfReader::new(&bytes).unwrap();
+    assert!(matches!(
+        reader.image_end(),
+        Err(ParseError::UnexpectedEof { .. })
+    ));
+}
+
+#[test]
+fn snapshots() {
+    let reader = ElfReader::new(include_bytes!("hello-world.bin")).unwrap();
+    let header = reader.header().unwrap().snapshot();
+
+    assert!(header.is_64bit);
+    assert_eq!(header.kind, ElfValue::Known(ElfKind::None));
+    assert_eq!(header.shnum, 10);
+
+    let section = reader.sections().unwrap().get(4).unwrap();
+    let snapshot = section.snapshot();
+    assert_eq!(snapshot.kind, section.kind());
+    assert_eq!(snapshot.flags, ElfValue::Known(section.known_flags()));
+    assert_eq!(snapshot.addr, 0x201ca4);
+    assert_eq!(snapshot.size, section.size());
+
+    let segment = reader.segments().unwrap().get(0).unwrap();
+    assert_eq!(segment.snapshot().offset, segment.offset());
+}
+
+#[test]
+fn snapshots_32bit() {
+    let mut builder = ElfBuilder::new(
+        ElfKind::Executable,
+        MachineKind::Sparc,
+        false,
+        Endianness::Big,
+    );
+    builder.set_entrypoint(0x8049000).unwrap();
+    builder.allow_unusual();
+    let bytes = builder.build_to_vec().unwrap();
+
+    let reader = ElfReader::new(&bytes).unwrap();
+    let header = reader.header().unwrap();
+    let snapshot = header.snapshot();
+
+    assert!(!snapshot.is_64bit);
+    assert_eq!(snapshot.endianness, Endianness::Big);
+    assert_eq!(snapshot.machine, ElfValue::Known(MachineKind::Sparc));
+    // the 32-bit fields are widened
+    assert_eq!(snapshot.entry, 0x8049000);
+    assert_eq!(snapshot.shoff, header.shoff());
+    assert_eq!(snapshot.ehsize, 52);
+    assert_eq!(snapshot.shentsize, 40);
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn serialize() {
+    let reader = ElfReader::new(include_bytes!("hello-world.bin")).unwrap();
+
+    let header = serde_json::to_value(reader.header().unwrap()).unwrap();
+    assert_eq!(
+        header["kind"],
+        serde_json::json!({ "value": 0, "name": "None" })
+    );
+    assert_eq!(
+        header["machine"],
+        serde_json::json!({ "value": 62, "name": "X86_64" })
+    );
+
+    let section = serde_json::to_value(reader.sections().unwrap().get(4).unwrap()).unwrap();
+    assert_eq!(
+        section["flags"],
+        serde_json::json!({ "value": 6, "name": ["Alloc", "ExecInstr"] })
+    );
+    assert_eq!(section["addr"], 0x201ca4);
+
+    let segment = serde_json::to_value(reader.segments().unwrap().get(0).unwrap()).unwrap();
+    assert_eq!(segment["kind"]["name"], "Phdr");
+}
+
+#[test]
+fn machine_names() {
+    for value in 0..=u16::MAX {
+        if let Some(machine) = MachineKind::from_u16(value) {
+            assert!(machine_name(value).is_some(), "{machine:?} has no name");
+            assert_eq!(machine.name(), machine_name(value).unwrap());
+        }
+    }
+
+    assert_eq!(machine_name(62), Some("AMD x86-64 architecture"));
+    assert_eq!(machine_name(0xffff), None);
+}
+
+#[test]
+fn machine_from_name() {
+    for (name, machine) in [
+        ("x86-64", MachineKind::X86_64),
+        ("X86_64", MachineKind::X86_64),
+        ("amd64", MachineKind::X86_64),
+        ("RISC-V", MachineKind::RiscV),
+        ("riscv", MachineKind::RiscV),
+        ("AArch64", MachineKind::Aarch64),
+        ("arm64", MachineKind::Aarch64),
+        ("i386", MachineKind::Ia386),
+        ("Intel 80386", MachineKind::Ia386),
+        ("ppc64", MachineKind::Ppc64),
+    ] {
+        assert_eq!(MachineKind::from_name(name), Some(machine), "{name}");
+    }
+
+    for machine in [MachineKind::Arm, MachineKind::Sparc, MachineKind::Ppc64] {
+        assert_eq!(MachineKind::from_name(machine.name()), Some(machine));
+    }
+
+    assert_eq!(MachineKind::from_name("not a machine"), None);
+}
+
+#[test]
+fn display() {
+    assert_eq!(ElfKind::Dynamic.to_string(), "DYN (Shared object file)");
+    assert_eq!(SectionKind::Progbits.to_string(), "PROGBITS");
+    assert_eq!(SectionKind::GnuHash.to_string(), "GNU_HASH");
+    assert_eq!(SegmentKind::Load.to_string(), "LOAD");
+    assert_eq!(SymbolKind::Func.to_string(), "FUNC");
+    assert_eq!(SymbolBinding::Weak.to_string(), "WEAK");
+    assert_eq!(OsAbi::None.to_string(), "UNIX - System V");
+    assert_eq!(format!("{:<6}|", SegmentKind::Tls), "TLS   |");
+
+    // Debug is unchanged
+    assert_eq!(format!("{:?}", ElfKind::Dynamic), "Dynamic");
+}
+
+#[test]
+fn segment_flags() {
+    let rx = SegmentFlag::Read | SegmentFlag::Execute;
+    assert_eq!(segment_flags_str(rx), "R E");
+    assert_eq!(rx.bits(), 5);
+    assert_eq!(
+        segment_flags_str(SegmentFlag::Read | SegmentFlag::Write),
+        "RW "
+    );
+    assert_eq!(segment_flags_str(FlagSet::full()), "RWE");
+    assert_eq!(segment_flags_str(FlagSet::default()), "   ");
+
+    let file = include_bytes!("hello-world.bin");
+    let reader = ElfReader::new(file).unwrap();
+    let flags = reader
+        .segments()
+        .unwrap()
+        .into_iter()
+        .filter(|segment| segment.kind() == ElfValue::Known(SegmentKind::Load))
+        .map(|segment| match segment.flags() {
+            ElfValue::Known(flags) => segment_flags_str(flags),
+            ElfValue::Unknown(_) => unreachable!(),
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(flags, ["R  ", "R E", "RW "]);
+}
+
+#[test]
+fn flag_predicates() {
+    let reader = ElfReader::new(include_bytes!("hello-world.bin")).unwrap();
+    let sections = reader.sections().unwrap();
+
+    let text = sections.get(4).unwrap();
+    assert!(text.is_alloc() && text.is_executable());
+    assert!(!text.is_writable() && !text.is_tls());
+    assert!(sections.get(5).unwrap().is_tls());
+    assert!(sections.get(6).unwrap().is_writable());
+    assert!(!sections.get(8).unwrap().is_alloc());
+
+    let segment = reader.segments().unwrap().get(2).unwrap();
+    assert!(segment.is_readable() && segment.is_executable() && !segment.is_writable());
+
+    // an OS-specific flag makes the flags unknown, but the standard flags are still found
+    let mut builder = ElfBuilder::new(
+        ElfKind::Relocatable,
+        MachineKind::X86_64,
+        true,
+        Endianness::Little,
+    );
+    let name = builder.add_string(".data");
+    builder
+        .add_section(Section {
+            data: Cow::Borrowed(&[0; 8]),
+            name,
+            kind: SectionKind::Progbits,
+            flags: SectionFlag::Alloc | SectionFlag::Write,
+            extra_flags: 0,
+            vaddr: 0,
+            info: 0,
+            entsize: 0,
+            alignment: 8,
+            size: None,
+            link: None,
+        })
+        .unwrap();
+
+    let mut bytes = builder.build_to_vec().unwrap();
+
+    let shoff = usize::try_from(ElfReader::new(&bytes).unwrap().header().unwrap().shoff()).unwrap();
+    bytes[shoff + 64 + 8 + 2] |= 0x10; // SHF_MASKOS bit 0x100000 of section 1
+
+    let reader = ElfReader::new(&bytes).unwrap();
+    let section = reader.sections().unwrap().get(1).unwrap();
+    assert_eq!(section.flags(), ElfValue::Unknown(0x100003));
+    assert!(section.is_alloc() && section.is_writable() && !section.is_executable());
+}
+
+#[test]
+fn no_program_headers() {
+    let builder = ElfBuilder::new(
+        ElfKind::Relocatable,
+        MachineKind::X86_64,
+        true,
+        Endianness::Little,
+    );
+    let mut bytes = builder.build_to_vec().unwrap();
+
+    // relocatable objects produced by common toolchains set e_phentsize to 0
+    bytes[54..56].copy_from_slice(&[0, 0]);
+
+    let reader = ElfReader::new(&bytes).unwrap();
+    assert_eq!(reader.header().unwrap().phnum(), 0);
+    assert!(reader.segments().unwrap().get(0).is_none());
+}
+
+#[test]
+fn section_at_offset() {
+    let reader = ElfReader::new(include_bytes!("hello-world.bin")).unwrap();
+    let sections = reader.sections().unwrap();
+    let text = sections.get(4).unwrap();
+
+    let find = |offset| {
+        reader
+            .section_at_offset(offset)
+            .unwrap()
+            .map(|s| s.offset())
+    };
+
+    assert_eq!(find(text.offset()), Some(text.offset()));
+    assert_eq!(find(text.offset() + text.size() - 1), Some(text.offset()));
+    // .tbss and .data both start at 0x1b20, but .tbss takes up no space in the file
+    assert_eq!(find(0x1b20), Some(sections.get(6).unwrap().offset()));
+    assert_eq!(
+        reader.section_at_offset(0x1b20).unwrap().unwrap().kind(),
+        ElfValue::Known(SectionKind::Progbits)
+    );
+    assert_eq!(find(0), None);
+    assert_eq!(find(u64::MAX), None);
+}
+
+#[test]
+fn segment_sections() {
+    let reader = ElfReader::new(include_bytes!("hello-world.bin")).unwrap();
+    let strings = reader.strings().unwrap();
+
+    let mapping = reader
+        .segments()
+        .unwrap()
+        .into_iter()
+        .map(|segment| {
+            reader
+                .segment_sections(&segment)
+                .unwrap()
+                .into_iter()
+                .map(|section| strings.get_str(section.name().into()).unwrap().unwrap())
+                .collect::<Vec<_>>()
+        })
+        .collect::<Vec<_>>();
+
+    // the same as the mapping printed by readelf -l, where .bss is within the addresses of the
+    // GNU_STACK segment
+    assert_eq!(
+        mapping,
+        [
+            &[][..],
+            &[".rodata", ".eh_frame_hdr", ".eh_frame"],
+            &[".text"],
+            &[".data", ".bss"],
+            &[".tbss"],
+            &[".eh_frame_hdr"],
+            &[".bss"],
+        ]
+    );
+}
+
+#[test]
+fn load_image() {
+    let bytes = include_bytes!("hello-world.bin");
+    let reader = ElfReader::new(bytes).unwrap();
+    let image = reader.load_image().unwrap();
+
+    assert_eq!(image.len(), 0x203b20 + 0x368c - 0x200000);
+    assert_eq!(&image[..0xca4], &bytes[..0xca4]);
+    assert_eq!(&image[0x1ca4..0x1ca4 + 0xe7b], &bytes[0xca4..0xca4 + 0xe7b]);
+    assert!(image[0x1ca4 + 0xe7b..0x3b20].iter().all(|&byte| byte == 0));
+    assert_eq!(
+        &image[0x3b20..0x3b20 + 0x1d2],
+        &bytes[0x1b20..0x1b20 + 0x1d2]
+    );
+    assert!(image[0x3b20 + 0x1d2..].iter().all(|&byte| byte == 0));
+
+    // move the executable segment into the first one
+    let mut bytes = bytes.to_vec();
+    let vaddr = 0x40 + 2 * 56 + 16;
+    bytes[vaddr..vaddr + 8].copy_from_slice(&0x200010u64.to_le_bytes());
+    assert_eq!(
+        ElfReader::new(&bytes).unwrap().load_image(),
+        Err(ParseError::OverlappingSegments)
+    );
+
+    // the first segment at 0 and the last one at the top of the address space can't be allocated
+    let mut bytes = include_bytes!("hello-world.bin").to_vec();
+    let (first, last) = (0x40 + 56, 0x40 + 3 * 56);
+    bytes[first + 16..first + 24].fill(0);
+    bytes[last + 16..last + 24].copy_from_slice(&(u64::MAX - 0x10).to_le_bytes());
+    bytes[last + 32..last + 40].copy_from_slice(&0x10u64.to_le_bytes());
+    bytes[last + 40..last + 48].copy_from_slice(&0x10u64.to_le_bytes());
+    assert_eq!(
+        ElfReader::new(&bytes).unwrap().load_image(),
+        Err(ParseError::InvalidValue("p_memsz"))
+    );
+}
+
+#[test]
+fn symbol_at() {
+    let mut builder = ElfBuilder::new(
+        ElfKind::Relocatable,
+        MachineKind::X86_64,
+        true,
+        Endianness::Little,
+    );
+    let name = builder.add_string(".text");
+    let text = builder
+        .add_section(Section {
+            data: Cow::Borrowed(&[0x90; 0x20]),
+            name,
+            kind: SectionKind::Progbits,
+            flags: SectionFlag::Alloc | SectionFlag::ExecInstr,
+            extra_flags: 0,
+            vaddr: 0x1000,
+            info: 0,
+            entsize: 0,
+            alignment: 16,
+            size: None,
+            link: None,
+        })
+        .unwrap();
+
+    for (name, value, size, kind) in [
+        ("outer", 0x1000, 0x20, SymbolKind::Func),
+        ("inner", 0x1010, 8, SymbolKind::Func),
+        ("label", 0x1018, 4, SymbolKind::NoType),
+    ] {
+        builder
+            .add_symbol(name, value, size, SymbolBinding::Global, kind, text)
+            .unwrap();
+    }
+
+    let bytes = builder.build_to_vec().unwrap();
+    let reader = ElfReader::new(&bytes).unwrap();
+    let symbols = reader
+        .sections()
+        .unwrap()
+        .first_of_kind(SectionKind::SymbolTable)
+        .unwrap()
+        .symbols()
+        .unwrap();
+    let strings = symbols.strings().unwrap();
+
+    let name_at = |addr| {
+        symbols
+            .symbol_at(addr)
+            .map(|symbol| strings.get_str(symbol.name().into()).unwrap().unwrap())
+    };
+
+    assert_eq!(name_at(0x1004), Some("outer"));
+    assert_eq!(name_at(0x1010), Some("inner"));
+    assert_eq!(name_at(0x1017), Some("inner"));
+    // the symbol without a type is skipped
+    assert_eq!(name_at(0x1018), Some("outer"));
+    assert_eq!(name_at(0x1020), None);
+    assert_eq!(name_at(0xfff), None);
+}
+
+#[test]
+fn symbols_in_section() {
+    let mut builder = ElfBuilder::new(
+        ElfKind::Relocatable,
+        MachineKind::X86_64,
+        true,
+        Endianness::Little,
+    );
+    let mut add_section = |name: &str, flags| {
+        let name = builder.add_string(name);
+        builder
+            .add_section(Section {
+                data: Cow::Borrowed(&[0; 0x10]),
+                name,
+                kind: SectionKind::Progbits,
+                flags,
+                extra_flags: 0,
+                vaddr: 0,
+                info: 0,
+                entsize: 0,
+                alignment: 1,
+                size: None,
+                link: None,
+            })
+            .unwrap()
+    };
+    let text = add_section(".text", SectionFlag::Alloc | SectionFlag::ExecInstr);
+    let data = add_section(".data", SectionFlag::Alloc | SectionFlag::Write);
+
+    for (name, kind, section) in [
+        ("main", SymbolKind::Func, text),
+        ("counter", SymbolKind::Object, data),
+        ("helper", SymbolKind::Func, text),
+    ] {
+        builder
+            .add_symbol(name, 0, 0, SymbolBinding::Global, kind, section)
+            .unwrap();
+    }
+
+    let bytes = builder.build_to_vec().unwrap();
+    let reader = ElfReader::new(&bytes).unwrap();
+    let sections = reader.sections().unwrap();
+    let text_index = sections
+        .with_names()
+        .position(|(_, name)| name == Some(".text"))
+        .unwrap();
+    let symbols = sections
+        .first_of_kind(SectionKind::SymbolTable)
+        .unwrap()
+        .symbols()
+        .unwrap();
+    let strings = symbols.strings().unwrap();
+
+    let names = symbols
+        .in_section(text_index.try_into().unwrap())
+        .map(|symbol| strings.get_str(symbol.name().into()).unwrap().unwrap())
+        .collect::<Vec<_>>();
+    assert_eq!(names, ["main", "helper"]);
+    // the null symbol is in the undefined section
+    assert_eq!(symbols.in_section(0).count(), 1);
+    assert_eq!(symbols.in_section(0xff00).count(), 0);
+}
+
+#[test]
+fn big_endian_symbols() {
+    for (machine, is_64bit) in [(MachineKind::Ppc, false), (MachineKind::Ppc64, true)] {
+        let mut builder = ElfBuilder::new(ElfKind::Relocatable, machine, is_64bit, Endianness::Big);
+        let name = builder.add_string(".data");
+        // more than 0xff sections, so that both bytes of st_shndx are used
+        let sections = (0..0x120)
+            .map(|_| {
+                builder
+                    .add_section(Section {
+                        data: Cow::Borrowed(&[0; 4]),
+                        name,
+                        kind: SectionKind::Progbits,
+                        flags: SectionFlag::Alloc | SectionFlag::Write,
+                        extra_flags: 0,
+                        vaddr: 0,
+                        info: 0,
+                        entsize: 0,
+                        alignment: 1,
+                        size: None,
+                        link: None,
+                    })
+                    .unwrap()
+            })
+            .collect::<Vec<_>>();
+
+        builder
+            .add_symbol(
+                "first",
+                0x1234_5678,
+                0x10,
+                SymbolBinding::Local,
+                SymbolKind::Object,
+                sections[0],
+            )
+            .unwrap();
+        builder
+            .add_symbol(
+                "last",
+                0x0102_0304,
+                0x0a0b_0c0d,
+                SymbolBinding::Global,
+                SymbolKind::Func,
+                sections[0x11f],
+            )
+            .unwrap();
+
+        let bytes = builder.build_to_vec().unwrap();
+        let reader = ElfReader::new(&bytes).unwrap();
+        let symbols = reader
+            .sections()
+            .unwrap()
+            .first_of_kind(SectionKind::SymbolTable)
+            .unwrap()
+            .symbols()
+            .unwrap();
+        let strings = symbols.strings().unwrap();
+
+        let read = symbols
+            .into_iter()
+            .skip(1)
+            .map(|symbol| {
+                (
+                    strings.get_str(symbol.name().into()).unwrap().unwrap(),
+                    symbol.value(),
+                    symbol.size(),
+                    symbol.shndx(),
+                    symbol.binding(),
+                    symbol.kind(),
+                )
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            read,
+            [
+                (
+                    "first",
+                    0x1234_5678,
+                    0x10,
+                    1,
+                    ElfValue::Known(SymbolBinding::Local),
+                    ElfValue::Known(SymbolKind::Object)
+                ),
+                (
+                    "last",
+                    0x0102_0304,
+                    0x0a0b_0c0d,
+                    0x120,
+                    ElfValue::Known(SymbolBinding::Global),
+                    ElfValue::Known(SymbolKind::Func)
+                ),
+            ]
+        );
+    }
+}
+
+#[test]
+fn truncated_file() {
+    let bytes = include_bytes!("hello-world.bin");
+    let reader = ElfReader::new(&bytes[..0xd00]).unwrap();
+
+    let error = reader.sections().unwrap_err();
+    assert_eq!(error, ParseError::UnexpectedEof { offset: 0x1d50 });
+    assert_eq!(error.to_string(), "unexpected end of file at byte 0x1d50");
+
+    let segment = reader.segments().unwrap().get(2).unwrap();
+    assert_eq!(
+        segment.data(),
+        Err(ParseError::UnexpectedEof { offset: 0xca4 })
+    );
+}
+
+#[test]
+fn header_table_bytes() {
+    let bytes = include_bytes!("hello-world.bin");
+    let reader = ElfReader::new(bytes).unwrap();
+
+    // 7 program headers after the ELF header, and 10 section headers at the end of the file
+    assert_eq!(reader.phdr_bytes().unwrap(), &bytes[0x40..0x40 + 7 * 56]);
+    assert_eq!(reader.shdr_bytes().unwrap(), &bytes[0x1d50..]);
+
+    let reader = ElfReader::new(&bytes[..0xd00]).unwrap();
+    assert_eq!(reader.phdr_bytes().unwrap(), &bytes[0x40..0x40 + 7 * 56]);
+    assert_eq!(
+        reader.shdr_bytes(),
+        Err(ParseError::UnexpectedEof { offset: 0x1d50 })
+    );
+}
+
+#[test]
+fn extended_section_count() {
+    let bytes = include_bytes!("hello-world.bin");
+    let reader = ElfReader::new(bytes).unwrap();
+    let header = reader.header().unwrap();
+    let shoff = usize::try_from(header.shoff()).unwrap();
+    let (shnum, shstrndx) = (header.shnum(), header.shstrndx());
+
+    let names = |reader: &ElfReader| {
+        let strings = reader.strings().unwrap();
+        reader
+            .sections()
+            .unwrap()
+            .into_iter()
+            .map(|section| strings.get_str(section.name().into()).unwrap().unwrap())
+            .map(str::to_string)
+            .collect::<Vec<_>>()
+    };
+
+    // move e_shnum to sh_size and e_shstrndx to sh_link of the first section header, as is done
+    // when there are more than 0xff00 sections
+    let mut extended = bytes.to_vec();
+    extended[60..62].copy_from_slice(&0u16.to_le_bytes());
+    extended[62..64].copy_from_slice(&0xffffu16.to_le_bytes());
+    extended[shoff + 32..shoff + 40].copy_from_slice(&u64::from(shnum).to_le_bytes());
+    extended[shoff + 40..shoff + 44].copy_from_slice(&u32::from(shstrndx).to_le_bytes());
+
+    let extended_reader = ElfReader::new(&extended).unwrap();
+    assert_eq!(extended_reader.header().unwrap().shnum(), 0);
+    assert_eq!(
+        extended_reader.sections().unwrap().len(),
+        usize::from(shnum)
+    );
+    assert_eq!(extended_reader.shstrndx(), Ok(usize::from(shstrndx)));
+    assert_eq!(names(&extended_reader), names(&reader));
+}
+
+#[test]
+fn extended_segment_count() {
+    let bytes = include_bytes!("hello-world.bin");
+    let reader = ElfReader::new(bytes).unwrap();
+    let header = reader.header().unwrap();
+    let shoff = usize::try_from(header.shoff()).unwrap();
+    let phnum = header.phnum();
+
+    let mut extended = bytes.to_vec();
+    extended[56..58].copy_from_slice(&0xffffu16.to_le_bytes());
+    extended[shoff + 44..shoff + 48].copy_from_slice(&u32::from(phnum).to_le_bytes());
+
+    let extended_reader = ElfReader::new(&extended).unwrap();
+    let segments = extended_reader.segments().unwrap();
+    assert_eq!(segments.len(), usize::from(phnum));
+    assert_eq!(
+        segments
+            .into_iter()
+            .map(|s| s.snapshot())
+            .collect::<Vec<_>>(),
+        reader
+            .segments()
+            .unwrap()
+            .into_iter()
+            .map(|s| s.snapshot())
+            .collect::<Vec<_>>()
+    );
+
+    // without a section header table, the count can't be found
+    extended[40..48].copy_from_slice(&0u64.to_le_bytes());
+    extended[60..62].copy_from_slice(&0u16.to_le_bytes());
+    assert_eq!(
+        ElfReader::new(&extended).unwrap().segments().unwrap_err(),
+        ParseError::InvalidValue("e_phnum")
+    );
+}
+
+#[test]
+fn truncated_header() {
+    for is_64bit in [false, true] {
+        let builder = ElfBuilder::new(
+            ElfKind::Relocatable,
+            MachineKind::X86_64,
+            is_64bit,
+            Endianness::Big,
+        );
+        let bytes = builder.build_to_vec().unwrap();
+        let header_size = if is_64bit { 64 } else { 52 };
+
+        let full = ElfReader::new(&bytes).unwrap().header().unwrap().snapshot();
+
+        for length in 0..=header_size {
+            let Ok(reader) = ElfReader::new(&bytes[..length]) else {
+                assert!(length < 16);
+                continue;
+            };
+
+            match reader.header() {
+                Ok(header) => {
+                    assert_eq!(length, header_size);
+                    assert_eq!(header.snapshot(), full);
+                }
+                Err(error) => {
+                    assert!(length < header_size);
+                    assert_eq!(error, ParseError::UnexpectedEof { offset: length });
+                }
+            }
+
+            // the tables can't be found without the header
+            if length < header_size {
+                assert!(reader.sections().is_err());
+                assert!(reader.segments().is_err());
+                assert!(reader.strings().is_err());
+            }
+        }
+    }
+}
+
+#[test]
+fn parse_checked() {
+    let bytes = include_bytes!("hello-world.bin");
+    assert!(ElfReader::parse_checked(bytes).is_ok());
+
+    // the section header table is at the end of the file
+    assert_eq!(
+        ElfReader::parse_checked(&bytes[..bytes.len() - 1]).unwrap_err(),
+        ParseError::UnexpectedEof { offset: 0x1d50 }
+    );
+
+    // point the data of .comment past the end of the file, and then to the largest offset
+    let mut bytes = bytes.to_vec();
+    let sh_offset = 0x1d50 + 8 * 64 + 24;
+    for offset in [0x10000u64, u64::MAX] {
+        bytes[sh_offset..sh_offset + 8].copy_from_slice(&offset.to_le_bytes());
+
+        assert!(ElfReader::new(&bytes).unwrap().sections().is_ok());
+        assert!(matches!(
+            ElfReader::parse_checked(&bytes),
+            Err(ParseError::UnexpectedEof { .. })
+        ));
+    }
+}
+
+#[test]
+fn owned_section_data() {
+    let bytes = include_bytes!("hello-world.bin");
+    let reader = ElfReader::new(bytes).unwrap();
+    let sections = reader.sections().unwrap();
+
+    let comment = sections.get(8).unwrap();
+    let mut data = comment.to_owned_data().unwrap();
+    assert_eq!(data, comment.data().unwrap());
+    data[0] = b'l';
+    assert_eq!(&data[..6], b"linker");
+
+    let bss = sections.get(7).unwrap();
+    assert_eq!(bss.to_owned_data().unwrap(), vec![0; 0x31ac]);
+}
+
+#[test]
+fn ident_padding() {
+    let mut bytes = include_bytes!("hello-world.bin").to_vec();
+    let reader = ElfReader::new(&bytes).unwrap();
+    assert_eq!(reader.header().unwrap().ident_padding(), &[0; 7]);
+
+    bytes[9..16].copy_from_slice(b"marker!");
+    let reader = ElfReader::new(&bytes).unwrap();
+    assert_eq!(reader.header().unwrap().ident_padding(), b"marker!");
+}
+
+#[test]
+fn comment_and_abi_tag() {
+    let reader = ElfReader::new(include_bytes!("hello-world.bin")).unwrap();
+    assert_eq!(
+        reader.comment().unwrap(),
+        Some(&b"Linker: LLD 17.0.3\0"[..])
+    );
+    assert_eq!(reader.abi_tag().unwrap(), None);
+
+    for endianness in [Endianness::Little, Endianness::Big] {
+        let mut builder =
+            ElfBuilder::new(ElfKind::Relocatable, MachineKind::X86_64, true, endianness);
+        let desc = [0, 3, 2, 0]
+            .into_iter()
+            .flat_map(|word| endianness.u32_to_bytes(word))
+            .collect::<Vec<_>>();
+        builder
+            .add_note_section(".note.ABI-tag", "GNU", 1, &desc)
+            .unwrap();
+
+        let bytes = builder.build_to_vec().unwrap();
+        let reader = ElfReader::new(&bytes).unwrap();
+        assert_eq!(reader.abi_tag().unwrap(), Some((0, 3, 2, 0)));
+        assert_eq!(reader.comment().unwrap(), None);
+    }
+
+    let mut builder = ElfBuilder::new(
+        ElfKind::Relocatable,
+        MachineKind::X86_64,
+        true,
+        Endianness::Little,
+    );
+    builder
+        .add_note_section(".note.ABI-tag", "GNU", 1, &[0; 12])
+        .unwrap();
+    let bytes = builder.build_to_vec().unwrap();
+    assert_eq!(
+        ElfReader::new(&bytes).unwrap().abi_tag(),
+        Err(ParseError::InvalidValue("n_descsz"))
+    );
+}
+
+#[test]
+fn section_names() {
+    let reader = ElfReader::new(include_bytes!("hello-world.bin")).unwrap();
+    let sections = reader.sections().unwrap();
+
+    let names = sections
+        .with_names()
+        .map(|(_, name)| name.unwrap())
+        .collect::<Vec<_>>();
+    assert_eq!(
+        names,
+        [
+            "",
+            ".rodata",
+            ".eh_frame_hdr",
+            ".eh_frame",
+            ".text",
+            ".tbss",
+            ".data",
+            ".bss",
+            ".comment",
+            ".shstrtab"
+        ]
+    );
+
+    let (text, _) = sections
+        .with_names()
+        .find(|(_, name)| *name == Some(".text"))
+        .unwrap();
+    assert_eq!(text.addr(), 0x201ca4);
+
+    // without a section name string table, the sections are still found
+    let mut bytes = include_bytes!("hello-world.bin").to_vec();
+    bytes[62..64].copy_from_slice(&100u16.to_le_bytes());
+    let reader = ElfReader::new(&bytes).unwrap();
+    assert!(reader
+        .sections()
+        .unwrap()
+        .with_names()
+        .all(|(_, name)| name.is_none()));
+}
+
+#[test]
+fn core_notes() {
+    let reader = ElfReader::new(include_bytes!("hello-world.bin")).unwrap();
+    assert!(!reader.is_core());
+    assert_eq!(reader.core_note_kinds().unwrap(), []);
+
+    let mut builder = ElfBuilder::new(ElfKind::Core, MachineKind::X86_64, true, Endianness::Little);
+    let notes = [
+        ("CORE", 1, 8),
+        ("CORE", 3, 4),
+        ("LINUX", 0x202, 4),
+        ("CORE", 0x1234, 0),
+    ];
+
+    for (index, (name, kind, size)) in notes.into_iter().enumerate() {
+        let section = builder
+            .add_note_section(format!("note{index}"), name, kind, &vec![0; size])
+            .unwrap();
+        let filesz = 12 + (name.len() + 1).next_multiple_of(4) + size;
+
+        builder
+            .add_segment(Segment {
+                section,
+                kind: SegmentKind::Note,
+                vaddr: 0,
+                paddr: 0,
+                filesz: filesz.try_into().unwrap(),
+                memsz: filesz.try_into().unwrap(),
+                flags: FlagSet::default(),
+                align: 4,
+                last_section: None,
+            })
+            .unwrap();
+    }
+
+    let bytes = builder.build_to_vec().unwrap();
+    let reader = ElfReader::new(&bytes).unwrap();
+    assert!(reader.is_core());
+    assert_eq!(
+        reader.core_note_kinds().unwrap(),
+        [
+            ElfValue::Known(CoreNoteKind::PrStatus),
+            ElfValue::Known(CoreNoteKind::PrPsInfo),
+            ElfValue::Known(CoreNoteKind::X86Xstate),
+            ElfValue::Unknown(0x1234),
+        ]
+    );
+
+    assert!(!ElfReader::new(&bytes[..16]).unwrap().is_core());
+}
+
+#[test]
+fn all_notes() {
+    let mut builder = ElfBuilder::new(ElfKind::Core, MachineKind::X86_64, true, Endianness::Little);
+    let gnu = builder
+        .add_note_section(".note.gnu", "GNU", 3, &[0xab; 4])
+        .unwrap();
+    builder
+        .add_note_section(".note.test", "test", 7, &[1, 2])
+        .unwrap();
+
+    // a note that is only in a segment, in a section that isn't a note section
+    let mut raw = Vec::new();
+    for word in [4, 4, 9] {
+        raw.extend(Endianness::Little.u32_to_bytes(word));
+    }
+    raw.extend(b"XYZ\0");
+    raw.extend([1, 2, 3, 4]);
+    let name = builder.add_string(".data");
+    let data = builder
+        .add_section(Section {
+            data: Cow::Borrowed(&raw),
+            name,
+            kind: SectionKind::Progbits,
+            flags: FlagSet::default(),
+            extra_flags: 0,
+            vaddr: 0,
+            info: 0,
+            entsize: 0,
+            alignment: 4,
+            size: None,
+            link: None,
+        })
+        .unwrap();
+
+    for (section, filesz) in [(gnu, 20), (data, 20)] {
+        builder
+            .add_segment(Segment {
+                section,
+                kind: SegmentKind::Note,
+                vaddr: 0,
+                paddr: 0,
+                filesz,
+                memsz: filesz,
+                flags: FlagSet::default(),
+                align: 4,
+                last_section: None,
+            })
+            .unwrap();
+    }
+
+    let bytes = builder.build_to_vec().unwrap();
+    let reader = ElfReader::new(&bytes).unwrap();
+    let notes = reader
+        .notes()
+        .unwrap()
+        .into_iter()
+        .map(|note| (note.name(), note.kind(), note.desc()))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        notes,
+        [
+            (&b"GNU"[..], 3, &[0xab; 4][..]),
+            (b"test", 7, &[1, 2]),
+            (b"XYZ", 9, &[1, 2, 3, 4]),
+        ]
+    );
+
+    let reader = ElfReader::new(include_bytes!("hello-world.bin")).unwrap();
+    assert_eq!(reader.notes().unwrap(), []);
+}
+
+#[test]
+fn prstatus() {
+    let build = |machine, is_64bit, endianness: Endianness, desc: &[u8]| {
+        let mut builder = ElfBuilder::new(ElfKind::Core, machine, is_64bit, endianness);
+        let section = builder.add_note_section("note", "CORE", 1, desc).unwrap();
+        let filesz = u64::try_from(20 + desc.len()).unwrap();
+
+        builder
+            .add_segment(Segment {
+                section,
+                kind: SegmentKind::Note,
+                vaddr: 0,
+                paddr: 0,
+                filesz,
+                memsz: filesz,
+                flags: FlagSet::default(),
+                align: 4,
+                last_section: None,
+            })
+            .unwrap();
+
+        builder.build_to_vec().unwrap()
+    };
+    let prstatus = |bytes: &[u8], machine| {
+        let reader = ElfReader::new(bytes).unwrap();
+        let segment = reader.segments().unwrap().of_kind(SegmentKind::Note).next();
+        segment.unwrap().notes().unwrap().prstatus(machine)
+    };
+
+    // 64-bit: registers at 112, 27 of them on x86-64
+    let endianness = Endianness::Little;
+    let mut desc = vec![0; 112 + 27 * 8 + 8];
+    desc[0..4].copy_from_slice(&endianness.u32_to_bytes(11));
+    desc[12..14].copy_from_slice(&endianness.u16_to_bytes(11));
+    desc[16..24].copy_from_slice(&endianness.u64_to_bytes(0x100));
+    desc[32..36].copy_from_slice(&endianness.u32_to_bytes(1234));
+    desc[36..40].copy_from_slice(&endianness.u32_to_bytes(1));
+    for index in 0..27 {
+        let offset = 112 + index * 8;
+        desc[offset..offset + 8].copy_from_slice(&endianness.u64_to_bytes(index as u64 + 1));
+    }
+
+    let bytes = build(MachineKind::X86_64, true, endianness, &desc);
+    let status = prstatus(&bytes, MachineKind::X86_64).unwrap();
+    assert_eq!(status.signo, 11);
+    assert_eq!(status.cursig, 11);
+    assert_eq!(status.sigpend, 0x100);
+    assert_eq!(status.pid, 1234);
+    assert_eq!(status.ppid, 1);
+    let Registers::X86_64(registers) = status.registers else {
+        panic!("expected x86-64 registers");
+    };
+    assert_eq!(registers.r15, 1);
+    assert_eq!(registers.rax, 11);
+    assert_eq!(registers.rip, 17);
+    assert_eq!(registers.rsp, 20);
+    assert_eq!(registers.gs, 27);
+
+    assert_eq!(prstatus(&bytes, MachineKind::Arm), None);
+    let bytes = build(MachineKind::X86_64, true, endianness, &desc[..300]);
+    assert_eq!(prstatus(&bytes, MachineKind::X86_64), None);
+
+    // 32-bit: registers at 72, 32 of them on RISC-V
+    let endianness = Endianness::Big;
+    let mut desc = vec![0; 72 + 32 * 4 + 4];
+    desc[24..28].copy_from_slice(&endianness.u32_to_bytes(42));
+    for index in 0..32 {
+        let offset = 72 + index * 4;
+        desc[offset..offset + 4].copy_from_slice(&endianness.u32_to_bytes(index as u32 + 1));
+    }
+
+    let bytes = build(MachineKind::RiscV, false, endianness, &desc);
+    let status = prstatus(&bytes, MachineKind::RiscV).unwrap();
+    assert_eq!(status.pid, 42);
+    let Registers::RiscV(registers) = status.registers else {
+        panic!("expected RISC-V registers");
+    };
+    assert_eq!(registers.pc, 1);
+    assert_eq!(registers.sp, 3);
+    assert_eq!(registers.a0, 11);
+    assert_eq!(registers.t6, 32);
+}
+
+#[test]
+fn auxv() {
+    for (is_64bit, endianness) in [(true, Endianness::Little), (false, Endianness::Big)] {
+        let entries: [(u64, u64); 5] = [(3, 0x400040), (9, 0x401000), (0x1234, 5), (0, 0), (9, 1)];
+        let desc = entries
+            .into_iter()
+            .flat_map(|(kind, value)| [kind, value])
+            .flat_map(|word| match is_64bit {
+                true => endianness.u64_to_bytes(word).to_vec(),
+                false => endianness.u32_to_bytes(word.try_into().unwrap()).to_vec(),
+            })
+            .collect::<Vec<_>>();
+
+        let mut builder = ElfBuilder::new(ElfKind::Core, MachineKind::X86_64, is_64bit, endianness);
+        let section = builder.add_note_section("note", "CORE", 6, &desc).unwrap();
+        let filesz = u64::try_from(20 + desc.len()).unwrap();
+        builder
+            .add_segment(Segment {
+                section,
+                kind: SegmentKind::Note,
+                vaddr: 0,
+                paddr: 0,
+                filesz,
+                memsz: filesz,
+                flags: FlagSet::default(),
+                align: 4,
+                last_section: None,
+            })
+            .unwrap();
+
+        let bytes = builder.build_to_vec().unwrap();
+        let reader = ElfReader::new(&bytes).unwrap();
+        let segment = reader.segments().unwrap().of_kind(SegmentKind::Note).next();
+        let notes = segment.unwrap().notes().unwrap();
+        assert_eq!(
+            notes.auxv().collect::<Vec<_>>(),
+            [
+                (ElfValue::Known(AuxType::Phdr), 0x400040),
+                (ElfValue::Known(AuxType::Entry), 0x401000),
+                (ElfValue::Unknown(0x1234), 5),
+            ]
+        );
+        assert_eq!(notes.prstatus(MachineKind::X86_64), None);
+    }
+}
+
+#[test]
+fn read_at_vaddr() {
+    let bytes = include_bytes!("hello-world.bin");
+    let reader = ElfReader::new(bytes).unwrap();
+    let segments = reader.segments().unwrap();
+
+    for segment in segments.of_kind(SegmentKind::Load) {
+        let (vaddr, offset) = (segment.vaddr(), segment.offset());
+        let offset_usize = usize::try_from(offset).unwrap();
+        assert_eq!(reader.vaddr_to_offset(vaddr), Some(offset));
+        assert_eq!(
+            reader.read_u32_at_vaddr(vaddr),
+            reader.read_u32(offset_usize)
+        );
+        assert_eq!(
+            reader.read_u64_at_vaddr(vaddr),
+            reader.read_u64(offset_usize)
+        );
+
+        // the last four bytes of the file data can't be read as a u64
+        let last = vaddr + segment.filesz() - 4;
+        assert!(reader.read_u32_at_vaddr(last).is_some());
+        assert_eq!(reader.read_u64_at_vaddr(last), None);
+
+        // nor can the zeroed bytes past it, such as .bss
+        let end = vaddr + segment.filesz();
+        if segment.memsz() > segment.filesz() {
+            assert_eq!(reader.vaddr_to_offset(end), None);
+            assert_eq!(reader.read_u32_at_vaddr(end), None);
+        }
+    }
+
+    assert_eq!(reader.vaddr_to_offset(0), None);
+    assert_eq!(reader.read_u64_at_vaddr(u64::MAX - 2), None);
+
+    // the file offset of an address can overflow when p_offset is near the end of the range
+    let mut bytes = bytes.to_vec();
+    let p_offset = 0x40 + 56 + 8;
+    bytes[p_offset..p_offset + 8].copy_from_slice(&(u64::MAX - 2).to_le_bytes());
+    let reader = ElfReader::new(&bytes).unwrap();
+    assert_eq!(reader.vaddr_to_offset(0x200010), None);
+    assert_eq!(reader.read_u32_at_vaddr(0x200010), None);
+}
+
+#[test]
+fn segments_check() {
+    let file = include_bytes!("hello-world.bin");
+    let reader = ElfReader::new(file).unwrap();
+    assert_eq!(reader.segments().unwrap().check(), Ok(()));
+
+    // the TLS segment of the fixture is outside of its only LOAD segment
+    let reader = ElfReader::new(include_bytes!("nonsense.bin")).unwrap();
+    assert_eq!(
+        reader.segments().unwrap().check(),
+        Err(ParseError::InvalidSegment {
+            index: 1,
+            field: "p_vaddr"
+        })
+    );
+
+    // patches a field of a program header, given as (index, offset in the header)
+    let check = |field: (usize, usize), value: u64| {
+        let mut bytes = file.to_vec();
+        let offset = 64 + 56 * field.0 + field.1;
+        bytes[offset..offset + 8].copy_from_slice(&value.to_le_bytes());
+
+        let reader = ElfReader::new(&bytes).unwrap();
+        let result = reader.segments().unwrap().check();
+        result
+    };
+    let invalid = |index, field| Err(ParseError::InvalidSegment { index, field });
+
+    // the PHDR segment is shorter than the program header table
+    assert_eq!(check((0, 32), 0x150), invalid(0, "p_filesz"));
+    // the second LOAD segment starts inside or before the first one
+    assert_eq!(
+        check((2, 16), 0x200100),
+        Err(ParseError::OverlappingSegments)
+    );
+    assert_eq!(check((2, 16), 0x100000), invalid(2, "p_vaddr"));
+    assert_eq!(check((3, 40), u64::MAX), invalid(3, "p_memsz"));
+    // the TLS initialization image is between the LOAD segments
+    assert_eq!(check((4, 32), 0x10), invalid(4, "p_vaddr"));
+}
+
+#[test]
+fn segment_alignment() {
+    let file = include_bytes!("hello-world.bin");
+    let reader = ElfReader::new(file).unwrap();
+    assert!(reader
+        .segments()
+        .unwrap()
+        .into_iter()
+        .all(|segment| segment.is_well_aligned()));
+
+    // patches fields of the second LOAD segment, given as (offset in the header, value)
+    let aligned = |fields: &[(usize, u64)]| {
+        let mut bytes = file.to_vec();
+        for &(offset, value) in fields {
+            let offset = 64 + 56 * 2 + offset;
+            bytes[offset..offset + 8].copy_from_slice(&value.to_le_bytes());
+        }
+
+        let reader = ElfReader::new(&bytes).unwrap();
+        let segment = reader.segments().unwrap().get(2).unwrap();
+        segment.is_well_aligned()
+    };
+
+    // p_vaddr is no longer congruent to p_offset
+    assert!(!aligned(&[(16, 0x201ca5)]));
+    // p_align is not a power of two
+    assert!(!aligned(&[(48, 0x3000)]));
+    // no alignment constraint
+    assert!(aligned(&[(16, 0x201ca5), (48, 0)]));
+    assert!(aligned(&[(16, 0x201ca5), (48, 1)]));
+}
+
+#[test]
+fn eh_frame_hdr() {
+    let file = include_bytes!("hello-world.bin");
+    let reader = ElfReader::new(file).unwrap();
+    let header = reader.eh_frame_hdr().unwrap().unwrap();
+    assert_eq!(header.version(), 1);
+    assert_eq!(
+        (
+            header.eh_frame_ptr_encoding(),
+            header.fde_count_encoding(),
+            header.table_encoding()
+        ),
+        (0x1b, 0x03, 0x3b)
+    );
+    // the start of .eh_frame
+    assert_eq!(header.eh_frame_ptr(), 0x200908);
+    assert_eq!(header.fde_count(), 20);
+    assert_eq!(header.get(0), Some((0x201ca4, 0x200920)));
+    assert_eq!(header.get(19), Some((0x202b08, 0x200c8c)));
+    assert_eq!(header.get(20), None);
+    assert!(header
+        .entries()
+        .zip(header.entries().skip(1))
+        .all(|(a, b)| a.0 < b.0));
+
+    assert_eq!(header.lookup(0x201ca4), Some(0x200920));
+    assert_eq!(header.lookup(0x201cb5), Some(0x200920));
+    assert_eq!(header.lookup(0x2027e0), Some(0x2009b8));
+    assert_eq!(header.lookup(0x300000), Some(0x200c8c));
+    assert_eq!(header.lookup(0x201ca3), None);
+
+    // the section gives the same result as the segment
+    let section = reader.sections().unwrap().get(2).unwrap();
+    let from_section = section.eh_frame_hdr().unwrap();
+    assert!(from_section.entries().eq(header.entries()));
+
+    // absolute big-endian values, with the count as ULEB128
+    let data = [
+        &[1, 0x03, 0x01, 0x02][..],
+        &0x8000u32.to_be_bytes(),
+        &[0x82, 0x01], // 130
+        &0x1000u16.to_be_bytes(),
+        &0x8010u16.to_be_bytes(),
+    ]
+    .concat();
+    let mut builder = ElfBuilder::new(
+        ElfKind::Relocatable,
+        MachineKind::Sparc,
+        false,
+        Endianness::Big,
+    );
+    let name = builder.add_string(".eh_frame_hdr");
+    builder
+        .add_section(Section {
+            data: Cow::Owned(data.clone()),
+            name,
+            kind: SectionKind::Progbits,
+            flags: SectionFlag::Alloc.into(),
+            extra_flags: 0,
+            vaddr: 0,
+            info: 0,
+            entsize: 0,
+            alignment: 4,
+            size: None,
+            link: None,
+        })
+        .unwrap();
+    let bytes = builder.build_to_vec().unwrap();
+    let reader = ElfReader::new(&bytes).unwrap();
+    // the count doesn't fit in the section
+    assert_eq!(
+        reader.eh_frame_hdr().unwrap_err(),
+        ParseError::InvalidValue("fde_count")
+    );
+
+    let mut bytes = bytes.clone();
+    let start = bytes
+        .windows(data.len())
+        .position(|window| window == data)
+        .unwrap();
+    // 1, padded to the same length
+    bytes[start + 8..start + 10].copy_from_slice(&[0x81, 0x00]);
+    let reader = ElfReader::new(&bytes).unwrap();
+    let header = reader.eh_frame_hdr().unwrap().unwrap();
+    assert_eq!(header.eh_frame_ptr(), 0x8000);
+    assert_eq!(header.entries().collect::<Vec<_>>(), [(0x1000, 0x8010)]);
+    assert_eq!(header.lookup(0xffff_ffff), Some(0x8010));
+
+    // an unsupported table encoding
+    bytes[start + 3] = 0x01;
+    let reader = ElfReader::new(&bytes).unwrap();
+    assert_eq!(
+        reader.eh_frame_hdr().unwrap_err(),
+        ParseError::InvalidValue("table_enc")
+    );
+
+    let reader = ElfReader::new(include_bytes!("nonsense.bin")).unwrap();
+    assert!(reader.eh_frame_hdr().unwrap().is_none());
+}
+
+#[test]
+fn segments_covering() {
+    let bytes = include_bytes!("hello-world.bin");
+    let reader = ElfReader::new(bytes).unwrap();
+    let segments = reader.segments().unwrap();
+
+    for segment in segments.of_kind(SegmentKind::Load) {
+        let covering = segments.covering(segment.vaddr()).unwrap();
+        assert_eq!(covering.vaddr(), segment.vaddr());
+        assert_eq!(covering.flags(), segment.flags());
+
+        // the zeroed bytes past the file data, such as .bss, are covered but not backed by the file
+        let end = segment.vaddr() + segment.filesz();
+        if segment.memsz() > segment.filesz() {
+            assert_eq!(segments.covering(end).unwrap().vaddr(), segment.vaddr());
+            assert_eq!(reader.vaddr_to_offset(end), None);
+        }
+    }
+    assert!(segments.covering(0).is_none());
+
+    let mut builder = ElfBuilder::new(
+        ElfKind::Executable,
+        MachineKind::X86_64,
+        true,
+        Endianness::Little,
+    );
+    let name = builder.add_string(".text");
+    let text = builder
+        .add_section(Section {
+            data: Cow::Owned(vec![0xc3; 0x20]),
+            name,
+            kind: SectionKind::Progbits,
+            flags: SectionFlag::Alloc | SectionFlag::ExecInstr,
+            extra_flags: 0,
+            vaddr: 0x1000,
+            info: 0,
+            entsize: 0,
+            alignment: 1,
+            size: None,
+            link: None,
+        })
+        .unwrap();
+    // overlapping segments: the one with the lowest address, and then the first one, is used
+    for (vaddr, flags) in [
+        (0x1008, SegmentFlag::Read | SegmentFlag::Write),
+        (0x1000, SegmentFlag::Read | SegmentFlag::Execute),
+        (0x1000, SegmentFlag::Read.into()),
+    ] {
+        builder
+            .add_segment(Segment {
+                section: text,
+                kind: SegmentKind::Load,
+                vaddr,
+                paddr: vaddr,
+                filesz: 0x18,
+                memsz: 0x18,
+                flags,
+                align: 1,
+                last_section: None,
+            })
+            .unwrap();
+    }
+    let bytes = builder.build_to_vec().unwrap();
+    let reader = ElfReader::new(&bytes).unwrap();
+    let segments = reader.segments().unwrap();
+
+    let covering = segments.covering(0x1010).unwrap();
+    assert_eq!(
+        covering.flags(),
+        ElfValue::Known(SegmentFlag::Read | SegmentFlag::Execute)
+    );
+    assert_eq!(segments.covering(0x101c).unwrap().vaddr(), 0x1008);
+    assert!(segments.covering(0x1020).is_none());
+}
+
+#[test]
+fn symbol_versions() {
+    let mut builder = ElfBuilder::new(
+        ElfKind::Dynamic,
+        MachineKind::X86_64,
+        true,
+        Endianness::Little,
+    );
+
+    let name = builder.add_string(".text");
+    let text = builder
+        .add_section(Section {
+            data: Cow::Borrowed(&[0xc3]),
+            name,
+            kind: SectionKind::Progbits,
+            flags: SectionFlag::Alloc | SectionFlag::ExecInstr,
+            extra_flags: 0,
+            vaddr: 0x1000,
+            info: 0,
+            entsize: 0,
+            alignment: 1,
+            size: None,
+            link: None,
+        })
+        .unwrap();
+
+    // symbols 1 to 4, all global so that they stay in this order
+    for name in ["foo", "bar", "puts", "baz"] {
+        builder
+            .add_dynamic_symbol(name, 0x1000, 1, true, SymbolKind::Func, text)
+            .unwrap();
+    }
+
+    let mut string = |string: &str| u32::try_from(builder.add_dynamic_string(string)).unwrap();
+    let (libfoo, vers_1, vers_2) = (string("libfoo.so"), string("VERS_1"), string("VERS_2"));
+    let (libempty, libc, glibc) = (
+        string("libempty.so"),
+        string("libc.so.6"),
+        string("GLIBC_2.2.5"),
+    );
+
+    let mut verdef = Vec::new();
+    for (flags, index, name, next) in [
+        (1u16, 1u16, libfoo, 28u32),
+        (0, 2, vers_1, 28),
+        (0, 3, vers_2, 0),
+    ] {
+        for half in [1, flags, index, 1] {
+            verdef.extend_from_slice(&half.to_le_bytes());
+        }
+        for word in [0, 20, next, name, 0] {
+            verdef.extend_from_slice(&word.to_le_bytes());
+        }
+    }
+
+    let mut verneed = Vec::new();
+    // a file without any versions is skipped
+    for (count, file, aux, next) in [(0u16, libempty, 0u32, 16u32), (1, libc, 16, 0)] {
+        for half in [1, count] {
+            verneed.extend_from_slice(&half.to_le_bytes());
+        }
+        for word in [file, aux, next] {
+            verneed.extend_from_slice(&word.to_le_bytes());
+        }
+    }
+    verneed.extend_from_slice(&0x0d69_6991u32.to_le_bytes());
+    verneed.extend_from_slice(&0u16.to_le_bytes());
+    verneed.extend_from_slice(&4u16.to_le_bytes());
+    verneed.extend_from_slice(&glibc.to_le_bytes());
+    verneed.extend_from_slice(&0u32.to_le_bytes());
+
+    let versym = [0u16, 2, 0x8003, 4, 1]
+        .iter()
+        .flat_map(|index| index.to_le_bytes())
+        .collect::<Vec<_>>();
+
+    let dynsym = builder.dynamic_symbol_table();
+    let dynstr = builder.dynamic_string_table();
+    for (name, data, kind, link, entsize) in [
+        (".gnu.version", versym, SectionKind::GnuVerSym, dynsym, 2),
+        (".gnu.version_d", verdef, SectionKind::GnuVerDef, dynstr, 0),
+        (
+            ".gnu.version_r",
+            verneed,
+            SectionKind::GnuVerNeed,
+            dynstr,
+            0,
+        ),
+    ] {
+        let name = builder.add_string(name);
+        builder
+            .add_section(Section {
+                data: Cow::Owned(data),
+                name,
+                kind,
+                flags: SectionFlag::Alloc.into(),
+                extra_flags: 0,
+                vaddr: 0,
+                info: 0,
+                entsize,
+                alignment: 2,
+                size: None,
+                link: Some(link),
+            })
+            .unwrap();
+    }
+
+    builder.allow_unusual();
+    let bytes = builder.build_to_vec().unwrap();
+    let reader = ElfReader::new(&bytes).unwrap();
+    let sections = reader.sections().unwrap();
+
+    let definitions = sections.get(3).unwrap().version_definitions().unwrap();
+    let strings = definitions.strings().unwrap();
+    let names = definitions
+        .into_iter()
+        .map(|definition| {
+            let name = strings.get_str(definition.name().into()).unwrap().unwrap();
+            (definition.index(), definition.flags(), name)
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(
+        names,
+        [(1, 1, "libfoo.so"), (2, 0, "VERS_1"), (3, 0, "VERS_2")]
+    );
+
+    let requirements = sections.get(4).unwrap().version_requirements().unwrap();
+    let strings = requirements.strings().unwrap();
+    let requirement = requirements.into_iter().collect::<Vec<_>>();
+    assert_eq!(requirement.len(), 1);
+    assert_eq!(
+        strings.get_str(requirement[0].file().into()),
+        Some(Ok("libc.so.6"))
+    );
+    assert_eq!(requirement[0].index(), 4);
+    assert_eq!(requirement[0].hash(), 0x0d69_6991);
+
+    let symbols = sections
+        .of_kind(SectionKind::DynSym)
+        .next()
+        .unwrap()
+        .symbols()
+        .unwrap();
+    let indices = (0..6)
+        .map(|index| symbols.version_index(index))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        indices,
+        [Some(0), Some(2), Some(0x8003), Some(4), Some(1), None]
+    );
+    let versions = (0..5)
+        .map(|index| symbols.version(index))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        versions,
+        [
+            None,
+            Some("VERS_1"),
+            Some("VERS_2"),
+            Some("GLIBC_2.2.5"),
+            None
+        ]
+    );
+}